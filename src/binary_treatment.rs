@@ -1,4 +1,4 @@
-use std::ops::{AddAssign, Index};
+use std::ops::Index;
 
 use num_traits::Float;
 
@@ -9,6 +9,12 @@ pub struct BinaryTreatment<F> {
     pub sample_size: Option<F>,
 }
 
+impl<F> Default for BinaryTreatment<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<F> BinaryTreatment<F> {
     pub fn new() -> Self {
         Self {
@@ -29,8 +35,7 @@ impl<F> BinaryTreatment<F> {
         let mut sum_output_treat = F::zero();
         let mut sum_treatment = F::zero();
         let mut n_treat = F::zero();
-        let mut pos = 0;
-        for treat in treatment {
+        for (pos, treat) in treatment.into_iter().enumerate() {
             if *treat == F::zero() {
                 sum_output_non_treat = sum_output_non_treat + output[pos];
                 n_non_treat = n_non_treat + F::one();
@@ -39,7 +44,6 @@ impl<F> BinaryTreatment<F> {
                 n_treat = n_treat + F::one();
                 sum_treatment = sum_treatment + F::one();
             }
-            pos += 1;
         }
         if (n_non_treat == F::zero()) | (n_treat == F::zero()) {
             return false; // TODO: error handling
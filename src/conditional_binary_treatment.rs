@@ -3,9 +3,9 @@ use std::{
     ops::{AddAssign, DivAssign, Index},
 };
 
-use num_traits::{zero, Float};
+use num_traits::Float;
 
-use crate::binary_treatment::{self, BinaryTreatment};
+use crate::binary_treatment::BinaryTreatment;
 
 #[derive(Debug)]
 pub struct ConditionalBinaryTreatment<F> {
@@ -14,6 +14,12 @@ pub struct ConditionalBinaryTreatment<F> {
     pub sample_size: Option<F>,
 }
 
+impl<F> Default for ConditionalBinaryTreatment<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<F> ConditionalBinaryTreatment<F> {
     pub fn new() -> Self {
         Self {
@@ -31,7 +37,7 @@ impl<F> ConditionalBinaryTreatment<F> {
     {
         let mut candidate = F::zero();
         let mut sample_size = F::zero();
-        for (i, g) in group.iter().enumerate() {
+        for (i, _) in group.iter().enumerate() {
             let mut binary_treatment = BinaryTreatment::new();
             if binary_treatment.fit(&treatment[i], &output[i]) {
                 let (cce_i, n_sample) = (
@@ -1,11 +1,92 @@
+//! Probability distributions and special functions.
+//!
+//! Everything is generic over `T: Float + FloatConst`. The series, continued
+//! fractions and iterations stop at the machine epsilon ε of `T`, so that the
+//! accuracy follows the precision: away from underflow, the special
+//! functions, and the densities, CDFs, survival functions and quantiles of
+//! the distributions, target relative errors below 64 ε, about 1e-14 in `f64`
+//! and 1e-5 in `f32`. The exceptions state their own accuracy: the
+//! non-central t-distribution is accurate in absolute terms, the
+//! distributions computed by numerical integration to ε<sup>2/3</sup>, and
+//! the exact Kolmogorov distribution is computed in `f64` whatever `T`. The
+//! random draws are computed in `f64` and rounded to `T`.
+
+mod archimedean;
 mod beta;
+mod binomial;
+mod cauchy;
+mod continuous;
+mod copula;
+mod discrete;
+mod dunnett;
+mod ecdf;
+mod fit;
 mod function;
+mod gamma;
+mod generalized_extreme_value;
+mod generalized_pareto;
+mod genz;
+mod gumbel;
+mod hypergeometric;
+mod kernel_density;
+mod kolmogorov;
+mod laplace;
+mod linalg;
+mod location_scale;
+mod logistic;
+mod lognormal;
 mod moment;
+mod multivariate_normal;
+mod multivariate_t;
+mod negative_binomial;
 mod normal;
+mod numeric;
+mod pareto;
+mod peaks_over_threshold;
+mod poisson;
+mod quadratic_edf;
+mod sample;
 mod student;
+mod studentized_range;
+mod vectorized;
+mod weibull;
 
+pub use archimedean::*;
 pub use beta::*;
+pub use binomial::*;
+pub use cauchy::*;
+pub use continuous::*;
+pub use copula::*;
+pub use discrete::*;
+pub use dunnett::*;
+pub use ecdf::*;
+pub use fit::*;
 pub use function::*;
-pub use moment::*;
+pub use gamma::*;
+pub use generalized_extreme_value::*;
+pub use generalized_pareto::*;
+pub use genz::*;
+pub use gumbel::*;
+pub use hypergeometric::*;
+pub use kernel_density::*;
+pub use kolmogorov::*;
+pub use laplace::*;
+pub(crate) use linalg::*;
+pub use location_scale::*;
+pub use logistic::*;
+pub use lognormal::*;
+pub(crate) use moment::*;
+pub use multivariate_normal::*;
+pub use multivariate_t::*;
+pub use negative_binomial::*;
 pub use normal::*;
+pub(crate) use numeric::*;
+pub use pareto::*;
+pub use peaks_over_threshold::*;
+pub use poisson::*;
+pub use quadratic_edf::*;
+pub use sample::*;
 pub use student::*;
+pub use studentized_range::*;
+pub use vectorized::*;
+pub use weibull::*;
@@ -0,0 +1,358 @@
+use ndarray::{arr1, Array1, ArrayBase, Data, Ix1, Ix2};
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{
+    find_root, fit_output, from_f64, integrate, integration_tol, kendall_tau, maximize_numerical,
+    open_unit, pseudo_observations, to_f64, Copula, FitCopula, FitOutput, Sample,
+};
+
+/// Checks that `u` = (u, v) is in (0, 1)<sup>2</sup>, giving the value of
+/// the log-density otherwise.
+fn check_unit_square<T: Float, S: Data<Elem = T>>(u: &ArrayBase<S, Ix1>) -> Result<(T, T), T> {
+    if u.len() != 2 {
+        return Err(T::nan());
+    }
+    let inside = |u: T| u > T::zero() && u < T::one();
+    if inside(u[0]) && inside(u[1]) {
+        Ok((u[0], u[1]))
+    } else {
+        Err(T::neg_infinity())
+    }
+}
+
+/// Maximum pseudo-likelihood estimate of the parameter θ of a bivariate
+/// Archimedean copula from `start`, with derivatives by finite differences.
+fn fit_archimedean<T, S, C>(
+    data: &ArrayBase<S, Ix2>,
+    start: impl Fn(T) -> T,
+    new: impl Fn(T) -> Option<C>,
+) -> Option<FitOutput<C, T>>
+where
+    T: Float,
+    S: Data<Elem = T>,
+    C: Copula<T>,
+{
+    if data.ncols() != 2 {
+        return None;
+    }
+    let u = pseudo_observations(data)?;
+    let n = u.nrows();
+    let tau = kendall_tau(&u.column(0), &u.column(1));
+    let loglik = |theta: &Array1<T>| {
+        let copula = new(theta[0])?;
+        let value = u
+            .rows()
+            .into_iter()
+            .fold(T::zero(), |acc, row| acc + copula.ln_pdf(&row));
+        value.is_finite().then_some(value)
+    };
+    let (theta, loglik, hessian) = maximize_numerical(arr1(&[start(tau)]), loglik)?;
+    fit_output(new(theta[0])?, theta, &hessian, loglik, n)
+}
+
+/// Clayton copula with parameter θ = `theta` > 0, of CDF C(u, v) =
+/// (u<sup>-θ</sup> + v<sup>-θ</sup> - 1)<sup>-1/θ</sup> and Kendall's τ =
+/// θ/(θ + 2). It has lower tail dependence 2<sup>-1/θ</sup>: joint small
+/// values cluster.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clayton<T> {
+    pub theta: T,
+}
+
+impl<T: Float> Clayton<T> {
+    /// Returns `None` unless `theta` is positive and finite.
+    pub fn new(theta: T) -> Option<Self> {
+        if theta.is_finite() && theta > T::zero() {
+            Some(Self { theta })
+        } else {
+            None
+        }
+    }
+
+    /// Kendall's τ = θ/(θ + 2).
+    pub fn kendall_tau(&self) -> T {
+        self.theta / (self.theta + T::one() + T::one())
+    }
+}
+
+impl<T: Float> Copula<T> for Clayton<T> {
+    fn dim(&self) -> usize {
+        2
+    }
+
+    fn ln_pdf<S: Data<Elem = T>>(&self, u: &ArrayBase<S, Ix1>) -> T {
+        let (u, v) = match check_unit_square(u) {
+            Ok(uv) => uv,
+            Err(value) => return value,
+        };
+        let one = T::one();
+        let theta = self.theta;
+        let (ln_u, ln_v) = (u.ln(), v.ln());
+        // u^-θ + v^-θ - 1 without cancellation.
+        let s = (-theta * ln_u).exp_m1() + (-theta * ln_v).exp();
+        theta.ln_1p() - (one + theta) * (ln_u + ln_v) - (one + one + one / theta) * s.ln()
+    }
+}
+
+/// Inversion of the conditional CDF of V given U.
+impl<T: Float> Sample<Array1<T>> for Clayton<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Array1<T> {
+        let (u, w) = (open_unit(rng), open_unit(rng));
+        let theta = to_f64(self.theta);
+        // v^-θ = u^-θ (w^(-θ/(1 + θ)) - 1) + 1.
+        let a = (-theta / (1. + theta) * w.ln()).exp_m1();
+        let ln_v = -((-theta * u.ln()).exp() * a).ln_1p() / theta;
+        arr1(&[from_f64(u), from_f64(ln_v.exp())])
+    }
+}
+
+/// Estimates `theta`, starting from the inversion θ = 2τ/(1 - τ) of Kendall's
+/// τ. Requires positive dependence.
+impl<T: Float> FitCopula<T> for Clayton<T> {
+    fn fit<S: Data<Elem = T>>(data: &ArrayBase<S, Ix2>) -> Option<FitOutput<Self, T>> {
+        let one = T::one();
+        let start = |tau: T| {
+            let tau = tau.max(T::from(0.05).unwrap());
+            (tau + tau) / (one - tau)
+        };
+        fit_archimedean(data, start, Self::new)
+    }
+}
+
+/// Gumbel (Gumbel-Hougaard) copula with parameter θ = `theta` ≥ 1, of CDF
+/// C(u, v) = exp(-((-ln u)<sup>θ</sup> + (-ln v)<sup>θ</sup>)<sup>1/θ</sup>)
+/// and Kendall's τ = 1 - 1/θ. It has upper tail dependence 2 -
+/// 2<sup>1/θ</sup>: joint large values cluster. θ = 1 is independence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GumbelCopula<T> {
+    pub theta: T,
+}
+
+impl<T: Float> GumbelCopula<T> {
+    /// Returns `None` unless `theta` is at least 1 and finite.
+    pub fn new(theta: T) -> Option<Self> {
+        if theta.is_finite() && theta >= T::one() {
+            Some(Self { theta })
+        } else {
+            None
+        }
+    }
+
+    /// Kendall's τ = 1 - 1/θ.
+    pub fn kendall_tau(&self) -> T {
+        T::one() - self.theta.recip()
+    }
+}
+
+impl<T: Float> Copula<T> for GumbelCopula<T> {
+    fn dim(&self) -> usize {
+        2
+    }
+
+    fn ln_pdf<S: Data<Elem = T>>(&self, u: &ArrayBase<S, Ix1>) -> T {
+        let (u, v) = match check_unit_square(u) {
+            Ok(uv) => uv,
+            Err(value) => return value,
+        };
+        let one = T::one();
+        let theta = self.theta;
+        let (x, y) = (-u.ln(), -v.ln());
+        let (ln_x, ln_y) = (x.ln(), y.ln());
+        // ln s with s = x^θ + y^θ, factoring the largest term.
+        let (big, small) = if ln_x > ln_y {
+            (ln_x, ln_y)
+        } else {
+            (ln_y, ln_x)
+        };
+        let ln_s = theta * big + (theta * (small - big)).exp().ln_1p();
+        let a = (ln_s / theta).exp();
+        -a + x
+            + y
+            + (theta - one) * (ln_x + ln_y)
+            + (one / theta - one - one) * ln_s
+            + (a + theta - one).ln()
+    }
+}
+
+/// Marshall-Olkin algorithm: U<sub>i</sub> = exp(-(E<sub>i</sub>/V)<sup>1/θ</sup>)
+/// with E<sub>i</sub> standard exponential and V positive stable of index
+/// 1/θ, drawn by the method of Chambers, Mallows and Stuck (1976).
+impl<T: Float> Sample<Array1<T>> for GumbelCopula<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Array1<T> {
+        let alpha = 1. / to_f64(self.theta);
+        let angle = std::f64::consts::PI * open_unit(rng);
+        let e = -open_unit(rng).ln();
+        let stable = (alpha * angle).sin() / angle.sin().powf(1. / alpha)
+            * ((angle - alpha * angle).sin() / e).powf((1. - alpha) / alpha);
+        Array1::from_shape_simple_fn(2, || {
+            let e = -open_unit(rng).ln();
+            from_f64((-(e / stable).powf(alpha)).exp())
+        })
+    }
+}
+
+/// Estimates `theta`, starting from the inversion θ = 1/(1 - τ) of Kendall's
+/// τ. Requires positive dependence.
+impl<T: Float> FitCopula<T> for GumbelCopula<T> {
+    fn fit<S: Data<Elem = T>>(data: &ArrayBase<S, Ix2>) -> Option<FitOutput<Self, T>> {
+        let start = |tau: T| (T::one() - tau.max(T::from(0.05).unwrap())).recip();
+        fit_archimedean(data, start, Self::new)
+    }
+}
+
+/// Frank copula with parameter θ = `theta` ≠ 0, of CDF C(u, v) = -ln(1 +
+/// (e<sup>-θu</sup> - 1)(e<sup>-θv</sup> - 1)/(e<sup>-θ</sup> - 1))/θ. It
+/// allows negative dependence (θ < 0) and has no tail dependence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frank<T> {
+    pub theta: T,
+}
+
+impl<T: Float + FloatConst> Frank<T> {
+    /// Returns `None` unless `theta` is non-zero and finite.
+    pub fn new(theta: T) -> Option<Self> {
+        if theta.is_finite() && theta != T::zero() {
+            Some(Self { theta })
+        } else {
+            None
+        }
+    }
+
+    /// Kendall's τ = 1 - 4(1 - D<sub>1</sub>(θ))/θ, with the Debye function
+    /// D<sub>1</sub>(θ) = ∫<sub>0</sub><sup>θ</sup> t/(e<sup>t</sup> - 1) dt
+    /// / θ computed by quadrature.
+    pub fn kendall_tau(&self) -> T {
+        let one = T::one();
+        // τ is odd in θ.
+        let theta = self.theta.abs();
+        let integrand = |t: T| if t == T::zero() { one } else { t / t.exp_m1() };
+        let points = [T::zero(), theta.min(T::from(8.).unwrap()), theta];
+        let (integral, _) = integrate(integrand, &points, T::zero(), integration_tol());
+        let debye = integral / theta;
+        let tau = one - T::from(4.).unwrap() * (one - debye) / theta;
+        if self.theta < T::zero() {
+            -tau
+        } else {
+            tau
+        }
+    }
+}
+
+impl<T: Float + FloatConst> Copula<T> for Frank<T> {
+    fn dim(&self) -> usize {
+        2
+    }
+
+    fn ln_pdf<S: Data<Elem = T>>(&self, u: &ArrayBase<S, Ix1>) -> T {
+        let (u, v) = match check_unit_square(u) {
+            Ok(uv) => uv,
+            Err(value) => return value,
+        };
+        let theta = self.theta;
+        let a = -(-theta).exp_m1();
+        // (1 - e^-θ) - (1 - e^-θu)(1 - e^-θv).
+        let d = a - (-theta * u).exp_m1() * (-theta * v).exp_m1();
+        (theta * a).ln() - theta * (u + v) - (d * d).ln()
+    }
+}
+
+/// Inversion of the conditional CDF of V given U.
+impl<T: Float + FloatConst> Sample<Array1<T>> for Frank<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Array1<T> {
+        let (u, w) = (open_unit(rng), open_unit(rng));
+        let theta = to_f64(self.theta);
+        let v = -(w * (-theta).exp_m1() / (w + (1. - w) * (-theta * u).exp())).ln_1p() / theta;
+        arr1(&[from_f64(u), from_f64(v)])
+    }
+}
+
+/// Estimates `theta`, starting from the inversion of Kendall's τ.
+impl<T: Float + FloatConst> FitCopula<T> for Frank<T> {
+    fn fit<S: Data<Elem = T>>(data: &ArrayBase<S, Ix2>) -> Option<FitOutput<Self, T>> {
+        let start = |tau: T| {
+            let target = tau.abs().max(T::from(0.01).unwrap());
+            let f = |theta: T| Self::new(theta).unwrap().kendall_tau() - target;
+            let mut hi = T::one();
+            while f(hi) < T::zero() && hi < T::from(1e3).unwrap() {
+                hi = hi + hi;
+            }
+            let theta =
+                find_root(f, T::from(1e-3).unwrap(), hi, T::from(1e-6).unwrap()).unwrap_or(hi);
+            if tau < T::zero() {
+                -theta
+            } else {
+                theta
+            }
+        };
+        fit_archimedean(data, start, Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::test_util::{assert_fit_tol, earnings_pairs, rel};
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn archimedean_values() {
+        let point = arr1(&[0.3, 0.8]);
+        let clayton = Clayton::new(2.).unwrap();
+        assert!(rel(clayton.ln_pdf(&point), -0.76336572899299053) < 1e-13);
+        assert_eq!(clayton.kendall_tau(), 0.5);
+        let gumbel = GumbelCopula::new(1.5).unwrap();
+        assert!(rel(gumbel.ln_pdf(&point), -0.40145082021222521) < 1e-13);
+        let frank = Frank::new(-3.).unwrap();
+        assert!(rel(frank.ln_pdf(&point), 0.31163391157908468) < 1e-13);
+        let frank = Frank::new(3.).unwrap();
+        assert!(rel(frank.kendall_tau(), 0.30724695943072378) < 1e-12);
+        assert_eq!(clayton.ln_pdf(&arr1(&[0., 0.5])), f64::NEG_INFINITY);
+        assert!(Clayton::new(-1.).is_none());
+        assert!(GumbelCopula::new(0.5).is_none());
+        assert!(Frank::new(0.).is_none());
+        // Sample Kendall's τ against the theoretical value.
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut check = |copula: &dyn Fn(&mut StdRng) -> Array1<f64>, tau: f64| {
+            let draws = (0..1000).map(|_| copula(&mut rng)).collect::<Vec<_>>();
+            let u = Array1::from_iter(draws.iter().map(|d| d[0]));
+            let v = Array1::from_iter(draws.iter().map(|d| d[1]));
+            let sample_tau: f64 = kendall_tau(&u, &v);
+            assert!((sample_tau - tau).abs() < 0.05, "{sample_tau} vs {tau}");
+        };
+        check(&|rng| clayton.sample(rng), 0.5);
+        check(&|rng| gumbel.sample(rng), gumbel.kendall_tau());
+        check(&|rng| frank.sample(rng), frank.kendall_tau());
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn archimedean_fits() {
+        // The Hessians are computed by finite differences.
+        let data = earnings_pairs();
+        assert_fit_tol(
+            &Clayton::fit(&data).unwrap(),
+            &[0.14007775154051205],
+            &[0.075029072250204007],
+            2.0022917989317594,
+            1e-6,
+        );
+        assert_fit_tol(
+            &GumbelCopula::fit(&data).unwrap(),
+            &[1.1089808112363064],
+            &[0.041732247591171699],
+            4.9830984966755107,
+            1e-6,
+        );
+        assert_fit_tol(
+            &Frank::fit(&data).unwrap(),
+            &[0.94531361253899102],
+            &[0.335992656135443],
+            3.9592229421457794,
+            1e-6,
+        );
+        assert!(Clayton::fit(&data.slice(ndarray::s![.., ..1])).is_none());
+    }
+}
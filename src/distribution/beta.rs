@@ -1,45 +1,153 @@
-use std::f64;
+use ndarray::{arr1, arr2};
+use num_traits::{Float, FloatConst};
+use rand::Rng;
 
-use super::beta as beta_function;
+use super::{
+    digamma, fit_output, from_f64, inc_beta_xy, ln_beta, maximize, standard_gamma, to_f64,
+    trigamma, Continuous, Fit, FitOutput, Sample,
+};
 
-/// Computes the probability density function of Beta distribution
-fn pdf_beta(x: f64, alpha: f64, beta: f64) -> f64 {
-    if x <= 0.0 || x >= 1.0 {
-        return 0.0;
+/// Beta distribution with shape parameters `alpha` and `beta`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Beta<T> {
+    pub alpha: T,
+    pub beta: T,
+}
+
+impl<T: Float> Beta<T> {
+    /// Returns `None` unless both shape parameters are positive and finite.
+    pub fn new(alpha: T, beta: T) -> Option<Self> {
+        let valid = |p: T| p.is_finite() && p > T::zero();
+        if valid(alpha) && valid(beta) {
+            Some(Self { alpha, beta })
+        } else {
+            None
+        }
+    }
+}
+
+/// Estimates (`alpha`, `beta`), starting from the method of moments. Requires
+/// values in (0, 1) that are not all equal.
+impl<T: Float + FloatConst> Fit<T> for Beta<T> {
+    fn fit<S>(sample: &S) -> Option<FitOutput<Self, T>>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let (zero, one) = (T::zero(), T::one());
+        let (mut n, mut sum, mut sum_sq) = (0, zero, zero);
+        let (mut sum_ln, mut sum_ln_1m) = (zero, zero);
+        for &x in sample {
+            if !(x > zero && x < one) {
+                return None;
+            }
+            n += 1;
+            sum = sum + x;
+            sum_sq = sum_sq + x * x;
+            sum_ln = sum_ln + x.ln();
+            sum_ln_1m = sum_ln_1m + (-x).ln_1p();
+        }
+        let nf = T::from(n).unwrap();
+        let mean = sum / nf;
+        let var = sum_sq / nf - mean * mean;
+        if var.is_nan() || var <= zero {
+            return None;
+        }
+        // α + β from the moments, replaced by 1 when the sample is more
+        // dispersed than any Beta distribution.
+        let total = (mean * (one - mean) / var - one).max(one);
+        let start = arr1(&[mean * total, (one - mean) * total]);
+        let (theta, loglik, hessian) = maximize(start, |p| {
+            let (a, b) = (p[0], p[1]);
+            Self::new(a, b)?;
+            let loglik = (a - one) * sum_ln + (b - one) * sum_ln_1m - nf * ln_beta(a, b)?;
+            let psi_ab = digamma(a + b)?;
+            let grad = arr1(&[
+                sum_ln - nf * (digamma(a)? - psi_ab),
+                sum_ln_1m - nf * (digamma(b)? - psi_ab),
+            ]);
+            let tri_ab = trigamma(a + b)?;
+            let hessian = arr2(&[
+                [-nf * (trigamma(a)? - tri_ab), nf * tri_ab],
+                [nf * tri_ab, -nf * (trigamma(b)? - tri_ab)],
+            ]);
+            Some((loglik, grad, hessian))
+        })?;
+        fit_output(Self::new(theta[0], theta[1])?, theta, &hessian, loglik, n)
     }
-    let beta_const = 1.0 / beta_function(alpha, beta);
-    beta_const * x.powf(alpha - 1.0) * (1.0 - x).powf(beta - 1.0)
 }
 
-/// Computes the cumulative distribution function of Beta distribution
-/// using numerical integration (Simpson's rule)
-pub(crate) fn cdf_beta(x: f64, alpha: f64, beta: f64) -> f64 {
-    if x <= 0.0 {
-        return 0.0;
+/// The CDF is the regularized incomplete Beta function P(X ≤ x) =
+/// I<sub>x</sub>(α, β).
+impl<T: Float + FloatConst> Continuous<T> for Beta<T> {
+    fn support(&self) -> (T, T) {
+        (T::zero(), T::one())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let (zero, one) = (T::zero(), T::one());
+        if x < zero || x > one {
+            return T::neg_infinity();
+        }
+        let ln_b = ln_beta(self.alpha, self.beta).unwrap();
+        let ln_x = if self.alpha == one {
+            zero
+        } else {
+            (self.alpha - one) * x.ln()
+        };
+        let ln_1mx = if self.beta == one {
+            zero
+        } else {
+            (self.beta - one) * (-x).ln_1p()
+        };
+        ln_x + ln_1mx - ln_b
+    }
+
+    fn cdf(&self, x: T) -> T {
+        inc_beta_xy(x, T::one() - x, self.alpha, self.beta).unwrap_or_else(T::nan)
     }
-    if x >= 1.0 {
-        return 1.0;
+
+    fn sf(&self, x: T) -> T {
+        inc_beta_xy(T::one() - x, x, self.beta, self.alpha).unwrap_or_else(T::nan)
     }
 
-    // Simpson's rule implementation
-    let n = 1000; // number of intervals (must be even)
-    let h = x / n as f64;
+    fn mean(&self) -> T {
+        self.alpha / (self.alpha + self.beta)
+    }
 
-    let mut sum = pdf_beta(0.0, alpha, beta) + pdf_beta(x, alpha, beta);
+    fn variance(&self) -> T {
+        let ab = self.alpha + self.beta;
+        self.alpha * self.beta / (ab * ab * (ab + T::one()))
+    }
+}
 
-    // Even terms
-    for i in (2..n).step_by(2) {
-        let xi = i as f64 * h;
-        sum += 2.0 * pdf_beta(xi, alpha, beta);
+/// Draws X / (X + Y) with X ∼ Gamma(`alpha`, 1) and Y ∼ Gamma(`beta`, 1).
+impl<T: Float> Sample<T> for Beta<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        let x = standard_gamma(rng, to_f64(self.alpha));
+        let y = standard_gamma(rng, to_f64(self.beta));
+        from_f64(x / (x + y))
     }
+}
 
-    // Odd terms
-    for i in (1..n).step_by(2) {
-        let xi = i as f64 * h;
-        sum += 4.0 * pdf_beta(xi, alpha, beta);
+/// Computes the probability density function of the Beta distribution with
+/// shape parameters `alpha` and `beta`, see [`Beta`]. Returns `None` unless
+/// both shape parameters are positive and finite and `x` is not NaN.
+pub fn pdf_beta<T: Float + FloatConst>(x: T, alpha: T, beta: T) -> Option<T> {
+    if x.is_nan() {
+        return None;
     }
+    Beta::new(alpha, beta).map(|dist| dist.pdf(x))
+}
 
-    (h / 3.0) * sum
+/// Computes the cumulative distribution function of the Beta distribution
+/// through the regularized incomplete Beta function
+/// [`inc_beta`](super::inc_beta). Returns `None` unless both shape parameters
+/// are positive and finite and `x` is not NaN.
+pub fn cdf_beta<T: Float + FloatConst>(x: T, alpha: T, beta: T) -> Option<T> {
+    if x.is_nan() {
+        return None;
+    }
+    Beta::new(alpha, beta).map(|dist| dist.cdf(x))
 }
 
 #[cfg(test)]
@@ -51,8 +159,10 @@ mod tests {
         let alpha = 2.0;
         let beta = 3.0;
 
-        assert!((cdf_beta(0.0, alpha, beta) - 0.0).abs() < 1e-10);
-        assert!((cdf_beta(1.0, alpha, beta) - 1.0).abs() < 1e-10);
+        assert_eq!(cdf_beta(0.0, alpha, beta), Some(0.));
+        assert_eq!(cdf_beta(1.0, alpha, beta), Some(1.));
+        assert_eq!(cdf_beta(-1f32, 2., 3.), Some(0.));
+        assert!(cdf_beta(0.5, -2., 3.).is_none());
     }
 
     #[test]
@@ -62,6 +172,11 @@ mod tests {
         let beta = 2.0;
 
         // For alpha = beta, CDF(0.5) should be 0.5
-        assert!((cdf_beta(x, alpha, beta) - 0.5).abs() < 1e-3);
+        assert!((cdf_beta(x, alpha, beta).unwrap() - 0.5).abs() < 1e-15);
+        assert!((cdf_beta(0.5f32, 2., 2.).unwrap() - 0.5).abs() < 1e-6);
+        // f(x) = 12 x (1 - x)^2 for α = 2 and β = 3.
+        assert!((pdf_beta(0.3, 2., 3.).unwrap() - 1.764).abs() < 1e-14);
+        assert!((pdf_beta(0.3f32, 2., 3.).unwrap() - 1.764).abs() < 1e-5);
+        assert_eq!(pdf_beta(1.5, 2., 3.), Some(0.));
     }
 }
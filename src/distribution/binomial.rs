@@ -0,0 +1,96 @@
+use num_traits::{Float, FloatConst};
+
+use super::{inc_beta_xy, ln_binomial, Discrete};
+
+/// Binomial distribution of the number of successes among `n` independent
+/// trials with success probability `p`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Binomial<T> {
+    pub n: u64,
+    pub p: T,
+}
+
+impl<T: Float> Binomial<T> {
+    /// Returns `None` unless `p` is in \[0, 1\].
+    pub fn new(n: u64, p: T) -> Option<Self> {
+        if p >= T::zero() && p <= T::one() {
+            Some(Self { n, p })
+        } else {
+            None
+        }
+    }
+}
+
+/// The CDF is the regularized incomplete Beta function P(X ≤ k) =
+/// I<sub>1-p</sub>(n - k, k + 1).
+impl<T: Float + FloatConst> Discrete<T> for Binomial<T> {
+    fn support(&self) -> (u64, Option<u64>) {
+        (0, Some(self.n))
+    }
+
+    fn ln_pmf(&self, k: u64) -> T {
+        let (zero, one) = (T::zero(), T::one());
+        if k > self.n {
+            return T::neg_infinity();
+        }
+        if self.p == zero || self.p == one {
+            let mode = if self.p == zero { 0 } else { self.n };
+            return if k == mode { zero } else { T::neg_infinity() };
+        }
+        let (n, k) = (T::from(self.n).unwrap(), T::from(k).unwrap());
+        ln_binomial(n, k).unwrap() + k * self.p.ln() + (n - k) * (-self.p).ln_1p()
+    }
+
+    fn cdf(&self, k: u64) -> T {
+        if k >= self.n {
+            return T::one();
+        }
+        let (n, k) = (T::from(self.n).unwrap(), T::from(k).unwrap());
+        let q = T::one() - self.p;
+        inc_beta_xy(q, self.p, n - k, k + T::one()).unwrap()
+    }
+
+    fn sf(&self, k: u64) -> T {
+        if k >= self.n {
+            return T::zero();
+        }
+        let (n, k) = (T::from(self.n).unwrap(), T::from(k).unwrap());
+        let q = T::one() - self.p;
+        inc_beta_xy(self.p, q, k + T::one(), n - k).unwrap()
+    }
+
+    fn mean(&self) -> T {
+        T::from(self.n).unwrap() * self.p
+    }
+
+    fn variance(&self) -> T {
+        T::from(self.n).unwrap() * self.p * (T::one() - self.p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn binomial_values() {
+        let binomial = Binomial::new(20, 0.3).unwrap();
+        assert!(rel(binomial.pmf(7), 0.16426198521723649) < 1e-14);
+        assert!(rel(binomial.cdf(7), 0.77227179741816049) < 1e-14);
+        assert!(rel(binomial.sf(7), 0.22772820258183951) < 1e-14);
+        let sum = (0..=20).map(|k| binomial.pmf(k)).sum::<f64>();
+        assert!((sum - 1.).abs() < 1e-14);
+        assert_eq!(binomial.cdf(20), 1.);
+        assert_eq!(binomial.pmf(21), 0.);
+        let rare = Binomial::new(1000, 0.01).unwrap();
+        assert!(rel(rare.sf(30), 6.4199286031376668e-8) < 1e-12);
+        assert_eq!(binomial.quantile(0.77227179741816049), Some(7));
+        assert_eq!(binomial.quantile(0.78), Some(8));
+        assert_eq!(binomial.quantile(0.), Some(0));
+        assert_eq!(binomial.quantile(1.), Some(20));
+        assert_eq!(Binomial::new(5, 1.).unwrap().pmf(5), 1.);
+        assert!(Binomial::new(5, 1.5).is_none());
+    }
+}
@@ -0,0 +1,95 @@
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{from_f64, open_unit, Continuous, Sample};
+
+/// Cauchy distribution with location `location` and scale `scale`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cauchy<T> {
+    pub location: T,
+    pub scale: T,
+}
+
+impl<T: Float> Cauchy<T> {
+    /// Returns `None` unless `location` is finite and `scale` is positive and
+    /// finite.
+    pub fn new(location: T, scale: T) -> Option<Self> {
+        if location.is_finite() && scale.is_finite() && scale > T::zero() {
+            Some(Self { location, scale })
+        } else {
+            None
+        }
+    }
+}
+
+/// The tails are computed as P(X ≤ x) = atan2(1, -z) / π with z = (x -
+/// location) / scale, which does not cancel for large |z|. The mean and the
+/// variance do not exist and are NaN.
+impl<T: Float + FloatConst> Continuous<T> for Cauchy<T> {
+    fn support(&self) -> (T, T) {
+        (T::neg_infinity(), T::infinity())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let z = (x - self.location) / self.scale;
+        -(T::PI() * self.scale).ln() - (z * z).ln_1p()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let z = (x - self.location) / self.scale;
+        T::one().atan2(-z) / T::PI()
+    }
+
+    fn sf(&self, x: T) -> T {
+        let z = (x - self.location) / self.scale;
+        T::one().atan2(z) / T::PI()
+    }
+
+    fn mean(&self) -> T {
+        T::nan()
+    }
+
+    fn variance(&self) -> T {
+        T::nan()
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        if p.is_nan() || p < T::zero() || p > T::one() {
+            return None;
+        }
+        if p == T::zero() || p == T::one() {
+            return Some(if p == T::zero() {
+                T::neg_infinity()
+            } else {
+                T::infinity()
+            });
+        }
+        // tan(π(p - 1/2)) = -1 / tan(πp)
+        Some(self.location - self.scale / (T::PI() * p).tan())
+    }
+}
+
+/// Inversion of the CDF.
+impl<T: Float + FloatConst> Sample<T> for Cauchy<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.quantile(from_f64(open_unit(rng))).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn cauchy_values() {
+        let cauchy = Cauchy::new(-1., 0.5).unwrap();
+        assert!(rel(cauchy.pdf(2.5), 0.012732395447351627) < 1e-14);
+        assert!(rel(cauchy.cdf(2.5), 0.95483276469913345) < 1e-14);
+        assert!(rel(cauchy.sf(1e6), 1.5915478393709814e-7) < 1e-12);
+        assert!(rel(cauchy.quantile(0.95483276469913345).unwrap(), 2.5) < 1e-13);
+        assert_eq!(cauchy.quantile(0.5), Some(-1.));
+        assert!(cauchy.mean().is_nan());
+    }
+}
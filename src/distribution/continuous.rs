@@ -0,0 +1,333 @@
+use num_traits::Float;
+
+/// Maximum number of safeguarded Newton steps of the numerical quantile.
+const QUANTILE_MAX_ITER: usize = 1_000;
+
+/// Common interface of the distributions with a density on the real line.
+pub trait Continuous<T: Float> {
+    /// Infimum and supremum of the support, possibly infinite.
+    fn support(&self) -> (T, T);
+
+    /// Logarithm of the probability density function, -∞ outside of the
+    /// support.
+    fn ln_pdf(&self, x: T) -> T;
+
+    /// Probability density function.
+    fn pdf(&self, x: T) -> T {
+        self.ln_pdf(x).exp()
+    }
+
+    /// Cumulative distribution function P(X ≤ x).
+    fn cdf(&self, x: T) -> T;
+
+    /// Survival function P(X > x), computed without the cancellation of 1 -
+    /// P(X ≤ x) in the upper tail.
+    fn sf(&self, x: T) -> T;
+
+    /// Expectation, infinite or NaN when it does not exist.
+    fn mean(&self) -> T;
+
+    /// Variance, infinite or NaN when it does not exist.
+    fn variance(&self) -> T;
+
+    /// Quantile function, the solution x of P(X ≤ x) = `p`.
+    ///
+    /// By default the root is bracketed from the mean (or the middle of the
+    /// support) by doubling steps, then refined by Newton steps on the CDF,
+    /// or on the survival function when `p` > 1/2 to keep the upper tail
+    /// accurate, falling back to bisection when a step leaves the bracket.
+    /// Returns `None` when `p` is not in \[0, 1\].
+    fn quantile(&self, p: T) -> Option<T> {
+        let (zero, one) = (T::zero(), T::one());
+        let (lower, upper) = self.support();
+        if p.is_nan() || p < zero || p > one {
+            return None;
+        }
+        if p == zero {
+            return Some(lower);
+        }
+        if p == one {
+            return Some(upper);
+        }
+        let half = T::from(0.5).unwrap();
+        let mean = self.mean();
+        let start = if mean.is_finite() && mean > lower && mean < upper {
+            mean
+        } else if lower.is_finite() && upper.is_finite() {
+            half * (lower + upper)
+        } else if lower.is_finite() {
+            lower + one
+        } else if upper.is_finite() {
+            upper - one
+        } else {
+            zero
+        };
+        newton_quantile(self, p, start)
+    }
+}
+
+/// Quantile of `dist` at `p` in (0, 1) by safeguarded Newton steps from
+/// `start`, see [`Continuous::quantile`].
+pub(crate) fn newton_quantile<T, D>(dist: &D, p: T, start: T) -> Option<T>
+where
+    T: Float,
+    D: Continuous<T> + ?Sized,
+{
+    let (zero, one) = (T::zero(), T::one());
+    let half = T::from(0.5).unwrap();
+    let (lower, upper) = dist.support();
+    // Increasing function whose root is the quantile.
+    let f = |x: T| {
+        if p <= half {
+            dist.cdf(x) - p
+        } else {
+            (one - p) - dist.sf(x)
+        }
+    };
+    let mut x = start;
+    let (mut lo, mut hi) = (lower, upper);
+    let mut fx = f(x);
+    // Bracket the root when the support is unbounded on its side.
+    let mut step = x.abs().max(one);
+    if fx < zero {
+        lo = x;
+        while upper.is_infinite() {
+            let next = x + step;
+            let f_next = f(next);
+            if f_next >= zero {
+                hi = next;
+                break;
+            }
+            lo = next;
+            step = step + step;
+            if !next.is_finite() {
+                return Some(next);
+            }
+        }
+    } else {
+        hi = x;
+        while lower.is_infinite() {
+            let next = x - step;
+            let f_next = f(next);
+            if f_next <= zero {
+                lo = next;
+                break;
+            }
+            hi = next;
+            step = step + step;
+            if !next.is_finite() {
+                return Some(next);
+            }
+        }
+    }
+    for _ in 0..QUANTILE_MAX_ITER {
+        if fx == zero {
+            return Some(x);
+        }
+        if fx < zero {
+            lo = x;
+        } else {
+            hi = x;
+        }
+        let newton = x - fx / dist.pdf(x);
+        let next = if newton > lo && newton < hi {
+            newton
+        } else if lo > zero && hi > lo + lo {
+            // Bisection on the logarithmic scale for wide positive brackets.
+            (lo * hi).sqrt()
+        } else if lo == zero && hi > zero {
+            hi / T::from(8.).unwrap()
+        } else {
+            half * (lo + hi)
+        };
+        let tol = T::from(2.).unwrap() * T::epsilon() * next.abs() + T::min_positive_value();
+        if (next - x).abs() <= tol || (hi - lo).abs() <= tol {
+            return Some(next);
+        }
+        x = next;
+        fx = f(x);
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::distribution::*;
+    use crate::test_util::rel;
+
+    /// Checks the CDF against the density, the survival function and the
+    /// quantile, and the sample mean against the expectation.
+    fn check<D: Continuous<f64> + Sample<f64>>(dist: &D, points: &[f64]) {
+        let mut rng = StdRng::seed_from_u64(1);
+        for &x in points {
+            let (cdf, sf) = (dist.cdf(x), dist.sf(x));
+            assert!((cdf + sf - 1.).abs() < 1e-14, "cdf + sf at {x}");
+            let h = 1e-5 * x.abs().max(1.);
+            let derivative = (dist.cdf(x + h) - dist.cdf(x - h)) / (2. * h);
+            let pdf = dist.pdf(x);
+            assert!(
+                (derivative - pdf).abs() < 1e-6 * pdf.max(1.),
+                "pdf at {x}: {pdf} vs {derivative}"
+            );
+            let q = dist.quantile(cdf).unwrap();
+            assert!(
+                (q - x).abs() < 1e-9 * x.abs().max(1.),
+                "quantile at {x}: {q}"
+            );
+        }
+        let mean = dist.mean();
+        if dist.variance().is_finite() {
+            let n = 50_000;
+            let draws = (0..n).map(|_| dist.sample(&mut rng)).sum::<f64>() / n as f64;
+            let tol = 5. * (dist.variance() / n as f64).sqrt();
+            assert!((draws - mean).abs() < tol, "sample mean {draws} vs {mean}");
+        }
+    }
+
+    #[test]
+    fn continuous_families_are_consistent() {
+        let points = [-3.1, -0.4, 0.2, 1.3, 2.5, 7.];
+        let positive = [0.05, 0.4, 1.3, 2.5, 7.];
+        check(&Normal::new(0.5, 2.).unwrap(), &points);
+        check(&StudentsT::new(4.5).unwrap(), &points);
+        check(&StudentsT::noncentral(6., 1.).unwrap(), &points);
+        check(&Logistic::new(1., 2.).unwrap(), &points);
+        check(&Cauchy::new(-1., 0.5).unwrap(), &points);
+        check(&Gumbel::new(0.5, 2.).unwrap(), &points);
+        check(&Laplace::new(1., 2.).unwrap(), &points);
+        check(
+            &GeneralizedExtremeValue::new(0.5, 2., 0.2).unwrap(),
+            &points,
+        );
+        check(
+            &GeneralizedExtremeValue::new(0.5, 2., -0.3).unwrap(),
+            &points,
+        );
+        let t = StudentsT::new(4.5).unwrap();
+        check(&LocationScale::new(t, 1., 2.).unwrap(), &points);
+        check(&Gamma::new(2.5, 1.5).unwrap(), &positive);
+        check(&Gamma::new(0.6, 1.5).unwrap(), &positive);
+        check(&ChiSquared::new(7.).unwrap(), &positive);
+        check(&LogNormal::new(0.3, 0.8).unwrap(), &positive);
+        check(&Weibull::new(1.7, 3.).unwrap(), &positive);
+        check(&Pareto::new(0.03, 3.).unwrap(), &positive);
+        check(&GeneralizedPareto::new(0., 2., 0.2).unwrap(), &positive);
+        check(&GeneralizedPareto::new(0., 2., -0.2).unwrap(), &positive);
+        check(&Beta::new(2., 5.).unwrap(), &[0.01, 0.1, 0.3, 0.5, 0.9]);
+        check(&Beta::new(0.5, 0.7).unwrap(), &[0.01, 0.1, 0.3, 0.5, 0.9]);
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn numerical_quantiles() {
+        let gamma = Gamma::new(2.5, 1.5).unwrap();
+        assert!(rel(gamma.pdf(2.5), 0.20380814168623311) < 1e-14);
+        assert!(rel(gamma.cdf(2.5), 0.35125764133240662) < 1e-14);
+        assert!(rel(gamma.quantile(0.3).unwrap(), 2.2499310995699297) < 1e-13);
+        let chi2 = ChiSquared::new(7.).unwrap();
+        assert!(rel(chi2.quantile(0.95).unwrap(), 14.067140449340166) < 1e-13);
+        let beta = Beta::new(2., 5.).unwrap();
+        assert!(rel(beta.pdf(0.3), 2.1608999999999994) < 1e-14);
+        assert!(rel(beta.quantile(0.1).unwrap(), 0.092595258913128752) < 1e-13);
+        let t = StudentsT::new(5.).unwrap();
+        assert!(rel(t.pdf(1.3), 0.15847673572898243) < 1e-14);
+        assert!(rel(t.quantile(0.975).unwrap(), 2.5705818356363148) < 1e-12);
+        let nct = StudentsT::noncentral(6., 1.).unwrap();
+        assert!(rel(nct.pdf(1.5), 0.30921410820782227) < 1e-10);
+        assert!(rel(nct.pdf(0.), 0.23213916088275153) < 1e-14);
+        assert!(rel(nct.pdf(-2.), 0.008472229178352187) < 1e-8);
+        assert!(rel(nct.mean(), 1.1512425464397995) < 1e-14);
+        // Tiny lower quantiles of a positive distribution.
+        let q = Gamma::new(0.5, 1.).unwrap().quantile(1e-100).unwrap();
+        assert!(rel(Gamma::new(0.5, 1.).unwrap().cdf(q), 1e-100) < 1e-12);
+        assert_eq!(gamma.quantile(0.), Some(0.));
+        assert!(gamma.quantile(1.1).is_none());
+    }
+
+    /// Worst relative errors of the density, the CDF and survival function
+    /// and the quantile of `d32` against `d64`, in units of f32::EPSILON.
+    fn single_precision_errors<D32, D64>(d32: &D32, d64: &D64, points: &[f32]) -> [f64; 3]
+    where
+        D32: Continuous<f32>,
+        D64: Continuous<f64>,
+    {
+        let ulps = |a: f32, b: f64| ((a as f64 - b) / b).abs() / f32::EPSILON as f64;
+        let mut worst = [0f64; 3];
+        for &x in points {
+            let x64 = x as f64;
+            worst[0] = worst[0].max(ulps(d32.pdf(x), d64.pdf(x64)));
+            worst[1] = worst[1]
+                .max(ulps(d32.cdf(x), d64.cdf(x64)))
+                .max(ulps(d32.sf(x), d64.sf(x64)));
+        }
+        for p in [0.001f32, 0.1, 0.5, 0.9, 0.999] {
+            let q = d32.quantile(p).unwrap();
+            worst[2] = worst[2].max(ulps(q, d64.quantile(p as f64).unwrap()));
+        }
+        worst
+    }
+
+    #[test]
+    fn single_precision_families() {
+        let points = [-3.125, -0.375, 0.25, 1.25, 2.5, 7.];
+        let positive = [0.0625, 0.375, 1.25, 2.5, 7.];
+        let unit = [0.015625, 0.125, 0.375, 0.5, 0.875];
+        let errors = [
+            single_precision_errors(
+                &Normal::new(0.5f32, 2.).unwrap(),
+                &Normal::new(0.5, 2.).unwrap(),
+                &points,
+            ),
+            single_precision_errors(
+                &StudentsT::new(4.5f32).unwrap(),
+                &StudentsT::new(4.5).unwrap(),
+                &points,
+            ),
+            single_precision_errors(
+                &Cauchy::new(-1f32, 0.5).unwrap(),
+                &Cauchy::new(-1., 0.5).unwrap(),
+                &points,
+            ),
+            single_precision_errors(
+                &GeneralizedExtremeValue::new(0.5f32, 2., 0.25).unwrap(),
+                &GeneralizedExtremeValue::new(0.5, 2., 0.25).unwrap(),
+                &points,
+            ),
+            single_precision_errors(
+                &Gamma::new(0.625f32, 1.5).unwrap(),
+                &Gamma::new(0.625, 1.5).unwrap(),
+                &positive,
+            ),
+            single_precision_errors(
+                &LogNormal::new(0.25f32, 0.75).unwrap(),
+                &LogNormal::new(0.25, 0.75).unwrap(),
+                &positive,
+            ),
+            single_precision_errors(
+                &Pareto::new(0.03125f32, 3.).unwrap(),
+                &Pareto::new(0.03125, 3.).unwrap(),
+                &positive,
+            ),
+            single_precision_errors(
+                &Beta::new(0.5f32, 0.75).unwrap(),
+                &Beta::new(0.5, 0.75).unwrap(),
+                &unit,
+            ),
+        ];
+        for worst in errors {
+            assert!(worst.iter().all(|e| *e < 64.), "{worst:?}");
+        }
+        // The non-central CDF is accurate in absolute terms.
+        let (nct32, nct64) = (
+            StudentsT::noncentral(6f32, 1.).unwrap(),
+            StudentsT::noncentral(6., 1.).unwrap(),
+        );
+        for x in points {
+            let error = (nct32.cdf(x) as f64 - nct64.cdf(x as f64)).abs();
+            assert!(error < 8. * f32::EPSILON as f64);
+        }
+    }
+}
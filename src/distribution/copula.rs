@@ -0,0 +1,508 @@
+use std::cell::RefCell;
+
+use ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix1, Ix2};
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{
+    cdf_n01, checked_cholesky, cholesky, cholesky_inverse, correlate, fit_output, from_f64,
+    ln_det_sqrt, lngamma, mahalanobis, mat_mul, maximize, maximize_numerical, quantile_n01,
+    standard_chi2, standard_normal, to_f64, Continuous, FitOutput, Sample, StudentsT,
+};
+use crate::statistical_test::average_ranks;
+
+/// Dependence structure of a random vector, the joint distribution of the
+/// uniform variables U<sub>i</sub> = F<sub>i</sub>(X<sub>i</sub>) obtained by
+/// applying to each component its continuous marginal CDF F<sub>i</sub>.
+pub trait Copula<T: Float>: Sample<Array1<T>> {
+    /// Dimension d.
+    fn dim(&self) -> usize;
+
+    /// Logarithm of the density c(u) at `u` in (0, 1)<sup>d</sup>,
+    /// -∞ outside. Returns NaN when `u` does not have dimension d.
+    fn ln_pdf<S: Data<Elem = T>>(&self, u: &ArrayBase<S, Ix1>) -> T;
+
+    /// Density c(u).
+    fn pdf<S: Data<Elem = T>>(&self, u: &ArrayBase<S, Ix1>) -> T {
+        self.ln_pdf(u).exp()
+    }
+
+    /// Draws a random vector X<sub>i</sub> = F<sub>i</sub><sup>-1</sup>(U<sub>i</sub>)
+    /// with the dependence of the copula and the given `margins`, for
+    /// instance to simulate correlated outcomes. Returns `None` unless there
+    /// are d margins.
+    fn sample_margins<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        margins: &[&dyn Continuous<T>],
+    ) -> Option<Array1<T>> {
+        if margins.len() != self.dim() {
+            return None;
+        }
+        let u = self.sample(rng);
+        margins
+            .iter()
+            .zip(&u)
+            .map(|(margin, u)| margin.quantile(*u))
+            .collect::<Option<Vec<_>>>()
+            .map(Array1::from)
+    }
+}
+
+/// Maximum pseudo-likelihood fitting of a copula.
+pub trait FitCopula<T>: Sized {
+    /// Fits the copula to `data`, whose rows are the observations, by
+    /// maximizing the likelihood of its [`pseudo_observations`], following
+    /// [Genest, Ghoudi and Rivest (1995)][ggr]. The margins are left
+    /// unspecified.
+    ///
+    /// The standard errors are those of a likelihood with known margins:
+    /// they ignore the variability of the ranks, and understate the
+    /// uncertainty of the estimates. Returns `None` when `data` has less
+    /// than two rows, the wrong number of columns or a NaN, or when the
+    /// pseudo-likelihood has no maximum in the parameter space.
+    ///
+    /// [ggr]: https://doi.org/10.1093/biomet/82.3.543
+    fn fit<S: Data<Elem = T>>(data: &ArrayBase<S, Ix2>) -> Option<FitOutput<Self, T>>;
+}
+
+/// Computes the pseudo-observations R<sub>ij</sub>/(n + 1) of `data`, where
+/// R<sub>ij</sub> is the rank of the observation i in the column j among the
+/// n rows, ties receiving their average rank. Returns `None` when `data`
+/// has less than two rows or a NaN.
+pub fn pseudo_observations<T: Float, S: Data<Elem = T>>(
+    data: &ArrayBase<S, Ix2>,
+) -> Option<Array2<T>> {
+    let (n, d) = data.dim();
+    if n < 2 || data.iter().any(|x| x.is_nan()) {
+        return None;
+    }
+    let nf = T::from(n + 1).unwrap();
+    let mut u = Array2::zeros((n, d));
+    for (j, column) in data.columns().into_iter().enumerate() {
+        let (ranks, _) = average_ranks(&column.to_vec());
+        for (i, rank) in ranks.into_iter().enumerate() {
+            u[[i, j]] = rank / nf;
+        }
+    }
+    Some(u)
+}
+
+/// Kendall's τ<sub>a</sub> = (concordant - discordant pairs) / (n(n - 1)/2),
+/// ties counting as neither.
+pub(crate) fn kendall_tau<T: Float, S1, S2>(x: &ArrayBase<S1, Ix1>, y: &ArrayBase<S2, Ix1>) -> T
+where
+    S1: Data<Elem = T>,
+    S2: Data<Elem = T>,
+{
+    let n = x.len();
+    let mut score = 0i64;
+    for i in 0..n {
+        for j in 0..i {
+            let s = (x[i] - x[j]) * (y[i] - y[j]);
+            if s > T::zero() {
+                score += 1;
+            } else if s < T::zero() {
+                score -= 1;
+            }
+        }
+    }
+    T::from(score).unwrap() / T::from(n * (n - 1) / 2).unwrap()
+}
+
+/// Pseudo-observations of `data` after checking its number of columns.
+fn checked_pseudo_observations<T: Float, S: Data<Elem = T>>(
+    data: &ArrayBase<S, Ix2>,
+    dim: Option<usize>,
+) -> Option<Array2<T>> {
+    if data.ncols() < 2 || dim.is_some_and(|d| data.ncols() != d) {
+        return None;
+    }
+    pseudo_observations(data)
+}
+
+/// Correlation matrix with the off-diagonal elements `rho` of its upper
+/// triangle in row order.
+fn correlation_from_pairs<T: Float>(rho: &[T], dim: usize) -> Array2<T> {
+    let mut r = Array2::eye(dim);
+    let mut k = 0;
+    for i in 0..dim {
+        for j in i + 1..dim {
+            r[[i, j]] = rho[k];
+            r[[j, i]] = rho[k];
+            k += 1;
+        }
+    }
+    r
+}
+
+/// Off-diagonal elements of the upper triangle of `r` in row order.
+fn pairs_from_correlation<T: Float>(r: &Array2<T>) -> Vec<T> {
+    let d = r.nrows();
+    (0..d)
+        .flat_map(|i| (i + 1..d).map(move |j| (i, j)))
+        .map(|(i, j)| r[[i, j]])
+        .collect()
+}
+
+/// Cholesky factor of `correlation` after checking that it is a correlation
+/// matrix of dimension at least 2.
+fn checked_correlation<T: Float>(correlation: &Array2<T>) -> Option<Array2<T>> {
+    let d = correlation.nrows();
+    if d < 2 || correlation.diag().iter().any(|c| *c != T::one()) {
+        return None;
+    }
+    checked_cholesky(&Array1::zeros(d), correlation)
+}
+
+/// Correlation matrix of the normal scores Φ<sup>-1</sup>(u) of the
+/// pseudo-observations `u`, with the scores.
+fn normal_scores<T: Float + FloatConst>(u: &Array2<T>) -> (Array2<T>, Array2<T>) {
+    let z = u.mapv(|u| quantile_n01(u).unwrap());
+    let s = mat_mul(&z.t(), &z);
+    let d = s.nrows();
+    let r = Array2::from_shape_fn((d, d), |(i, j)| s[[i, j]] / (s[[i, i]] * s[[j, j]]).sqrt());
+    (r, z)
+}
+
+/// Gaussian copula with correlation matrix R = `correlation`, the dependence
+/// of a multivariate normal distribution, of density c(u) =
+/// |R|<sup>-1/2</sup> exp(-z<sup>T</sup> (R<sup>-1</sup> - I) z / 2) with
+/// z<sub>i</sub> = Φ<sup>-1</sup>(u<sub>i</sub>).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianCopula<T> {
+    correlation: Array2<T>,
+    cholesky: Array2<T>,
+}
+
+impl<T: Float + FloatConst> GaussianCopula<T> {
+    /// Returns `None` unless `correlation` is a symmetric positive definite
+    /// matrix with unit diagonal, of dimension at least 2.
+    pub fn new(correlation: Array2<T>) -> Option<Self> {
+        let cholesky = checked_correlation(&correlation)?;
+        Some(Self {
+            correlation,
+            cholesky,
+        })
+    }
+
+    /// Correlation matrix R.
+    pub fn correlation(&self) -> &Array2<T> {
+        &self.correlation
+    }
+}
+
+impl<T: Float + FloatConst> Copula<T> for GaussianCopula<T> {
+    fn dim(&self) -> usize {
+        self.correlation.nrows()
+    }
+
+    fn ln_pdf<S: Data<Elem = T>>(&self, u: &ArrayBase<S, Ix1>) -> T {
+        if u.len() != self.dim() {
+            return T::nan();
+        }
+        if u.iter().any(|u| !(*u > T::zero() && *u < T::one())) {
+            return T::neg_infinity();
+        }
+        let z = u.mapv(|u| quantile_n01(u).unwrap());
+        let d2 = mahalanobis(&self.cholesky, &Array1::zeros(self.dim()), &z).unwrap();
+        let squares = z.iter().fold(T::zero(), |acc, z| acc + *z * *z);
+        -ln_det_sqrt(&self.cholesky) - T::from(0.5).unwrap() * (d2 - squares)
+    }
+}
+
+/// Draws Φ(L Z) with Z a vector of independent standard normal variates.
+impl<T: Float + FloatConst> Sample<Array1<T>> for GaussianCopula<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Array1<T> {
+        let d = self.dim();
+        let z = Array1::from_shape_simple_fn(d, || from_f64(standard_normal(rng)));
+        correlate(&Array1::zeros(d), &self.cholesky, &z, T::one()).mapv(|x| cdf_n01(x).unwrap())
+    }
+}
+
+/// Estimates the correlations ρ<sub>ij</sub>, i < j, in row order, starting
+/// from the correlations of the normal scores.
+impl<T: Float + FloatConst> FitCopula<T> for GaussianCopula<T> {
+    fn fit<S: Data<Elem = T>>(data: &ArrayBase<S, Ix2>) -> Option<FitOutput<Self, T>> {
+        let u = checked_pseudo_observations(data, None)?;
+        let (n, d) = u.dim();
+        let nf = T::from(n).unwrap();
+        let (start, z) = normal_scores(&u);
+        // Sums of squares and cross products S = ∑ z z^T.
+        let s = mat_mul(&z.t(), &z);
+        let trace = s.diag().sum();
+        let pairs = (0..d)
+            .flat_map(|i| (i + 1..d).map(move |j| (i, j)))
+            .collect::<Vec<_>>();
+        let (theta, loglik, hessian) =
+            maximize(Array1::from(pairs_from_correlation(&start)), |theta| {
+                if theta.iter().any(|r| r.is_nan() || r.abs() >= T::one()) {
+                    return None;
+                }
+                let r = correlation_from_pairs(theta.as_slice().unwrap(), d);
+                let l = cholesky(&r)?;
+                // ℓ = -n ln|R|/2 - tr((P - I) S)/2 with P = R^-1, of gradient
+                // -n P_ij + M_ij with M = P S P.
+                let p = cholesky_inverse(&l);
+                let m = mat_mul(&mat_mul(&p, &s), &p);
+                let half = T::from(0.5).unwrap();
+                let loglik = -nf * ln_det_sqrt(&l) - half * ((&p * &s).sum() - trace);
+                let grad = pairs
+                    .iter()
+                    .map(|(i, j)| m[[*i, *j]] - nf * p[[*i, *j]])
+                    .collect::<Array1<_>>();
+                let hessian = Array2::from_shape_fn((pairs.len(), pairs.len()), |(k, l)| {
+                    let ((i, j), (a, b)) = (pairs[k], pairs[l]);
+                    nf * (p[[i, a]] * p[[b, j]] + p[[i, b]] * p[[a, j]])
+                        - (p[[i, a]] * m[[b, j]]
+                            + p[[i, b]] * m[[a, j]]
+                            + m[[i, a]] * p[[b, j]]
+                            + m[[i, b]] * p[[a, j]])
+                });
+                Some((loglik, grad, hessian))
+            })?;
+        let copula = Self::new(correlation_from_pairs(theta.as_slice().unwrap(), d))?;
+        fit_output(copula, theta, &hessian, loglik, n)
+    }
+}
+
+/// Student's t copula with correlation matrix R = `correlation` and ν = `df`
+/// degrees of freedom, the dependence of a multivariate t-distribution. Unlike
+/// the Gaussian copula, it has tail dependence: joint extremes are more
+/// likely, all the more so as ν is small.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StudentsTCopula<T> {
+    correlation: Array2<T>,
+    cholesky: Array2<T>,
+    df: T,
+}
+
+impl<T: Float + FloatConst> StudentsTCopula<T> {
+    /// Returns `None` unless `correlation` is a symmetric positive definite
+    /// matrix with unit diagonal, of dimension at least 2, and `df` is
+    /// positive and finite.
+    pub fn new(correlation: Array2<T>, df: T) -> Option<Self> {
+        if !(df.is_finite() && df > T::zero()) {
+            return None;
+        }
+        let cholesky = checked_correlation(&correlation)?;
+        Some(Self {
+            correlation,
+            cholesky,
+            df,
+        })
+    }
+
+    /// Correlation matrix R.
+    pub fn correlation(&self) -> &Array2<T> {
+        &self.correlation
+    }
+
+    /// Degrees of freedom ν.
+    pub fn df(&self) -> T {
+        self.df
+    }
+}
+
+/// Sum of the logarithms of the density of the t copula at the rows of the t
+/// scores `x` = F<sub>ν</sub><sup>-1</sup>(u), ln f<sub>ν,R</sub>(x) - ∑ ln
+/// f<sub>ν</sub>(x<sub>i</sub>) for the multivariate and univariate
+/// t-densities.
+fn t_copula_ln_pdf<T: Float + FloatConst>(cholesky: &Array2<T>, df: T, x: &Array2<T>) -> T {
+    let (one, half) = (T::one(), T::from(0.5).unwrap());
+    let (n, dim) = x.dim();
+    let d = T::from(dim).unwrap();
+    let constant = lngamma(half * (df + d)) + (d - one) * lngamma(half * df)
+        - d * lngamma(half * (df + one))
+        - ln_det_sqrt(cholesky);
+    let mut z = vec![T::zero(); dim];
+    let mut sum = T::from(n).unwrap() * constant;
+    for row in x.rows() {
+        // Squared Mahalanobis distance by forward substitution L z = x.
+        let mut d2 = T::zero();
+        let mut margins = T::zero();
+        for i in 0..dim {
+            z[i] = row[i];
+            for k in 0..i {
+                z[i] = z[i] - cholesky[[i, k]] * z[k];
+            }
+            z[i] = z[i] / cholesky[[i, i]];
+            d2 = d2 + z[i] * z[i];
+            margins = margins + (row[i] * row[i] / df).ln_1p();
+        }
+        sum = sum - half * (df + d) * (d2 / df).ln_1p() + half * (df + one) * margins;
+    }
+    sum
+}
+
+impl<T: Float + FloatConst> Copula<T> for StudentsTCopula<T> {
+    fn dim(&self) -> usize {
+        self.correlation.nrows()
+    }
+
+    fn ln_pdf<S: Data<Elem = T>>(&self, u: &ArrayBase<S, Ix1>) -> T {
+        if u.len() != self.dim() {
+            return T::nan();
+        }
+        if u.iter().any(|u| !(*u > T::zero() && *u < T::one())) {
+            return T::neg_infinity();
+        }
+        let t = StudentsT::new(self.df).unwrap();
+        let x = u.mapv(|u| t.quantile(u).unwrap());
+        t_copula_ln_pdf(&self.cholesky, self.df, &x.insert_axis(Axis(0)))
+    }
+}
+
+/// Draws F<sub>ν</sub>(L Z / (V/ν)<sup>1/2</sup>) with Z a vector of
+/// independent standard normal variates and V ∼ χ<sup>2</sup>(ν).
+impl<T: Float + FloatConst> Sample<Array1<T>> for StudentsTCopula<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Array1<T> {
+        let d = self.dim();
+        let z = Array1::from_shape_simple_fn(d, || from_f64(standard_normal(rng)));
+        let df = to_f64(self.df);
+        let radius = from_f64::<T>((standard_chi2(rng, df) / df).sqrt());
+        let t = StudentsT::new(self.df).unwrap();
+        correlate(&Array1::zeros(d), &self.cholesky, &z, T::one() / radius).mapv(|x| t.cdf(x))
+    }
+}
+
+/// Estimates the correlations ρ<sub>ij</sub>, i < j, in row order, and the
+/// degrees of freedom `df`, starting from the inversion ρ<sub>ij</sub> =
+/// sin(π τ<sub>ij</sub>/2) of Kendall's τ and 5 degrees of freedom. The
+/// derivatives are computed by finite differences. Returns `None` when the
+/// pseudo-likelihood increases without bound in `df`, in which case the
+/// [`GaussianCopula`] fits at least as well.
+impl<T: Float + FloatConst> FitCopula<T> for StudentsTCopula<T> {
+    fn fit<S: Data<Elem = T>>(data: &ArrayBase<S, Ix2>) -> Option<FitOutput<Self, T>> {
+        let u = checked_pseudo_observations(data, None)?;
+        let (n, d) = u.dim();
+        let tau = Array2::from_shape_fn((d, d), |(i, j)| {
+            if i == j {
+                T::one()
+            } else {
+                (T::FRAC_PI_2() * kendall_tau(&u.column(i), &u.column(j))).sin()
+            }
+        });
+        let start = if cholesky(&tau).is_some() {
+            tau
+        } else {
+            normal_scores(&u).0
+        };
+        let mut theta = pairs_from_correlation(&start);
+        theta.push(T::from(5.).unwrap());
+        // By symmetry, the t scores only need the quantiles of the distinct
+        // values of min(u, 1 - u), cached for the last few degrees of freedom
+        // evaluated by the finite differences.
+        let half = T::from(0.5).unwrap();
+        let mut grid = u.iter().map(|u| u.min(T::one() - *u)).collect::<Vec<_>>();
+        grid.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        grid.dedup();
+        let position = u.mapv(|u| {
+            let v = u.min(T::one() - u);
+            grid.binary_search_by(|g| g.partial_cmp(&v).unwrap())
+                .unwrap()
+        });
+        let quantiles = RefCell::new(Vec::<(T, Vec<T>)>::new());
+        let loglik = |theta: &Array1<T>| {
+            let (rho, df) = (
+                &theta.as_slice().unwrap()[..theta.len() - 1],
+                theta[theta.len() - 1],
+            );
+            if rho.iter().any(|r| r.is_nan() || r.abs() >= T::one()) {
+                return None;
+            }
+            let t = StudentsT::new(df).filter(|_| df.is_finite())?;
+            let l = cholesky(&correlation_from_pairs(rho, d))?;
+            let mut cache = quantiles.borrow_mut();
+            if !cache.iter().any(|(nu, _)| *nu == df) {
+                if cache.len() == 8 {
+                    cache.remove(0);
+                }
+                let q = grid.iter().map(|g| t.quantile(*g).unwrap()).collect();
+                cache.push((df, q));
+            }
+            let q = &cache.iter().find(|(nu, _)| *nu == df).unwrap().1;
+            let x = Array2::from_shape_fn((n, d), |(i, j)| {
+                let x = q[position[[i, j]]];
+                if u[[i, j]] > half {
+                    -x
+                } else {
+                    x
+                }
+            });
+            Some(t_copula_ln_pdf(&l, df, &x))
+        };
+        let (theta, loglik, hessian) = maximize_numerical(Array1::from(theta), loglik)?;
+        let k = theta.len() - 1;
+        let copula = Self::new(correlation_from_pairs(&theta.to_vec()[..k], d), theta[k])?;
+        fit_output(copula, theta, &hessian, loglik, n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, arr2};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::distribution::Normal;
+    use crate::test_util::{assert_fit, earnings_pairs, rel};
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn copula_values() {
+        let u = pseudo_observations(&arr2(&[[3., 1.], [1., 2.], [3., 2.], [2., 5.]])).unwrap();
+        assert_eq!(u.column(0), arr1(&[0.7, 0.2, 0.7, 0.4]));
+        assert_eq!(u.column(1), arr1(&[0.2, 0.5, 0.5, 0.8]));
+        let r = arr2(&[[1., 0.5], [0.5, 1.]]);
+        let point = arr1(&[0.3, 0.8]);
+        let gaussian = GaussianCopula::new(r.clone()).unwrap();
+        assert!(rel(gaussian.ln_pdf(&point), -0.31427706779005782) < 1e-13);
+        assert_eq!(gaussian.ln_pdf(&arr1(&[0.3, 1.])), f64::NEG_INFINITY);
+        assert!(gaussian.ln_pdf(&arr1(&[0.3, 0.8, 0.5])).is_nan());
+        let t = StudentsTCopula::new(r.clone(), 4.).unwrap();
+        assert!(rel(t.ln_pdf(&point), -0.41284411433511618) < 1e-10);
+        assert!(GaussianCopula::new(arr2(&[[1., 1.5], [1.5, 1.]])).is_none());
+        assert!(StudentsTCopula::new(r, 0.).is_none());
+        // Kendall's τ of the Gaussian copula is 2 arcsin(ρ)/π = 1/3.
+        let mut rng = StdRng::seed_from_u64(1);
+        let normal = Normal::new(10., 2.).unwrap();
+        let draws = (0..1000)
+            .map(|_| {
+                gaussian
+                    .sample_margins(&mut rng, &[&normal, &normal])
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let x = Array1::from_iter(draws.iter().map(|d| d[0]));
+        let y = Array1::from_iter(draws.iter().map(|d| d[1]));
+        assert!((kendall_tau::<f64, _, _>(&x, &y) - 1. / 3.).abs() < 0.05);
+        assert!((x.mean().unwrap() - 10.).abs() < 0.3);
+        assert!(gaussian.sample_margins(&mut rng, &[&normal]).is_none());
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn elliptical_copula_fits() {
+        let data = earnings_pairs();
+        let gaussian = GaussianCopula::fit(&data).unwrap();
+        assert_fit(
+            &gaussian,
+            &[0.15395563824465549],
+            &[0.054495426862531884],
+            3.7523728519370955,
+        );
+        assert_eq!(
+            gaussian.distribution.correlation()[[1, 0]],
+            gaussian.estimates[0]
+        );
+        // The pseudo-likelihood is flat in the degrees of freedom, and its
+        // finite differences go through numerical t quantiles.
+        let t = StudentsTCopula::fit(&data).unwrap();
+        assert!(rel(t.estimates[0], 0.15839680297834732) < 1e-7);
+        assert!(rel(t.estimates[1], 20.379955509596628) < 1e-6);
+        assert!(rel(t.std_errors[0], 0.056576170643953989) < 1e-5);
+        assert!(rel(t.std_errors[1], 29.421928966743254) < 1e-3);
+        assert!(rel(t.loglik, 4.0069192843108573) < 1e-10);
+    }
+}
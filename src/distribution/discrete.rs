@@ -0,0 +1,73 @@
+use num_traits::Float;
+
+/// Common interface of the distributions supported by the non-negative
+/// integers.
+pub trait Discrete<T: Float> {
+    /// Smallest and largest values of the support, the latter being `None`
+    /// when the support is unbounded.
+    fn support(&self) -> (u64, Option<u64>);
+
+    /// Logarithm of the probability mass function, -∞ outside of the support.
+    fn ln_pmf(&self, k: u64) -> T;
+
+    /// Probability mass function P(X = k).
+    fn pmf(&self, k: u64) -> T {
+        self.ln_pmf(k).exp()
+    }
+
+    /// Cumulative distribution function P(X ≤ k).
+    fn cdf(&self, k: u64) -> T;
+
+    /// Survival function P(X > k), computed without the cancellation of 1 -
+    /// P(X ≤ k) in the upper tail.
+    fn sf(&self, k: u64) -> T;
+
+    fn mean(&self) -> T;
+
+    fn variance(&self) -> T;
+
+    /// Quantile function, the smallest k such that P(X ≤ k) ≥ `p`.
+    ///
+    /// The search starts at the mean and brackets k by doubling steps before
+    /// a bisection. As in R, `p` is shrunk by 64 machine epsilons to absorb
+    /// the rounding errors of the CDF. Returns `None` when `p` is not in \[0,
+    /// 1\], or when `p` = 1 and the support is unbounded.
+    fn quantile(&self, p: T) -> Option<u64> {
+        let (lower, upper) = self.support();
+        if p.is_nan() || p < T::zero() || p > T::one() {
+            return None;
+        }
+        if p == T::zero() {
+            return Some(lower);
+        }
+        if p == T::one() {
+            return upper;
+        }
+        let target = p * (T::one() - T::from(64.).unwrap() * T::epsilon());
+        let upper = upper.unwrap_or(u64::MAX);
+        let start = self.mean().to_u64().unwrap_or(lower).clamp(lower, upper);
+        let (mut lo, mut hi) = if self.cdf(start) >= target {
+            (lower, start)
+        } else {
+            let mut lo = start;
+            let mut step = 1u64;
+            loop {
+                let hi = lo.saturating_add(step).min(upper);
+                if hi == upper || self.cdf(hi) >= target {
+                    break (lo + 1, hi);
+                }
+                lo = hi;
+                step = step.saturating_mul(2);
+            }
+        };
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.cdf(mid) >= target {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        Some(lo)
+    }
+}
@@ -0,0 +1,215 @@
+use ndarray::Array2;
+use num_traits::{Float, FloatConst};
+
+use super::{cdf_n01, chi_expectation, invert_cdf, log_cdf_n01, normal_expectation, sf_n01};
+use crate::statistical_test::TestTSide;
+
+/// Distribution of Dunnett's many-to-one comparisons statistic, the maximum
+/// of the t-statistics T<sub>i</sub> = (X̄<sub>i</sub> - X̄<sub>0</sub>) / (S
+/// (1/n<sub>i</sub> + 1/n<sub>0</sub>)<sup>1/2</sup>) comparing each
+/// treatment group i = 1, ..., m to a control group 0, where S<sup>2</sup>
+/// is the pooled variance with ν = `df` degrees of freedom. The maximum runs
+/// over the T<sub>i</sub> for [`TestTSide::UpperOneSided`], over the
+/// -T<sub>i</sub>, with the same distribution, for
+/// [`TestTSide::LowerOneSided`], and over the |T<sub>i</sub>| for
+/// [`TestTSide::TwoSided`].
+///
+/// The statistics T<sub>i</sub> have a multivariate t-distribution with
+/// correlations ρ<sub>ij</sub> = λ<sub>i</sub>λ<sub>j</sub>, λ<sub>i</sub> =
+/// (n<sub>i</sub> / (n<sub>i</sub> + n<sub>0</sub>))<sup>1/2</sup>. This
+/// product structure reduces the probabilities to the two-dimensional
+/// integrals of [Dunnett (1955)][dunnett] over the common normal factor and
+/// the scale S, computed by adaptive Gauss-Kronrod quadrature.
+///
+/// [dunnett]: https://doi.org/10.1080/01621459.1955.10501294
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dunnett<T> {
+    lambda: Vec<T>,
+    df: T,
+    side: TestTSide,
+}
+
+impl<T: Float + FloatConst> Dunnett<T> {
+    /// Comparisons of the `treatments` groups, given by their sizes, to a
+    /// `control` group. Returns `None` when a size is zero, there is no
+    /// treatment group or `df` is not positive (it may be infinite).
+    pub fn new(control: usize, treatments: &[usize], df: T, side: TestTSide) -> Option<Self> {
+        if control == 0 || treatments.is_empty() || treatments.contains(&0) {
+            return None;
+        }
+        if df.is_nan() || df <= T::zero() {
+            return None;
+        }
+        let n0 = T::from(control).unwrap();
+        let lambda = treatments
+            .iter()
+            .map(|n| {
+                let n = T::from(*n).unwrap();
+                (n / (n + n0)).sqrt()
+            })
+            .collect();
+        Some(Self { lambda, df, side })
+    }
+
+    /// `comparisons` treatment groups of the same size as the control group,
+    /// with correlations 1/2.
+    pub fn balanced(comparisons: usize, df: T, side: TestTSide) -> Option<Self> {
+        Self::new(1, &vec![1; comparisons], df, side)
+    }
+
+    /// Number of comparisons m.
+    pub fn comparisons(&self) -> usize {
+        self.lambda.len()
+    }
+
+    /// Degrees of freedom ν.
+    pub fn df(&self) -> T {
+        self.df
+    }
+
+    /// Side of the comparisons.
+    pub fn side(&self) -> TestTSide {
+        self.side
+    }
+
+    /// Correlation matrix of the statistics T<sub>i</sub>.
+    pub fn correlation(&self) -> Array2<T> {
+        let m = self.comparisons();
+        Array2::from_shape_fn((m, m), |(i, j)| {
+            if i == j {
+                T::one()
+            } else {
+                self.lambda[i] * self.lambda[j]
+            }
+        })
+    }
+
+    /// CDF P(max T<sub>i</sub> ≤ `d`), or P(max |T<sub>i</sub>| ≤ `d`)
+    /// when two-sided.
+    pub fn cdf(&self, d: T) -> T {
+        if let Some(value) = self.limit(d, true) {
+            return value;
+        }
+        chi_expectation(
+            |s| normal_expectation(|z| self.ln_conditional_cdf(d * s, z).exp(), T::zero()),
+            self.df,
+        )
+    }
+
+    /// Survival function, the adjusted p-value of the largest statistic
+    /// `d`.
+    pub fn sf(&self, d: T) -> T {
+        if let Some(value) = self.limit(d, false) {
+            return value;
+        }
+        let spread = self.spread(d);
+        chi_expectation(
+            |s| normal_expectation(|z| -self.ln_conditional_cdf(d * s, z).exp_m1(), spread * s),
+            self.df,
+        )
+        .min(T::one())
+    }
+
+    /// Quantile function, the critical value of Dunnett's test for `p` = 1
+    /// - α. Returns `None` when `p` is not in \[0, 1\].
+    pub fn quantile(&self, p: T) -> Option<T> {
+        let lower = if self.is_two_sided() {
+            T::zero()
+        } else {
+            T::neg_infinity()
+        };
+        invert_cdf(
+            p,
+            lower,
+            T::from(2.).unwrap(),
+            |d| self.cdf(d),
+            |d| self.sf(d),
+        )
+    }
+
+    /// CDF (`lower_tail`) or survival function at NaN, infinite values and
+    /// the lower end of the support.
+    fn limit(&self, d: T, lower_tail: bool) -> Option<T> {
+        let (zero, one) = (T::zero(), T::one());
+        let cdf = if d.is_nan() {
+            return Some(d);
+        } else if d == T::infinity() {
+            one
+        } else if d == T::neg_infinity() || (self.is_two_sided() && d <= zero) {
+            zero
+        } else {
+            return None;
+        };
+        Some(if lower_tail { cdf } else { one - cdf })
+    }
+
+    /// Whether the statistic is the maximum of the absolute values.
+    fn is_two_sided(&self) -> bool {
+        self.side == TestTSide::TwoSided
+    }
+
+    /// Half-width around which the statistics exceed `d` given the common
+    /// normal factor, bounding the range of the integrals.
+    fn spread(&self, d: T) -> T {
+        let lambda = self.lambda.iter().fold(T::zero(), |acc, l| acc.max(*l));
+        lambda * d.abs()
+    }
+
+    /// Logarithm of P(max T<sub>i</sub> ≤ t / s) given S = s and the common
+    /// normal factor `z`, with `t` = d s.
+    fn ln_conditional_cdf(&self, t: T, z: T) -> T {
+        let half = T::from(0.5).unwrap();
+        self.lambda.iter().fold(T::zero(), |acc, lambda| {
+            let c = (T::one() - *lambda * *lambda).sqrt();
+            let upper = (t - *lambda * z) / c;
+            let ln_p = if self.is_two_sided() {
+                let lower = (-t - *lambda * z) / c;
+                // Probability of the interval, or one minus its tails.
+                let p = cdf_n01(upper).unwrap() - cdf_n01(lower).unwrap();
+                if p < half {
+                    p.max(T::zero()).ln()
+                } else {
+                    (-(cdf_n01(lower).unwrap() + sf_n01(upper).unwrap())).ln_1p()
+                }
+            } else {
+                log_cdf_n01(upper).unwrap()
+            };
+            acc + ln_p
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distribution::{Continuous, StudentsT};
+
+    #[test]
+    fn dunnett_values() {
+        // Tables of Dunnett (1955), 2.57 two-sided and 2.15 one-sided for two
+        // comparisons, ν = 10 and α = 0.05.
+        let two_sided = Dunnett::balanced(2, 10., TestTSide::TwoSided).unwrap();
+        assert!((two_sided.cdf(2.5683388760316364) - 0.95).abs() < 1e-12);
+        let one_sided = Dunnett::balanced(2, 10., TestTSide::UpperOneSided).unwrap();
+        assert!((one_sided.quantile(0.95).unwrap() - 2.1506138267513504).abs() < 1e-9);
+        assert!((one_sided.cdf(-1.) + one_sided.sf(-1.) - 1.).abs() < 1e-14);
+        let lower = Dunnett::balanced(2, 10., TestTSide::LowerOneSided).unwrap();
+        assert_eq!(
+            lower.cdf(2.1506138267513504),
+            one_sided.cdf(2.1506138267513504)
+        );
+        // A single comparison is a t-test.
+        let t = StudentsT::new(7.).unwrap();
+        let single = Dunnett::balanced(1, 7., TestTSide::TwoSided).unwrap();
+        assert!((single.sf(3.) - 2. * t.sf(3.)).abs() < 1e-14);
+        // Unbalanced groups, ρ_12 = (5/15 · 7/17)^(1/2).
+        let unbalanced = Dunnett::new(10, &[5, 7, 12], 30., TestTSide::TwoSided).unwrap();
+        let rho = unbalanced.correlation()[[0, 1]];
+        assert!((rho - (5. / 15. * 7. / 17f64).sqrt()).abs() < 1e-15);
+        assert!((unbalanced.cdf(2.4) - 0.9392947253212603).abs() < 1e-12);
+        assert_eq!(unbalanced.cdf(0.), 0.);
+        assert_eq!(unbalanced.sf(f64::INFINITY), 0.);
+        assert!(Dunnett::new(0, &[5], 10., TestTSide::TwoSided).is_none());
+        assert!(Dunnett::<f64>::new(5, &[], 10., TestTSide::TwoSided).is_none());
+    }
+}
@@ -0,0 +1,122 @@
+use num_traits::Float;
+use rand::Rng;
+
+use super::Sample;
+
+/// Empirical cumulative distribution function of a sample,
+/// F<sub>n</sub>(x) = #{x<sub>i</sub> ≤ x} / n.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ecdf<T> {
+    sorted: Vec<T>,
+}
+
+impl<T: Float> Ecdf<T> {
+    /// Returns `None` when `sample` is empty or contains NaN.
+    pub fn new<S>(sample: &S) -> Option<Self>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let mut sorted = sample.into_iter().copied().collect::<Vec<_>>();
+        if sorted.is_empty() || sorted.iter().any(|x| x.is_nan()) {
+            return None;
+        }
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Some(Self { sorted })
+    }
+
+    /// Number of observations n.
+    pub fn nobs(&self) -> usize {
+        self.sorted.len()
+    }
+
+    /// Sorted sample.
+    pub fn sorted(&self) -> &[T] {
+        &self.sorted
+    }
+
+    /// Evaluates F<sub>n</sub>(`x`).
+    pub fn eval(&self, x: T) -> T {
+        let count = self.sorted.partition_point(|xi| *xi <= x);
+        T::from(count).unwrap() / T::from(self.nobs()).unwrap()
+    }
+
+    /// Empirical quantile, the smallest observation x<sub>(k)</sub> with
+    /// F<sub>n</sub>(x<sub>(k)</sub>) ≥ `p` (type 1 of Hyndman and Fan).
+    /// Returns `None` when `p` is not in \[0, 1\].
+    pub fn quantile(&self, p: T) -> Option<T> {
+        if p.is_nan() || p < T::zero() || p > T::one() {
+            return None;
+        }
+        let n = self.nobs();
+        let nf = T::from(n).unwrap();
+        // As in R, a fuzz of a few epsilons keeps n p = k on the observation k.
+        let fuzz = T::from(4.).unwrap() * T::epsilon() * nf;
+        let k = (p * nf - fuzz).ceil().to_usize().unwrap_or(n);
+        Some(self.sorted[k.clamp(1, n) - 1])
+    }
+
+    /// Half-width ε = (ln(2/α) / 2n)<sup>1/2</sup> of the confidence band of
+    /// level 1 - α = `level` from the Dvoretzky-Kiefer-Wolfowitz inequality
+    /// with Massart's constant, P(sup |F<sub>n</sub> - F| > ε) ≤ α. Returns
+    /// `None` unless `level` is in (0, 1).
+    pub fn dkw_epsilon(&self, level: T) -> Option<T> {
+        let (zero, one) = (T::zero(), T::one());
+        if level.is_nan() || level <= zero || level >= one {
+            return None;
+        }
+        let two = one + one;
+        Some(((two / (one - level)).ln() / (two * T::from(self.nobs()).unwrap())).sqrt())
+    }
+
+    /// Lower and upper bounds at `x` of the simultaneous DKW confidence band
+    /// of level `level`, F<sub>n</sub>(x) ∓ ε clipped to \[0, 1\], see
+    /// [`Ecdf::dkw_epsilon`].
+    pub fn dkw_band(&self, x: T, level: T) -> Option<(T, T)> {
+        let epsilon = self.dkw_epsilon(level)?;
+        let f = self.eval(x);
+        Some(((f - epsilon).max(T::zero()), (f + epsilon).min(T::one())))
+    }
+}
+
+/// Draws uniformly among the observations, as in the nonparametric
+/// bootstrap.
+impl<T: Float> Sample<T> for Ecdf<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.sorted[rng.gen_range(0..self.sorted.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn ecdf_values() {
+        let ecdf = Ecdf::new(&[3., 1., 2., 2., 5.]).unwrap();
+        assert_eq!(ecdf.sorted(), &[1., 2., 2., 3., 5.]);
+        assert_eq!(ecdf.eval(0.5), 0.);
+        assert_eq!(ecdf.eval(2.), 0.6);
+        assert_eq!(ecdf.eval(4.9), 0.8);
+        assert_eq!(ecdf.eval(5.), 1.);
+        assert_eq!(ecdf.quantile(0.), Some(1.));
+        assert_eq!(ecdf.quantile(0.2), Some(1.));
+        assert_eq!(ecdf.quantile(0.21), Some(2.));
+        assert_eq!(ecdf.quantile(0.6), Some(2.));
+        assert_eq!(ecdf.quantile(1.), Some(5.));
+        assert_eq!(ecdf.quantile(1.5), None);
+        // ε = (ln 40 / 10)^(1/2)
+        let epsilon = ecdf.dkw_epsilon(0.95).unwrap();
+        assert!((epsilon - 0.6073614619083052).abs() < 1e-15);
+        assert_eq!(ecdf.dkw_band(2., 0.95).unwrap(), (0., 1.));
+        let large = Ecdf::new(&(0..1000).map(f64::from).collect::<Vec<_>>()).unwrap();
+        let (lower, upper) = large.dkw_band(499., 0.95).unwrap();
+        assert!((lower - (0.5 - 0.04294694083467376)).abs() < 1e-15);
+        assert!((upper - (0.5 + 0.04294694083467376)).abs() < 1e-15);
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!((0..100).all(|_| large.sample(&mut rng).fract() == 0.));
+        assert!(Ecdf::<f64>::new(&[]).is_none());
+        assert!(Ecdf::new(&[1., f64::NAN]).is_none());
+    }
+}
@@ -0,0 +1,226 @@
+use ndarray::{arr1, arr2};
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{
+    digamma, fit_output, from_f64, inc_gamma, inc_gamma_upper, lngamma, maximize, newton_quantile,
+    quantile_n01, standard_chi2, standard_gamma, to_f64, trigamma, Continuous, Fit, FitOutput,
+    Sample,
+};
+
+/// Gamma distribution with shape k = `shape` and scale θ = `scale`, of density
+/// x<sup>k-1</sup>e<sup>-x/θ</sup> / (Γ(k)θ<sup>k</sup>) on x > 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gamma<T> {
+    pub shape: T,
+    pub scale: T,
+}
+
+impl<T: Float> Gamma<T> {
+    /// Returns `None` unless `shape` and `scale` are positive and finite.
+    pub fn new(shape: T, scale: T) -> Option<Self> {
+        let valid = |p: T| p.is_finite() && p > T::zero();
+        if valid(shape) && valid(scale) {
+            Some(Self { shape, scale })
+        } else {
+            None
+        }
+    }
+}
+
+/// Estimates (`shape`, `scale`), starting from the approximate solution of ln
+/// k - ψ(k) = ln x̄ - mean(ln x) of [Minka (2002)][minka]. Requires positive
+/// values that are not all equal.
+///
+/// [minka]: https://tminka.github.io/papers/minka-gamma.pdf
+impl<T: Float + FloatConst> Fit<T> for Gamma<T> {
+    fn fit<S>(sample: &S) -> Option<FitOutput<Self, T>>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let zero = T::zero();
+        let (mut n, mut sum, mut sum_ln) = (0, zero, zero);
+        for &x in sample {
+            if !(x.is_finite() && x > zero) {
+                return None;
+            }
+            n += 1;
+            sum = sum + x;
+            sum_ln = sum_ln + x.ln();
+        }
+        let nf = T::from(n).unwrap();
+        let mean = sum / nf;
+        let s = mean.ln() - sum_ln / nf;
+        if s.is_nan() || s <= zero {
+            return None;
+        }
+        let (three, twelve) = (T::from(3.).unwrap(), T::from(12.).unwrap());
+        let shape =
+            (three - s + ((s - three).powi(2) + T::from(24.).unwrap() * s).sqrt()) / (twelve * s);
+        let (theta, loglik, hessian) = maximize(arr1(&[shape, mean / shape]), |p| {
+            let (k, scale) = (p[0], p[1]);
+            Self::new(k, scale)?;
+            let ln_scale = scale.ln();
+            let loglik = (k - T::one()) * sum_ln - sum / scale - nf * (k * ln_scale + lngamma(k));
+            let grad = arr1(&[
+                sum_ln - nf * (ln_scale + digamma(k)?),
+                (sum / scale - nf * k) / scale,
+            ]);
+            let cross = -nf / scale;
+            let hessian = arr2(&[
+                [-nf * trigamma(k)?, cross],
+                [cross, (nf * k - (sum + sum) / scale) / (scale * scale)],
+            ]);
+            Some((loglik, grad, hessian))
+        })?;
+        fit_output(Self::new(theta[0], theta[1])?, theta, &hessian, loglik, n)
+    }
+}
+
+/// The CDF is the regularized incomplete Gamma function P(X ≤ x) = P(k,
+/// x/θ).
+impl<T: Float + FloatConst> Continuous<T> for Gamma<T> {
+    fn support(&self) -> (T, T) {
+        (T::zero(), T::infinity())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let (zero, one) = (T::zero(), T::one());
+        if x < zero {
+            return T::neg_infinity();
+        }
+        if x == zero {
+            return if self.shape < one {
+                T::infinity()
+            } else if self.shape == one {
+                -self.scale.ln()
+            } else {
+                T::neg_infinity()
+            };
+        }
+        (self.shape - one) * x.ln()
+            - x / self.scale
+            - lngamma(self.shape)
+            - self.shape * self.scale.ln()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        if x <= T::zero() {
+            return T::zero();
+        }
+        inc_gamma(self.shape, x / self.scale).unwrap_or_else(T::nan)
+    }
+
+    fn sf(&self, x: T) -> T {
+        if x <= T::zero() {
+            return T::one();
+        }
+        inc_gamma_upper(self.shape, x / self.scale).unwrap_or_else(T::nan)
+    }
+
+    fn mean(&self) -> T {
+        self.shape * self.scale
+    }
+
+    fn variance(&self) -> T {
+        self.shape * self.scale * self.scale
+    }
+
+    /// Newton steps from the Wilson-Hilferty approximation k(1 - c + z
+    /// c<sup>1/2</sup>)<sup>3</sup>θ with c = 1/(9k) and z the normal
+    /// quantile, or from the lower tail approximation (p Γ(k + 1))<sup>1/k</sup>θ
+    /// when it is not positive.
+    fn quantile(&self, p: T) -> Option<T> {
+        let (zero, one) = (T::zero(), T::one());
+        if p.is_nan() || p < zero || p > one {
+            return None;
+        }
+        if p == zero {
+            return Some(zero);
+        }
+        if p == one {
+            return Some(T::infinity());
+        }
+        let k = self.shape;
+        let c = one / (T::from(9.).unwrap() * k);
+        let z = quantile_n01(p)?;
+        let wilson_hilferty = k * (one - c + z * c.sqrt()).powi(3);
+        let start = if wilson_hilferty > zero {
+            wilson_hilferty
+        } else {
+            ((p.ln() + lngamma(k + one)) / k).exp()
+        };
+        newton_quantile(self, p, start * self.scale)
+    }
+}
+
+/// Marsaglia-Tsang method, see [`standard_gamma`].
+impl<T: Float> Sample<T> for Gamma<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.scale * from_f64(standard_gamma(rng, to_f64(self.shape)))
+    }
+}
+
+/// Chi-squared distribution χ<sup>2</sup>(`df`), the Gamma(`df`/2, 2)
+/// distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChiSquared<T> {
+    pub df: T,
+}
+
+impl<T: Float> ChiSquared<T> {
+    /// Returns `None` unless `df` is positive and finite.
+    pub fn new(df: T) -> Option<Self> {
+        if df.is_finite() && df > T::zero() {
+            Some(Self { df })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Float> ChiSquared<T> {
+    fn as_gamma(&self) -> Gamma<T> {
+        let two = T::one() + T::one();
+        Gamma {
+            shape: self.df / two,
+            scale: two,
+        }
+    }
+}
+
+impl<T: Float + FloatConst> Continuous<T> for ChiSquared<T> {
+    fn support(&self) -> (T, T) {
+        (T::zero(), T::infinity())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        self.as_gamma().ln_pdf(x)
+    }
+
+    fn cdf(&self, x: T) -> T {
+        self.as_gamma().cdf(x)
+    }
+
+    fn sf(&self, x: T) -> T {
+        self.as_gamma().sf(x)
+    }
+
+    fn mean(&self) -> T {
+        self.df
+    }
+
+    fn variance(&self) -> T {
+        self.df + self.df
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        self.as_gamma().quantile(p)
+    }
+}
+
+impl<T: Float> Sample<T> for ChiSquared<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        from_f64(standard_chi2(rng, to_f64(self.df)))
+    }
+}
@@ -0,0 +1,380 @@
+use ndarray::{arr1, arr2};
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{
+    fit_output, from_f64, lngamma, maximize, open_unit, Continuous, Fit, FitOutput, Sample,
+    EULER_GAMMA,
+};
+
+/// Largest |ξ z| below which the derivatives in the shape use their series.
+const SHAPE_SERIES_LIMIT: f64 = 0.1;
+
+/// Maximum number of terms of the series in the shape.
+const SHAPE_SERIES_MAX_TERMS: usize = 60;
+
+/// Generalized extreme value distribution with location μ = `location`,
+/// scale σ = `scale` and shape ξ = `shape`, of CDF exp(-(1 + ξ
+/// z)<sup>-1/ξ</sup>) with z = (x - μ)/σ on 1 + ξ z > 0.
+///
+/// It is the limit law of normalized block maxima: ξ > 0 is the Fréchet
+/// (heavy tail) case, ξ < 0 the reversed Weibull case with an upper end
+/// point μ - σ/ξ, and ξ = 0 the [`Gumbel`](super::Gumbel) distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeneralizedExtremeValue<T> {
+    pub location: T,
+    pub scale: T,
+    pub shape: T,
+}
+
+impl<T: Float + FloatConst> GeneralizedExtremeValue<T> {
+    /// Returns `None` unless `location` and `shape` are finite and `scale` is
+    /// positive and finite.
+    pub fn new(location: T, scale: T, shape: T) -> Option<Self> {
+        if location.is_finite() && shape.is_finite() && scale.is_finite() && scale > T::zero() {
+            Some(Self {
+                location,
+                scale,
+                shape,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Probability weighted moments estimate of [Hosking, Wallis and Wood
+    /// (1985)][hww], from the unbiased estimates b<sub>r</sub> of
+    /// E\[X F(X)<sup>r</sup>\], r = 0, 1, 2, and their approximation of the
+    /// shape. Returns `None` when the sample has less than three values, a
+    /// value that is not finite or all its values equal.
+    ///
+    /// [hww]: https://doi.org/10.1080/00401706.1985.10488049
+    pub fn pwm<S>(sample: &S) -> Option<Self>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let (b0, b1, b2) = probability_weighted_moments(sample)?;
+        let (zero, one) = (T::zero(), T::one());
+        let two = one + one;
+        let ln2 = T::LN_2();
+        let c =
+            (two * b1 - b0) / (T::from(3.).unwrap() * b2 - b0) - ln2 / T::from(3.).unwrap().ln();
+        // Shape k = -ξ of Hosking's parametrization.
+        let k = T::from(7.859).unwrap() * c + T::from(2.9554).unwrap() * c * c;
+        if !k.is_finite() {
+            return None;
+        }
+        if k == zero {
+            let scale = (two * b1 - b0) / ln2;
+            return Self::new(b0 - T::from(EULER_GAMMA).unwrap() * scale, scale, zero);
+        }
+        let gamma = lngamma(one + k).exp();
+        let scale = (two * b1 - b0) * k / (gamma * -(-k * ln2).exp_m1());
+        Self::new(b0 + scale * (gamma - one) / k, scale, -k)
+    }
+
+    /// (z, 1 + ξ z, (1 + ξ z)<sup>-1/ξ</sup>) at `x`, with t ≤ 0 outside of
+    /// the support.
+    fn reduce(&self, x: T) -> (T, T, T) {
+        let z = (x - self.location) / self.scale;
+        let t = T::one() + self.shape * z;
+        (z, t, (-ln_1p_over(z, self.shape)).exp())
+    }
+}
+
+/// ln(1 + ξ `z`) / ξ, continuous at ξ = `shape` = 0.
+pub(crate) fn ln_1p_over<T: Float>(z: T, shape: T) -> T {
+    if shape == T::zero() {
+        z
+    } else {
+        (shape * z).ln_1p() / shape
+    }
+}
+
+/// (exp(ξ `a`) - 1) / ξ, continuous at ξ = `shape` = 0.
+pub(crate) fn exp_m1_over<T: Float>(a: T, shape: T) -> T {
+    if shape == T::zero() {
+        a
+    } else {
+        (shape * a).exp_m1() / shape
+    }
+}
+
+/// B = -∂(ln(1 + ξ z)/ξ)/∂ξ = ln(1 + ξ z)/ξ<sup>2</sup> - z/(ξ(1 + ξ z))
+/// at `z` and ξ = `shape`, and its derivative in ξ, by their Taylor series in
+/// ξ z where the closed forms cancel.
+pub(crate) fn shape_derivatives<T: Float>(z: T, shape: T) -> (T, T) {
+    let one = T::one();
+    let a = shape * z;
+    if a.abs() >= T::from(SHAPE_SERIES_LIMIT).unwrap() {
+        let t = one + a;
+        let y = a.ln_1p();
+        let xi2 = shape * shape;
+        let b = y / xi2 - z / (shape * t);
+        let db = (one + one) * (z / (xi2 * t) - y / (xi2 * shape)) + z * z / (shape * t * t);
+        return (b, db);
+    }
+    // B = z² ∑ (-a)^j (j + 1)/(j + 2) and B_ξ = -z³ ∑ (-a)^j (j + 1)(j + 2)/(j + 3).
+    let (mut b, mut db) = (T::zero(), T::zero());
+    let mut power = one;
+    for j in 0..SHAPE_SERIES_MAX_TERMS {
+        let jf = T::from(j).unwrap();
+        let (term_b, term_db) = (
+            power * (jf + one) / (jf + one + one),
+            power * (jf + one) * (jf + one + one) / (jf + T::from(3.).unwrap()),
+        );
+        b = b + term_b;
+        db = db + term_db;
+        if term_db.abs() <= T::epsilon() * db.abs() {
+            break;
+        }
+        power = -power * a;
+    }
+    (z * z * b, -z * z * z * db)
+}
+
+/// Unbiased estimates of the probability weighted moments E\[X
+/// F(X)<sup>r</sup>\], r = 0, 1, 2.
+fn probability_weighted_moments<T: Float, S>(sample: &S) -> Option<(T, T, T)>
+where
+    for<'a> &'a S: IntoIterator<Item = &'a T>,
+{
+    let mut sorted = Vec::new();
+    for &x in sample {
+        if !x.is_finite() {
+            return None;
+        }
+        sorted.push(x);
+    }
+    let n = sorted.len();
+    if n < 3 {
+        return None;
+    }
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    if sorted[0] == sorted[n - 1] {
+        return None;
+    }
+    let nf = T::from(n).unwrap();
+    let one = T::one();
+    let (mut b0, mut b1, mut b2) = (T::zero(), T::zero(), T::zero());
+    for (i, x) in sorted.iter().enumerate() {
+        let i = T::from(i).unwrap();
+        let w1 = i / (nf - one);
+        b0 = b0 + *x;
+        b1 = b1 + w1 * *x;
+        b2 = b2 + w1 * (i - one) / (nf - one - one) * *x;
+    }
+    Some((b0 / nf, b1 / nf, b2 / nf))
+}
+
+/// Estimates (`location`, `scale`, `shape`), starting from the probability
+/// weighted moments estimate, or from the moments of a Gumbel distribution
+/// when it excludes some of the values. Requires at least three values,
+/// not all equal. The estimates have the usual asymptotic properties for ξ >
+/// -1/2 only, and the likelihood is unbounded for ξ < -1.
+impl<T: Float + FloatConst> Fit<T> for GeneralizedExtremeValue<T> {
+    fn fit<S>(sample: &S) -> Option<FitOutput<Self, T>>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let (zero, one) = (T::zero(), T::one());
+        let values = sample.into_iter().copied().collect::<Vec<_>>();
+        let pwm = Self::pwm::<Vec<T>>(&values)?;
+        let n = values.len();
+        let start = if values.iter().all(|x| pwm.reduce(*x).1 > zero) {
+            pwm
+        } else {
+            let nf = T::from(n).unwrap();
+            let mean = values.iter().fold(zero, |acc, x| acc + *x) / nf;
+            let var = values.iter().fold(zero, |acc, x| acc + (*x - mean).powi(2)) / nf;
+            // Variance π²σ²/6 and mean μ + γσ of the Gumbel distribution.
+            let scale = (T::from(6.).unwrap() * var).sqrt() / T::PI();
+            Self::new(mean - T::from(EULER_GAMMA).unwrap() * scale, scale, zero)?
+        };
+        let start = arr1(&[start.location, start.scale, start.shape]);
+        let (theta, loglik, hessian) = maximize(start, |p| {
+            let dist = Self::new(p[0], p[1], p[2])?;
+            let (sigma, xi) = (p[1], p[2]);
+            // Sums of the log-density g(z, ξ) without -ln σ and of its
+            // derivatives.
+            let (mut g, mut gz, mut gxi) = (zero, zero, zero);
+            let (mut sum_z_gz, mut gzz, mut z_gzz, mut z2_gzz) = (zero, zero, zero, zero);
+            let (mut gzxi, mut z_gzxi, mut gxixi) = (zero, zero, zero);
+            for x in &values {
+                let (z, t, u) = dist.reduce(*x);
+                if t <= zero {
+                    return None;
+                }
+                let (b, db) = shape_derivatives(z, xi);
+                let a = one + xi - u;
+                g = g - (one + xi) * ln_1p_over(z, xi) - u;
+                let dz = -a / t;
+                let dzz = (xi * a - u) / (t * t);
+                let dzxi = -(one - u * b) / t + a * z / (t * t);
+                gz = gz + dz;
+                sum_z_gz = sum_z_gz + z * dz;
+                gzz = gzz + dzz;
+                z_gzz = z_gzz + z * dzz;
+                z2_gzz = z2_gzz + z * z * dzz;
+                gxi = gxi + (one - u) * b - z / t;
+                gzxi = gzxi + dzxi;
+                z_gzxi = z_gzxi + z * dzxi;
+                gxixi = gxixi - u * b * b + (one - u) * db + z * z / (t * t);
+            }
+            let nf = T::from(values.len()).unwrap();
+            let two = one + one;
+            let s2 = sigma * sigma;
+            let loglik = g - nf * sigma.ln();
+            let grad = arr1(&[-gz / sigma, -(nf + sum_z_gz) / sigma, gxi]);
+            let mu_sigma = (z_gzz + gz) / s2;
+            let hessian = arr2(&[
+                [gzz / s2, mu_sigma, -gzxi / sigma],
+                [
+                    mu_sigma,
+                    (nf + z2_gzz + two * sum_z_gz) / s2,
+                    -z_gzxi / sigma,
+                ],
+                [-gzxi / sigma, -z_gzxi / sigma, gxixi],
+            ]);
+            Some((loglik, grad, hessian))
+        })?;
+        fit_output(
+            Self::new(theta[0], theta[1], theta[2])?,
+            theta,
+            &hessian,
+            loglik,
+            n,
+        )
+    }
+}
+
+/// The mean is infinite for ξ ≥ 1 and the variance for ξ ≥ 1/2.
+impl<T: Float + FloatConst> Continuous<T> for GeneralizedExtremeValue<T> {
+    fn support(&self) -> (T, T) {
+        let end = self.location - self.scale / self.shape;
+        if self.shape > T::zero() {
+            (end, T::infinity())
+        } else if self.shape < T::zero() {
+            (T::neg_infinity(), end)
+        } else {
+            (T::neg_infinity(), T::infinity())
+        }
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let (z, t, u) = self.reduce(x);
+        if t <= T::zero() {
+            return T::neg_infinity();
+        }
+        -self.scale.ln() - (T::one() + self.shape) * ln_1p_over(z, self.shape) - u
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let (_, t, u) = self.reduce(x);
+        if t <= T::zero() {
+            return if self.shape > T::zero() {
+                T::zero()
+            } else {
+                T::one()
+            };
+        }
+        (-u).exp()
+    }
+
+    fn sf(&self, x: T) -> T {
+        let (_, t, u) = self.reduce(x);
+        if t <= T::zero() {
+            return if self.shape > T::zero() {
+                T::one()
+            } else {
+                T::zero()
+            };
+        }
+        -(-u).exp_m1()
+    }
+
+    fn mean(&self) -> T {
+        let (zero, one) = (T::zero(), T::one());
+        let xi = self.shape;
+        if xi >= one {
+            return T::infinity();
+        }
+        if xi == zero {
+            return self.location + self.scale * T::from(EULER_GAMMA).unwrap();
+        }
+        self.location + self.scale * lngamma(one - xi).exp_m1() / xi
+    }
+
+    fn variance(&self) -> T {
+        let (zero, one) = (T::zero(), T::one());
+        let xi = self.shape;
+        if xi == zero {
+            return (self.scale * T::PI()).powi(2) / T::from(6.).unwrap();
+        }
+        if xi + xi >= one {
+            return T::infinity();
+        }
+        let g1 = lngamma(one - xi).exp();
+        let g2 = lngamma(one - xi - xi).exp();
+        (self.scale / xi).powi(2) * (g2 - g1 * g1)
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        if p.is_nan() || p < T::zero() || p > T::one() {
+            return None;
+        }
+        let a = -(-p.ln()).ln();
+        Some(self.location + self.scale * exp_m1_over(a, self.shape))
+    }
+}
+
+/// Inversion of the CDF.
+impl<T: Float + FloatConst> Sample<T> for GeneralizedExtremeValue<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.quantile(from_f64(open_unit(rng))).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::lalonde::RE78,
+        test_util::{assert_fit, rel},
+    };
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn generalized_extreme_value_values() {
+        let gev = GeneralizedExtremeValue::new(0.5, 2., 0.2).unwrap();
+        assert!(rel(gev.pdf(2.5), 0.11203386432543155) < 1e-14);
+        assert!(rel(gev.cdf(2.5), 0.66906265266781882) < 1e-14);
+        assert!(rel(gev.mean(), 2.1422971372530337) < 1e-14);
+        assert!(rel(gev.variance(), 13.376142249191526) < 1e-13);
+        assert!(rel(gev.quantile(0.99).unwrap(), 15.593652817171567) < 1e-14);
+        assert_eq!(gev.cdf(-10.), 0.);
+        // ξ = 0 is the Gumbel distribution.
+        let gumbel = GeneralizedExtremeValue::new(0.5, 2., 0.).unwrap();
+        assert!(rel(gumbel.cdf(2.5), 0.69220062755534635) < 1e-14);
+        let near = GeneralizedExtremeValue::new(0.5, 2., 1e-9).unwrap();
+        assert!(rel(near.cdf(2.5), 0.69220062755534635) < 1e-8);
+        assert!(GeneralizedExtremeValue::new(0., 0., 0.1).is_none());
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn generalized_extreme_value_fit() {
+        // Maxima of blocks of 19 earnings, in thousands of dollars.
+        let earnings = RE78.iter().map(|x| *x as f64 / 1000.).collect::<Vec<_>>();
+        let maxima = earnings
+            .chunks(19)
+            .map(|block| block.iter().fold(f64::NEG_INFINITY, |a, b| a.max(*b)))
+            .collect::<Vec<_>>();
+        assert_fit(
+            &GeneralizedExtremeValue::fit(&maxima).unwrap(),
+            &[16.170068429088868, 4.6052301877831114, 0.26457928005752993],
+            &[0.83010894411398589, 0.68133348050061392, 0.1213082684368645],
+            -123.89354144625512,
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use ndarray::{arr1, arr2};
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{
+    exp_m1_over, fit_output, from_f64, ln_1p_over, maximize, open_unit, shape_derivatives,
+    Continuous, Fit, FitOutput, Sample,
+};
+
+/// Generalized Pareto distribution with location μ = `location`, scale σ =
+/// `scale` and shape ξ = `shape`, of survival function (1 + ξ
+/// z)<sup>-1/ξ</sup> with z = (x - μ)/σ on z ≥ 0 and 1 + ξ z > 0.
+///
+/// It is the limit law of the excesses over a high threshold: ξ > 0 gives a
+/// Pareto tail, ξ = 0 the exponential distribution and ξ < 0 an upper end
+/// point μ - σ/ξ.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeneralizedPareto<T> {
+    pub location: T,
+    pub scale: T,
+    pub shape: T,
+}
+
+impl<T: Float + FloatConst> GeneralizedPareto<T> {
+    /// Returns `None` unless `location` and `shape` are finite and `scale` is
+    /// positive and finite.
+    pub fn new(location: T, scale: T, shape: T) -> Option<Self> {
+        if location.is_finite() && shape.is_finite() && scale.is_finite() && scale > T::zero() {
+            Some(Self {
+                location,
+                scale,
+                shape,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Probability weighted moments estimate of [Hosking and Wallis
+    /// (1987)][hw] from a sample of excesses, with location 0 and the
+    /// plotting positions (i - 0.35)/n. Returns `None` when the sample has
+    /// less than two values, a negative or infinite value or all its values
+    /// equal.
+    ///
+    /// [hw]: https://doi.org/10.1080/00401706.1987.10488243
+    pub fn pwm<S>(sample: &S) -> Option<Self>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let mut sorted = Vec::new();
+        for &x in sample {
+            if !(x.is_finite() && x >= T::zero()) {
+                return None;
+            }
+            sorted.push(x);
+        }
+        let n = sorted.len();
+        if n < 2 {
+            return None;
+        }
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        if sorted[0] == sorted[n - 1] {
+            return None;
+        }
+        let nf = T::from(n).unwrap();
+        let offset = T::from(0.35).unwrap();
+        let (mut a0, mut a1) = (T::zero(), T::zero());
+        for (i, x) in sorted.iter().enumerate() {
+            // Estimates of E[X] and E[X (1 - F(X))].
+            let p = (T::from(i + 1).unwrap() - offset) / nf;
+            a0 = a0 + *x;
+            a1 = a1 + (T::one() - p) * *x;
+        }
+        let (a0, a1) = (a0 / nf, a1 / nf);
+        let d = a0 - (a1 + a1);
+        let two = T::one() + T::one();
+        Self::new(T::zero(), two * a0 * a1 / d, two - a0 / d)
+    }
+
+    /// (z, 1 + ξ z) at `x`.
+    fn reduce(&self, x: T) -> (T, T) {
+        let z = (x - self.location) / self.scale;
+        (z, T::one() + self.shape * z)
+    }
+}
+
+/// Estimates (`scale`, `shape`) from a sample of excesses over a threshold,
+/// the location being 0, starting from the probability weighted moments
+/// estimate, or from the exponential distribution when it excludes some of
+/// the values. Requires non-negative values, at least two and not all equal.
+/// The estimates have the usual asymptotic properties for ξ > -1/2 only,
+/// and the likelihood is unbounded for ξ < -1.
+impl<T: Float + FloatConst> Fit<T> for GeneralizedPareto<T> {
+    fn fit<S>(sample: &S) -> Option<FitOutput<Self, T>>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let (zero, one) = (T::zero(), T::one());
+        let values = sample.into_iter().copied().collect::<Vec<_>>();
+        let pwm = Self::pwm::<Vec<T>>(&values)?;
+        let n = values.len();
+        let nf = T::from(n).unwrap();
+        let start = if values.iter().all(|x| pwm.reduce(*x).1 > zero) {
+            pwm
+        } else {
+            let mean = values.iter().fold(zero, |acc, x| acc + *x) / nf;
+            Self::new(zero, mean, zero)?
+        };
+        let (theta, loglik, hessian) = maximize(arr1(&[start.scale, start.shape]), |p| {
+            let dist = Self::new(zero, p[0], p[1])?;
+            let (sigma, xi) = (p[0], p[1]);
+            // Sums of the log-density g(z, ξ) without -ln σ and of its
+            // derivatives.
+            let (mut g, mut z_gz, mut z2_gzz, mut gxi, mut z_gzxi, mut gxixi) =
+                (zero, zero, zero, zero, zero, zero);
+            for x in &values {
+                let (z, t) = dist.reduce(*x);
+                if t <= zero {
+                    return None;
+                }
+                let (b, db) = shape_derivatives(z, xi);
+                g = g - (one + xi) * ln_1p_over(z, xi);
+                z_gz = z_gz - (one + xi) * z / t;
+                z2_gzz = z2_gzz + (one + xi) * xi * z * z / (t * t);
+                gxi = gxi + b - z / t;
+                z_gzxi = z_gzxi - z / t + (one + xi) * z * z / (t * t);
+                gxixi = gxixi + db + z * z / (t * t);
+            }
+            let s2 = sigma * sigma;
+            let loglik = g - nf * sigma.ln();
+            let grad = arr1(&[-(nf + z_gz) / sigma, gxi]);
+            let hessian = arr2(&[
+                [(nf + z2_gzz + (one + one) * z_gz) / s2, -z_gzxi / sigma],
+                [-z_gzxi / sigma, gxixi],
+            ]);
+            Some((loglik, grad, hessian))
+        })?;
+        fit_output(
+            Self::new(zero, theta[0], theta[1])?,
+            theta,
+            &hessian,
+            loglik,
+            n,
+        )
+    }
+}
+
+/// The mean is infinite for ξ ≥ 1 and the variance for ξ ≥ 1/2.
+impl<T: Float + FloatConst> Continuous<T> for GeneralizedPareto<T> {
+    fn support(&self) -> (T, T) {
+        if self.shape < T::zero() {
+            (self.location, self.location - self.scale / self.shape)
+        } else {
+            (self.location, T::infinity())
+        }
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let (z, t) = self.reduce(x);
+        if z < T::zero() || t <= T::zero() {
+            return T::neg_infinity();
+        }
+        -self.scale.ln() - (T::one() + self.shape) * ln_1p_over(z, self.shape)
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let (z, t) = self.reduce(x);
+        if z <= T::zero() {
+            return T::zero();
+        }
+        if t <= T::zero() {
+            return T::one();
+        }
+        -(-ln_1p_over(z, self.shape)).exp_m1()
+    }
+
+    fn sf(&self, x: T) -> T {
+        let (z, t) = self.reduce(x);
+        if z <= T::zero() {
+            return T::one();
+        }
+        if t <= T::zero() {
+            return T::zero();
+        }
+        (-ln_1p_over(z, self.shape)).exp()
+    }
+
+    fn mean(&self) -> T {
+        let one = T::one();
+        if self.shape >= one {
+            return T::infinity();
+        }
+        self.location + self.scale / (one - self.shape)
+    }
+
+    fn variance(&self) -> T {
+        let one = T::one();
+        let xi = self.shape;
+        if xi + xi >= one {
+            return T::infinity();
+        }
+        (self.scale / (one - xi)).powi(2) / (one - xi - xi)
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        if p.is_nan() || p < T::zero() || p > T::one() {
+            return None;
+        }
+        let a = -(-p).ln_1p();
+        Some(self.location + self.scale * exp_m1_over(a, self.shape))
+    }
+}
+
+/// Inversion of the CDF.
+impl<T: Float + FloatConst> Sample<T> for GeneralizedPareto<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.quantile(from_f64(open_unit(rng))).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::lalonde::RE78,
+        test_util::{assert_fit, rel},
+    };
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn generalized_pareto_values() {
+        let gpd = GeneralizedPareto::new(1., 2., 0.2).unwrap();
+        assert!(rel(gpd.pdf(3.5), 0.131072) < 1e-14);
+        assert!(rel(gpd.sf(3.5), 0.32768) < 1e-14);
+        assert!(rel(gpd.quantile(0.99).unwrap(), 16.118864315095801) < 1e-14);
+        // ξ = 0 is the exponential distribution.
+        let exponential = GeneralizedPareto::new(1., 2., 0.).unwrap();
+        assert!(rel(exponential.sf(3.5), (-1.25f64).exp()) < 1e-15);
+        let bounded = GeneralizedPareto::new(0., 2., -0.5).unwrap();
+        assert_eq!(bounded.support(), (0., 4.));
+        assert_eq!(bounded.cdf(4.5), 1.);
+        assert!(rel(bounded.cdf(3.), 0.9375) < 1e-15);
+        assert!(GeneralizedPareto::new(0., -1., 0.1).is_none());
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn generalized_pareto_fit() {
+        // Excesses of the earnings over 15, in thousands of dollars.
+        let excesses = RE78
+            .iter()
+            .map(|x| *x as f64 / 1000.)
+            .filter(|x| *x > 15.)
+            .map(|x| x - 15.)
+            .collect::<Vec<_>>();
+        assert_fit(
+            &GeneralizedPareto::fit(&excesses).unwrap(),
+            &[4.629308418287819, 0.24148504223704639],
+            &[1.0190549075602816, 0.16982131430177039],
+            -133.14684141495213,
+        );
+        assert!(GeneralizedPareto::fit(&[-1., 2., 3.]).is_none());
+    }
+}
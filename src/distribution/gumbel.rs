@@ -0,0 +1,86 @@
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{from_f64, open_unit, Continuous, Sample};
+
+/// Euler-Mascheroni constant.
+#[allow(clippy::excessive_precision)]
+pub(crate) const EULER_GAMMA: f64 = 0.57721566490153286061;
+
+/// Gumbel (maximum extreme value) distribution with location μ = `location`
+/// and scale β = `scale`, of CDF e<sup>-e<sup>-(x - μ)/β</sup></sup>.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gumbel<T> {
+    pub location: T,
+    pub scale: T,
+}
+
+impl<T: Float> Gumbel<T> {
+    /// Returns `None` unless `location` is finite and `scale` is positive and
+    /// finite.
+    pub fn new(location: T, scale: T) -> Option<Self> {
+        if location.is_finite() && scale.is_finite() && scale > T::zero() {
+            Some(Self { location, scale })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Float + FloatConst> Continuous<T> for Gumbel<T> {
+    fn support(&self) -> (T, T) {
+        (T::neg_infinity(), T::infinity())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let z = (x - self.location) / self.scale;
+        -self.scale.ln() - z - (-z).exp()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let z = (x - self.location) / self.scale;
+        (-(-z).exp()).exp()
+    }
+
+    fn sf(&self, x: T) -> T {
+        let z = (x - self.location) / self.scale;
+        -(-(-z).exp()).exp_m1()
+    }
+
+    fn mean(&self) -> T {
+        self.location + self.scale * T::from(EULER_GAMMA).unwrap()
+    }
+
+    fn variance(&self) -> T {
+        (self.scale * T::PI()).powi(2) / T::from(6.).unwrap()
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        if p.is_nan() || p < T::zero() || p > T::one() {
+            return None;
+        }
+        Some(self.location - self.scale * (-p.ln()).ln())
+    }
+}
+
+/// Inversion of the CDF.
+impl<T: Float + FloatConst> Sample<T> for Gumbel<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.quantile(from_f64(open_unit(rng))).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn gumbel_values() {
+        let gumbel = Gumbel::new(0.5, 2.).unwrap();
+        assert!(rel(gumbel.pdf(2.5), 0.12732319002179125) < 1e-14);
+        assert!(rel(gumbel.cdf(2.5), 0.69220062755534635) < 1e-14);
+        assert!(rel(gumbel.quantile(0.69220062755534635).unwrap(), 2.5) < 1e-14);
+    }
+}
@@ -0,0 +1,139 @@
+use num_traits::{Float, FloatConst};
+
+use super::{ln_binomial, Discrete};
+
+/// Hypergeometric distribution of the number of successes among `draws`
+/// draws without replacement from a population of size `population`
+/// containing `successes` successes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hypergeometric {
+    pub population: u64,
+    pub successes: u64,
+    pub draws: u64,
+}
+
+impl Hypergeometric {
+    /// Returns `None` when `population` is zero or when `successes` or
+    /// `draws` exceed `population`.
+    pub fn new(population: u64, successes: u64, draws: u64) -> Option<Self> {
+        if population > 0 && successes <= population && draws <= population {
+            Some(Self {
+                population,
+                successes,
+                draws,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Mode ⌊(draws + 1)(successes + 1) / (population + 2)⌋.
+    fn mode(&self) -> u64 {
+        let mode =
+            (self.draws as u128 + 1) * (self.successes as u128 + 1) / (self.population as u128 + 2);
+        mode as u64
+    }
+}
+
+/// The PMF is C(K, k) C(N - K, n - k) / C(N, n). Without a closed form for
+/// the CDF, the probabilities are summed over the tail that does not contain
+/// the mode, and the other tail is obtained by complement.
+impl<T: Float + FloatConst> Discrete<T> for Hypergeometric {
+    fn support(&self) -> (u64, Option<u64>) {
+        let lower = (self.draws + self.successes).saturating_sub(self.population);
+        (lower, Some(self.draws.min(self.successes)))
+    }
+
+    fn ln_pmf(&self, k: u64) -> T {
+        let (lower, upper) = <Self as Discrete<T>>::support(self);
+        if k < lower || k > upper.unwrap() {
+            return T::neg_infinity();
+        }
+        let t = |v: u64| T::from(v).unwrap();
+        let (n, big_k, draws) = (t(self.population), t(self.successes), t(self.draws));
+        let k = t(k);
+        ln_binomial(big_k, k).unwrap() + ln_binomial(n - big_k, draws - k).unwrap()
+            - ln_binomial(n, draws).unwrap()
+    }
+
+    fn cdf(&self, k: u64) -> T {
+        let (lower, upper) = <Self as Discrete<T>>::support(self);
+        let upper = upper.unwrap();
+        if k >= upper {
+            T::one()
+        } else if k < lower {
+            T::zero()
+        } else if k < self.mode() {
+            (lower..=k).fold(T::zero(), |acc, j| acc + self.pmf(j))
+        } else {
+            T::one() - <Self as Discrete<T>>::sf(self, k)
+        }
+    }
+
+    fn sf(&self, k: u64) -> T {
+        let (lower, upper) = <Self as Discrete<T>>::support(self);
+        let upper = upper.unwrap();
+        if k >= upper {
+            T::zero()
+        } else if k < lower {
+            T::one()
+        } else if k >= self.mode() {
+            (k + 1..=upper).fold(T::zero(), |acc, j| acc + self.pmf(j))
+        } else {
+            T::one() - <Self as Discrete<T>>::cdf(self, k)
+        }
+    }
+
+    fn mean(&self) -> T {
+        let t = |v: u64| T::from(v).unwrap();
+        t(self.draws) * t(self.successes) / t(self.population)
+    }
+
+    fn variance(&self) -> T {
+        let t = |v: u64| T::from(v).unwrap();
+        let (n, big_k, draws) = (t(self.population), t(self.successes), t(self.draws));
+        let one = T::one();
+        if n <= one {
+            return T::zero();
+        }
+        draws * (big_k / n) * ((n - big_k) / n) * ((n - draws) / (n - one))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn hypergeometric_values() {
+        let hyper = Hypergeometric::new(50, 10, 12).unwrap();
+        assert!(rel(hyper.pmf(3), 0.27028632539455461) < 1e-13);
+        assert!(rel(hyper.cdf(3), 0.82094352279402885) < 1e-13);
+        assert!(rel(hyper.sf(3), 0.17905647720597115) < 1e-13);
+        let sum = (0..=10)
+            .map(|k| Discrete::<f64>::pmf(&hyper, k))
+            .sum::<f64>();
+        assert!((sum - 1.).abs() < 1e-14);
+        let far: f64 = Hypergeometric::new(500, 100, 60).unwrap().sf(30);
+        assert!(rel(far, 3.516091016102035e-9) < 1e-12);
+        assert_eq!(hyper.quantile(0.82094352279402885), Some(3));
+        assert_eq!(hyper.quantile(0.83), Some(4));
+        assert_eq!(
+            <Hypergeometric as Discrete<f64>>::support(&hyper),
+            (0, Some(10))
+        );
+        let full = Hypergeometric::new(10, 8, 5).unwrap();
+        assert_eq!(
+            <Hypergeometric as Discrete<f64>>::support(&full),
+            (3, Some(5))
+        );
+        assert_eq!(Discrete::<f64>::pmf(&full, 2), 0.);
+        assert!(Hypergeometric::new(10, 11, 5).is_none());
+        assert!(Hypergeometric::new(0, 0, 0).is_none());
+        let single = Hypergeometric::new(1, 1, 1).unwrap();
+        assert_eq!(Discrete::<f64>::mean(&single), 1.);
+        assert_eq!(Discrete::<f64>::variance(&single), 0.);
+    }
+}
@@ -0,0 +1,119 @@
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{from_f64, open_unit, Continuous, Sample};
+
+/// Laplace (double exponential) distribution with location μ = `location`
+/// and scale b = `scale`, of density e<sup>-|x - μ|/b</sup> / (2b).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Laplace<T> {
+    pub location: T,
+    pub scale: T,
+}
+
+impl<T: Float> Laplace<T> {
+    /// Returns `None` unless `location` is finite and `scale` is positive and
+    /// finite.
+    pub fn new(location: T, scale: T) -> Option<Self> {
+        if location.is_finite() && scale.is_finite() && scale > T::zero() {
+            Some(Self { location, scale })
+        } else {
+            None
+        }
+    }
+
+    /// Maximum likelihood estimate, the sample median and the mean absolute
+    /// deviation around it. Returns `None` for an empty or constant sample.
+    pub fn mle<S>(sample: &S) -> Option<Self>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let mut sorted = sample.into_iter().copied().collect::<Vec<T>>();
+        if sorted.is_empty() || sorted.iter().any(|x| x.is_nan()) {
+            return None;
+        }
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = sorted.len();
+        let location = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / T::from(2.).unwrap()
+        };
+        let scale = sorted
+            .iter()
+            .fold(T::zero(), |acc, x| acc + (*x - location).abs())
+            / T::from(n).unwrap();
+        Self::new(location, scale)
+    }
+}
+
+impl<T: Float + FloatConst> Continuous<T> for Laplace<T> {
+    fn support(&self) -> (T, T) {
+        (T::neg_infinity(), T::infinity())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        -((x - self.location).abs() / self.scale) - (self.scale + self.scale).ln()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let z = (x - self.location) / self.scale;
+        let half = T::from(0.5).unwrap();
+        if z < T::zero() {
+            half * z.exp()
+        } else {
+            T::one() - half * (-z).exp()
+        }
+    }
+
+    fn sf(&self, x: T) -> T {
+        self.cdf(self.location + self.location - x)
+    }
+
+    fn mean(&self) -> T {
+        self.location
+    }
+
+    fn variance(&self) -> T {
+        T::from(2.).unwrap() * self.scale * self.scale
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        let (zero, one) = (T::zero(), T::one());
+        if p.is_nan() || p < zero || p > one {
+            return None;
+        }
+        let half = T::from(0.5).unwrap();
+        if p < half {
+            Some(self.location + self.scale * (p + p).ln())
+        } else {
+            Some(self.location - self.scale * ((one - p) + (one - p)).ln())
+        }
+    }
+}
+
+/// Inversion of the CDF.
+impl<T: Float + FloatConst> Sample<T> for Laplace<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.quantile(from_f64(open_unit(rng))).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn laplace_values() {
+        let laplace = Laplace::new(1., 2.).unwrap();
+        assert!(rel(laplace.pdf(2.5), 0.11809163818525368) < 1e-14);
+        assert!(rel(laplace.cdf(2.5), 0.76381672362949265) < 1e-14);
+        assert!(rel(laplace.sf(-0.5), 0.76381672362949265) < 1e-14);
+        assert!(rel(laplace.quantile(0.76381672362949265).unwrap(), 2.5) < 1e-14);
+        let fitted = Laplace::mle(&[1., 7., 2., 3.]).unwrap();
+        assert_eq!(fitted.location, 2.5);
+        assert_eq!(fitted.scale, 1.75);
+    }
+}
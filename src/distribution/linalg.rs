@@ -0,0 +1,70 @@
+use ndarray::{Array1, Array2, ArrayBase, Data, Ix2};
+use num_traits::Float;
+
+/// Lower triangular Cholesky factor L of a symmetric matrix A = L
+/// L<sup>T</sup>. Returns `None` when A is not positive definite.
+pub(crate) fn cholesky<T: Float>(a: &Array2<T>) -> Option<Array2<T>> {
+    let n = a.nrows();
+    let mut l = Array2::zeros((n, n));
+    for j in 0..n {
+        let mut d = a[[j, j]];
+        for k in 0..j {
+            d = d - l[[j, k]] * l[[j, k]];
+        }
+        if d.is_nan() || d <= T::zero() {
+            return None;
+        }
+        let d = d.sqrt();
+        l[[j, j]] = d;
+        for i in j + 1..n {
+            let mut s = a[[i, j]];
+            for k in 0..j {
+                s = s - l[[i, k]] * l[[j, k]];
+            }
+            l[[i, j]] = s / d;
+        }
+    }
+    Some(l)
+}
+
+/// Solves A x = `b` given the Cholesky factor `l` of A.
+pub(crate) fn cholesky_solve<T: Float>(l: &Array2<T>, b: &Array1<T>) -> Array1<T> {
+    let n = l.nrows();
+    let mut x = b.clone();
+    for i in 0..n {
+        for k in 0..i {
+            x[i] = x[i] - l[[i, k]] * x[k];
+        }
+        x[i] = x[i] / l[[i, i]];
+    }
+    for i in (0..n).rev() {
+        for k in i + 1..n {
+            x[i] = x[i] - l[[k, i]] * x[k];
+        }
+        x[i] = x[i] / l[[i, i]];
+    }
+    x
+}
+
+/// Inverse of A given its Cholesky factor `l`.
+pub(crate) fn cholesky_inverse<T: Float>(l: &Array2<T>) -> Array2<T> {
+    let n = l.nrows();
+    let mut inv = Array2::zeros((n, n));
+    for j in 0..n {
+        let mut e = Array1::zeros(n);
+        e[j] = T::one();
+        inv.column_mut(j).assign(&cholesky_solve(l, &e));
+    }
+    inv
+}
+
+/// Matrix product A B of `a` and `b`, whose dimensions must match.
+pub(crate) fn mat_mul<T: Float, S1, S2>(a: &ArrayBase<S1, Ix2>, b: &ArrayBase<S2, Ix2>) -> Array2<T>
+where
+    S1: Data<Elem = T>,
+    S2: Data<Elem = T>,
+{
+    Array2::from_shape_fn((a.nrows(), b.ncols()), |(i, j)| {
+        (0..a.ncols()).fold(T::zero(), |acc, k| acc + a[[i, k]] * b[[k, j]])
+    })
+}
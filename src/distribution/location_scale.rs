@@ -0,0 +1,78 @@
+use num_traits::Float;
+use rand::Rng;
+
+use super::{Continuous, Sample};
+
+/// Location-scale family generated by a distribution, the law of `location`
+/// + `scale` X with X distributed as `base`.
+///
+/// For instance `LocationScale::new(StudentsT::new(df)?, location, scale)`
+/// is the t-distribution used as a heavy-tailed alternative to the normal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocationScale<D, T> {
+    pub base: D,
+    pub location: T,
+    pub scale: T,
+}
+
+impl<D, T: Float> LocationScale<D, T> {
+    /// Returns `None` unless `location` is finite and `scale` is positive and
+    /// finite.
+    pub fn new(base: D, location: T, scale: T) -> Option<Self> {
+        if location.is_finite() && scale.is_finite() && scale > T::zero() {
+            Some(Self {
+                base,
+                location,
+                scale,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn standardize(&self, x: T) -> T {
+        (x - self.location) / self.scale
+    }
+}
+
+impl<D: Continuous<T>, T: Float> Continuous<T> for LocationScale<D, T> {
+    fn support(&self) -> (T, T) {
+        let (lower, upper) = self.base.support();
+        (
+            self.location + self.scale * lower,
+            self.location + self.scale * upper,
+        )
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        self.base.ln_pdf(self.standardize(x)) - self.scale.ln()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        self.base.cdf(self.standardize(x))
+    }
+
+    fn sf(&self, x: T) -> T {
+        self.base.sf(self.standardize(x))
+    }
+
+    fn mean(&self) -> T {
+        self.location + self.scale * self.base.mean()
+    }
+
+    fn variance(&self) -> T {
+        self.scale * self.scale * self.base.variance()
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        self.base
+            .quantile(p)
+            .map(|q| self.location + self.scale * q)
+    }
+}
+
+impl<D: Sample<T>, T: Float> Sample<T> for LocationScale<D, T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.location + self.scale * self.base.sample(rng)
+    }
+}
@@ -0,0 +1,81 @@
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{from_f64, open_unit, Continuous, Sample};
+
+/// Logistic distribution with location `location` and scale `scale`, of CDF
+/// 1 / (1 + e<sup>-(x - location) / scale</sup>).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Logistic<T> {
+    pub location: T,
+    pub scale: T,
+}
+
+impl<T: Float> Logistic<T> {
+    /// Returns `None` unless `location` is finite and `scale` is positive and
+    /// finite.
+    pub fn new(location: T, scale: T) -> Option<Self> {
+        if location.is_finite() && scale.is_finite() && scale > T::zero() {
+            Some(Self { location, scale })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Float + FloatConst> Continuous<T> for Logistic<T> {
+    fn support(&self) -> (T, T) {
+        (T::neg_infinity(), T::infinity())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let z = ((x - self.location) / self.scale).abs();
+        -z - T::from(2.).unwrap() * (-z).exp().ln_1p() - self.scale.ln()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        T::one() / (T::one() + (-(x - self.location) / self.scale).exp())
+    }
+
+    fn sf(&self, x: T) -> T {
+        T::one() / (T::one() + ((x - self.location) / self.scale).exp())
+    }
+
+    fn mean(&self) -> T {
+        self.location
+    }
+
+    fn variance(&self) -> T {
+        (self.scale * T::PI()).powi(2) / T::from(3.).unwrap()
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        if p.is_nan() || p < T::zero() || p > T::one() {
+            return None;
+        }
+        Some(self.location + self.scale * (p.ln() - (-p).ln_1p()))
+    }
+}
+
+/// Inversion of the CDF.
+impl<T: Float + FloatConst> Sample<T> for Logistic<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.quantile(from_f64(open_unit(rng))).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn logistic_values() {
+        let logistic = Logistic::new(1., 2.).unwrap();
+        assert!(rel(logistic.pdf(2.5), 0.10894749688090701) < 1e-14);
+        assert!(rel(logistic.cdf(2.5), 0.67917869917539297) < 1e-14);
+        assert!(rel(logistic.sf(80.), 7.0043520261686452e-18) < 1e-14);
+        assert!((logistic.quantile(0.67917869917539297).unwrap() - 2.5).abs() < 1e-14);
+    }
+}
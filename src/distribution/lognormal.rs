@@ -0,0 +1,113 @@
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{cdf_n01, from_f64, quantile_n01, sf_n01, standard_normal, Continuous, Sample};
+
+/// Log-normal distribution, the law of e<sup>Y</sup> with Y ∼ N(`mu`,
+/// `sigma`<sup>2</sup>).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogNormal<T> {
+    pub mu: T,
+    pub sigma: T,
+}
+
+impl<T: Float> LogNormal<T> {
+    /// Returns `None` unless `mu` is finite and `sigma` is positive and finite.
+    pub fn new(mu: T, sigma: T) -> Option<Self> {
+        if mu.is_finite() && sigma.is_finite() && sigma > T::zero() {
+            Some(Self { mu, sigma })
+        } else {
+            None
+        }
+    }
+
+    /// Maximum likelihood estimate, the mean and the (biased) standard
+    /// deviation of the logarithms of the sample. Returns `None` when the
+    /// sample has a non-positive value or less than two distinct values.
+    pub fn mle<S>(sample: &S) -> Option<Self>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let logs = sample
+            .into_iter()
+            .map(|x| if *x > T::zero() { Some(x.ln()) } else { None })
+            .collect::<Option<Vec<T>>>()?;
+        let n = T::from(logs.len()).unwrap();
+        let mu = logs.iter().fold(T::zero(), |acc, y| acc + *y) / n;
+        let var = logs
+            .iter()
+            .fold(T::zero(), |acc, y| acc + (*y - mu).powi(2))
+            / n;
+        Self::new(mu, var.sqrt())
+    }
+
+    fn z(&self, x: T) -> T {
+        (x.ln() - self.mu) / self.sigma
+    }
+}
+
+impl<T: Float + FloatConst> Continuous<T> for LogNormal<T> {
+    fn support(&self) -> (T, T) {
+        (T::zero(), T::infinity())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        if x <= T::zero() {
+            return T::neg_infinity();
+        }
+        let z = self.z(x);
+        -T::from(0.5).unwrap() * (z * z + (T::PI() + T::PI()).ln()) - (x * self.sigma).ln()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        if x <= T::zero() {
+            return T::zero();
+        }
+        cdf_n01(self.z(x)).unwrap()
+    }
+
+    fn sf(&self, x: T) -> T {
+        if x <= T::zero() {
+            return T::one();
+        }
+        sf_n01(self.z(x)).unwrap()
+    }
+
+    fn mean(&self) -> T {
+        (self.mu + self.sigma * self.sigma / T::from(2.).unwrap()).exp()
+    }
+
+    fn variance(&self) -> T {
+        let s2 = self.sigma * self.sigma;
+        s2.exp_m1() * (self.mu + self.mu + s2).exp()
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        quantile_n01(p).map(|z| (self.mu + self.sigma * z).exp())
+    }
+}
+
+impl<T: Float> Sample<T> for LogNormal<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        (self.mu + self.sigma * from_f64(standard_normal(rng))).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn lognormal_values() {
+        let lognormal = LogNormal::new(0.3, 0.8).unwrap();
+        assert!(rel(lognormal.pdf(2.5), 0.14825587554199163) < 1e-14);
+        assert!(rel(lognormal.cdf(2.5), 0.77945782551163639) < 1e-14);
+        assert!(rel(lognormal.quantile(0.9).unwrap(), 3.7630931008738346) < 1e-14);
+        let fitted = LogNormal::mle(&[1., 2., 4.]).unwrap();
+        assert!(rel(fitted.mu, 2f64.ln()) < 1e-15);
+        assert!(rel(fitted.sigma, (2f64 / 3.).sqrt() * 2f64.ln()) < 1e-15);
+        assert!(LogNormal::mle(&[1., -2.]).is_none());
+    }
+}
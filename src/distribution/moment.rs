@@ -1,12 +1,19 @@
-pub(crate) fn mean<S, T>(sample: &S) -> Option<(T, T)>
+use std::borrow::Borrow;
+
+use num_traits::Float;
+
+/// Computes the mean of `sample` and its size n. Returns `None` when the
+/// sample is empty.
+pub(crate) fn mean<T, I>(sample: I) -> Option<(T, T)>
 where
-    T: num_traits::Float,
-    for<'a> &'a S: IntoIterator<Item = &'a T>,
+    T: Float,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
 {
     let mut m = T::zero();
     let mut n = T::zero();
     for x in sample {
-        m = m + *x;
+        m = m + *x.borrow();
         n = n + T::one();
     }
     if n > T::zero() {
@@ -16,26 +23,51 @@ where
     }
 }
 
-pub(crate) fn variance<S, T>(sample: &S) -> Option<(T, T, T)>
+/// Computes the unbiased variance ∑(x<sub>i</sub> - x̄)<sup>2</sup>/(n - 1)
+/// of `sample`, its mean x̄ and its size n in one pass, with the updates of
+/// Welford (1962). Returns `None` when the sample has less than two values.
+pub(crate) fn variance<T, I>(sample: I) -> Option<(T, T, T)>
 where
-    T: num_traits::Float,
-    for<'a> &'a S: IntoIterator<Item = &'a T>,
+    T: Float,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
 {
-    let (mean, mut n) = if let Some((m, s)) = mean(sample) {
-        (m, s)
-    } else {
-        return None;
-    };
-    let mut var = T::zero();
+    let mut mean = T::zero();
+    let mut n = T::zero();
+    // Sum of the squared deviations from the current mean.
+    let mut m2 = T::zero();
     for x in sample {
-        var = var + (*x - mean).powi(2);
+        let x = *x.borrow();
         n = n + T::one();
+        let delta = x - mean;
+        mean = mean + delta / n;
+        m2 = m2 + delta * (x - mean);
     }
     if n > T::one() {
-        let mean = mean / n;
-        let var = (T::one() / n) * var;
-        Some((var, mean, n))
+        Some((m2 / (n - T::one()), mean, n))
     } else {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr1;
+
+    use super::*;
+
+    #[test]
+    fn moment_values() {
+        let sample = [2., 4., 4., 4., 5., 5., 7., 9.];
+        assert_eq!(mean(sample), Some((5., 8.)));
+        assert_eq!(variance(sample), Some((32. / 7., 5., 8.)));
+        // Iterators of values, ndarray views and a large offset.
+        let shifted = sample.iter().map(|x| x + 1e9);
+        let (var, m, n) = variance::<f64, _>(shifted).unwrap();
+        assert!((var - 32. / 7.).abs() < 1e-6);
+        assert_eq!((m, n), (1e9 + 5., 8.));
+        assert_eq!(variance::<f64, _>(arr1(&sample).view()), variance(sample));
+        assert!(variance::<f64, _>(&[1.]).is_none());
+        assert!(mean::<f64, _>(&[]).is_none());
+    }
+}
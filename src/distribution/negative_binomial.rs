@@ -0,0 +1,155 @@
+use num_traits::{Float, FloatConst};
+
+use super::{inc_beta_xy, ln_beta, Discrete};
+
+/// Negative binomial distribution of the number of failures before the `r`-th
+/// success in independent trials with success probability `p`. The size `r`
+/// may be any positive real number, as in count-data regressions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NegativeBinomial<T> {
+    pub r: T,
+    pub p: T,
+}
+
+impl<T: Float> NegativeBinomial<T> {
+    /// Returns `None` unless `r` is positive and finite and `p` is in (0, 1\].
+    pub fn new(r: T, p: T) -> Option<Self> {
+        if r.is_finite() && r > T::zero() && p > T::zero() && p <= T::one() {
+            Some(Self { r, p })
+        } else {
+            None
+        }
+    }
+}
+
+/// The PMF is Γ(k + r) / (Γ(r) k!) p<sup>r</sup>(1 - p)<sup>k</sup> and the CDF
+/// is the regularized incomplete Beta function P(X ≤ k) = I<sub>p</sub>(r, k +
+/// 1).
+impl<T: Float + FloatConst> Discrete<T> for NegativeBinomial<T> {
+    fn support(&self) -> (u64, Option<u64>) {
+        (0, None)
+    }
+
+    fn ln_pmf(&self, k: u64) -> T {
+        let k = T::from(k).unwrap();
+        if self.p == T::one() {
+            return if k == T::zero() {
+                T::zero()
+            } else {
+                T::neg_infinity()
+            };
+        }
+        // Γ(k + r) / (Γ(r) k!) = 1 / ((k + r) B(r, k + 1))
+        let ln_coef = -(k + self.r).ln() - ln_beta(self.r, k + T::one()).unwrap();
+        ln_coef + self.r * self.p.ln() + k * (-self.p).ln_1p()
+    }
+
+    fn cdf(&self, k: u64) -> T {
+        let k = T::from(k).unwrap();
+        inc_beta_xy(self.p, T::one() - self.p, self.r, k + T::one()).unwrap()
+    }
+
+    fn sf(&self, k: u64) -> T {
+        let k = T::from(k).unwrap();
+        inc_beta_xy(T::one() - self.p, self.p, k + T::one(), self.r).unwrap()
+    }
+
+    fn mean(&self) -> T {
+        self.r * (T::one() - self.p) / self.p
+    }
+
+    fn variance(&self) -> T {
+        self.r * (T::one() - self.p) / (self.p * self.p)
+    }
+}
+
+/// Geometric distribution of the number of failures before the first success
+/// in independent trials with success probability `p`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geometric<T> {
+    pub p: T,
+}
+
+impl<T: Float> Geometric<T> {
+    /// Returns `None` unless `p` is in (0, 1\].
+    pub fn new(p: T) -> Option<Self> {
+        if p > T::zero() && p <= T::one() {
+            Some(Self { p })
+        } else {
+            None
+        }
+    }
+}
+
+/// P(X > k) = (1 - p)<sup>k+1</sup>.
+impl<T: Float + FloatConst> Discrete<T> for Geometric<T> {
+    fn support(&self) -> (u64, Option<u64>) {
+        (0, None)
+    }
+
+    fn ln_pmf(&self, k: u64) -> T {
+        let k = T::from(k).unwrap();
+        if self.p == T::one() {
+            return if k == T::zero() {
+                T::zero()
+            } else {
+                T::neg_infinity()
+            };
+        }
+        self.p.ln() + k * (-self.p).ln_1p()
+    }
+
+    fn cdf(&self, k: u64) -> T {
+        let k = T::from(k).unwrap();
+        -((k + T::one()) * (-self.p).ln_1p()).exp_m1()
+    }
+
+    fn sf(&self, k: u64) -> T {
+        let k = T::from(k).unwrap();
+        ((k + T::one()) * (-self.p).ln_1p()).exp()
+    }
+
+    fn mean(&self) -> T {
+        (T::one() - self.p) / self.p
+    }
+
+    fn variance(&self) -> T {
+        (T::one() - self.p) / (self.p * self.p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn negative_binomial_values() {
+        let nb = NegativeBinomial::new(2.5, 0.4).unwrap();
+        assert!(rel(nb.pmf(3), 0.14344091466523769) < 1e-14);
+        assert!(rel(nb.cdf(3), 0.5558019215511944) < 1e-14);
+        assert!(rel(nb.sf(3), 0.4441980784488056) < 1e-14);
+        let small_size = NegativeBinomial::new(0.3, 0.2).unwrap();
+        assert!(rel(small_size.pmf(5), 0.021446307373769391) < 1e-14);
+        assert!(rel(small_size.cdf(5), 0.94197632159418565) < 1e-14);
+        assert_eq!(nb.quantile(0.5558019215511944), Some(3));
+        assert_eq!(nb.quantile(0.56), Some(4));
+        assert!(NegativeBinomial::new(0., 0.5).is_none());
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn geometric_values() {
+        let geometric = Geometric::new(0.2).unwrap();
+        assert!(rel(geometric.pmf(4), 0.08192000000000002) < 1e-14);
+        assert!(rel(geometric.cdf(4), 0.67231999999999991) < 1e-14);
+        let nb = NegativeBinomial::new(1., 0.2).unwrap();
+        for k in [0, 3, 17] {
+            assert!(rel(geometric.pmf(k), nb.pmf(k)) < 1e-14);
+            assert!(rel(geometric.sf(k), nb.sf(k)) < 1e-13);
+        }
+        assert_eq!(geometric.quantile(0.67231999999999991), Some(4));
+        assert_eq!(Geometric::new(1.).unwrap().quantile(0.9), Some(0));
+    }
+}
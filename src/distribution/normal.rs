@@ -1,32 +1,190 @@
-use num_traits::NumCast;
-
-/// Computes the cumulative distribution function of the standard normal using
-/// the formula of [Dia (2023)][paper]. The number `x` should be convertible to
-/// `f64`.
-///
-/// [paper]: https://papers.ssrn.com/sol3/papers.cfm?abstract_id=4487559
-pub(crate) fn cdf_n01<T: num_traits::Float>(x: T) -> Option<T> {
-    let abs_x = if let Some(x) = <f64 as NumCast>::from(x) {
-        x
-    } else {
+use ndarray::{arr1, Array2};
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{
+    erfc, erfcinv, erfcx, fit_output, from_f64, standard_normal, Continuous, Fit, FitOutput, Sample,
+};
+
+/// Normal distribution N(μ, σ<sup>2</sup>) with mean μ = `mean` and standard
+/// deviation σ = `std_dev`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal<T> {
+    pub mean: T,
+    pub std_dev: T,
+}
+
+impl<T: Float> Normal<T> {
+    /// Returns `None` unless `mean` is finite and `std_dev` is positive and
+    /// finite.
+    pub fn new(mean: T, std_dev: T) -> Option<Self> {
+        if mean.is_finite() && std_dev.is_finite() && std_dev > T::zero() {
+            Some(Self { mean, std_dev })
+        } else {
+            None
+        }
+    }
+
+    /// Maximum likelihood estimate, the sample mean and the (biased) sample
+    /// standard deviation. Returns `None` for a sample with less than two
+    /// distinct values.
+    pub fn mle<S>(sample: &S) -> Option<Self>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let (sum, n) = sample
+            .into_iter()
+            .fold((T::zero(), T::zero()), |(s, n), x| (s + *x, n + T::one()));
+        let mean = sum / n;
+        let var = sample
+            .into_iter()
+            .fold(T::zero(), |acc, x| acc + (*x - mean).powi(2))
+            / n;
+        Self::new(mean, var.sqrt())
+    }
+}
+
+/// Closed form: the estimates (`mean`, `std_dev`) are [`Normal::mle`] and the
+/// observed information is diag(n/σ<sup>2</sup>, 2n/σ<sup>2</sup>).
+impl<T: Float + FloatConst> Fit<T> for Normal<T> {
+    fn fit<S>(sample: &S) -> Option<FitOutput<Self, T>>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let normal = Self::mle(sample)?;
+        let n = sample.into_iter().count();
+        let nf = T::from(n).unwrap();
+        let var = normal.std_dev * normal.std_dev;
+        let loglik = -T::from(0.5).unwrap() * nf * (((T::PI() + T::PI()) * var).ln() + T::one());
+        let hessian = Array2::from_diag(&arr1(&[-nf / var, -(nf + nf) / var]));
+        let estimates = arr1(&[normal.mean, normal.std_dev]);
+        fit_output(normal, estimates, &hessian, loglik, n)
+    }
+}
+
+impl<T: Float + FloatConst> Continuous<T> for Normal<T> {
+    fn support(&self) -> (T, T) {
+        (T::neg_infinity(), T::infinity())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let z = (x - self.mean) / self.std_dev;
+        -T::from(0.5).unwrap() * (z * z + (T::PI() + T::PI()).ln()) - self.std_dev.ln()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        cdf_n01((x - self.mean) / self.std_dev).unwrap_or_else(T::nan)
+    }
+
+    fn sf(&self, x: T) -> T {
+        sf_n01((x - self.mean) / self.std_dev).unwrap_or_else(T::nan)
+    }
+
+    fn mean(&self) -> T {
+        self.mean
+    }
+
+    fn variance(&self) -> T {
+        self.std_dev * self.std_dev
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        quantile_n01(p).map(|z| self.mean + self.std_dev * z)
+    }
+}
+
+/// Ziggurat method, see [`standard_normal`].
+impl<T: Float> Sample<T> for Normal<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        self.mean + self.std_dev * from_f64(standard_normal(rng))
+    }
+}
+
+/// Computes the cumulative distribution function Φ of the standard normal
+/// through the complementary error function, Φ(x) = erfc(-x/√2) / 2, so that
+/// the lower tail keeps its relative accuracy far below 1e-16. Returns `None`
+/// when `x` is NaN.
+pub fn cdf_n01<T: Float + FloatConst>(x: T) -> Option<T> {
+    if x.is_nan() {
         return None;
-    };
-    let x2 = abs_x.powi(2);
-    let one_minus_cdf_abs_x = (0.39894228040143268 / (abs_x + 2.92678600515804815))
-        * ((x2 + 8.42742300458043240 * abs_x + 18.38871225773938487)
-            / (x2 + 5.81582518933527391 * abs_x + 8.97280659046817350))
-        * ((x2 + 7.30756258553673541 * abs_x + 18.25323235347346525)
-            / (x2 + 5.70347935898051437 * abs_x + 10.27157061171363079))
-        * ((x2 + 5.66479518878470765 * abs_x + 18.61193318971775795)
-            / (x2 + 5.51862483025707963 * abs_x + 12.72323261907760928))
-        * ((x2 + 4.91396098895240075 * abs_x + 24.14804072812762821)
-            / (x2 + 5.26184239579604207 * abs_x + 16.88639562007936908))
-        * ((x2 + 3.83362947800146179 * abs_x + 11.61511226260603247)
-            / (x2 + 4.92081346632882033 * abs_x + 24.12333774572479110))
-        * ((-x2 / 2.).exp());
-    if x > T::zero() {
-        T::from(1. - one_minus_cdf_abs_x)
+    }
+    Some(erfc(-x * T::FRAC_1_SQRT_2()) / T::from(2.).unwrap())
+}
+
+/// Computes the survival function 1 - Φ(x) of the standard normal, accurate
+/// in the upper tail, see [`cdf_n01`].
+pub fn sf_n01<T: Float + FloatConst>(x: T) -> Option<T> {
+    cdf_n01(-x)
+}
+
+/// Computes ln Φ(x), the logarithm of the cumulative distribution function of
+/// the standard normal, without underflow in the lower tail where ln Φ(x) =
+/// -x<sup>2</sup>/2 + ln(erfcx(-x/√2) / 2).
+pub fn log_cdf_n01<T: Float + FloatConst>(x: T) -> Option<T> {
+    if x.is_nan() {
+        return None;
+    }
+    let zero = T::zero();
+    let one = T::one();
+    if x < -one {
+        let two = one + one;
+        Some(-x * x / two + (erfcx(-x * T::FRAC_1_SQRT_2()) / two).ln())
+    } else if x < zero {
+        cdf_n01(x).map(|cdf| cdf.ln())
     } else {
-        T::from(one_minus_cdf_abs_x)
+        sf_n01(x).map(|sf| (-sf).ln_1p())
+    }
+}
+
+/// Computes ln(1 - Φ(x)), the logarithm of the survival function of the
+/// standard normal, see [`log_cdf_n01`].
+pub fn log_sf_n01<T: Float + FloatConst>(x: T) -> Option<T> {
+    log_cdf_n01(-x)
+}
+
+/// Computes the quantile function Φ<sup>-1</sup>(p) = -√2 erfcinv(2p) of the
+/// standard normal. Returns `None` when `p` is not in \[0, 1\].
+pub fn quantile_n01<T: Float + FloatConst>(p: T) -> Option<T> {
+    if p.is_nan() || p < T::zero() || p > T::one() {
+        return None;
+    }
+    Some(-T::SQRT_2() * erfcinv(p + p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    fn cdf_n01_values() {
+        // Φ from mpmath.ncdf at 30 digits.
+        assert!(rel(cdf_n01(-3.).unwrap(), 1.349898031630095e-3) < 1e-13);
+        assert!(rel(cdf_n01(-1.).unwrap(), 0.15865525393145705) < 1e-14);
+        assert!(rel(cdf_n01(0.5).unwrap(), 0.6914624612740131) < 1e-14);
+        assert!(rel(cdf_n01(1.).unwrap(), 0.8413447460685429) < 1e-14);
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn normal_tails() {
+        assert!(rel(sf_n01(9.).unwrap(), 1.1285884059538406e-19) < 1e-13);
+        assert!(rel(cdf_n01(-38.).unwrap(), 2.8854283600687843e-316) < 1e-12);
+        assert!((cdf_n01(0.).unwrap() - 0.5).abs() < 1e-16);
+        for (x, expected) in [
+            (-40., -804.60844201375379),
+            (-5., -15.064998393988726),
+            (-0.5, -1.1759117615936186),
+            (3., -0.0013508099647481938),
+            (10., -7.6198530241605261e-24),
+        ] {
+            assert!(rel(log_cdf_n01(x).unwrap(), expected) < 1e-14);
+        }
+        assert!(rel(log_sf_n01(38.).unwrap(), -726.55721601882013) < 1e-14);
+        assert!(rel(quantile_n01(1e-20).unwrap(), -9.2623400897984076) < 1e-14);
+        assert!(rel(quantile_n01(0.975).unwrap(), 1.9599639845400539) < 1e-14);
+        assert!(rel(quantile_n01(0.025).unwrap(), -1.9599639845400542) < 1e-14);
+        assert!(cdf_n01(f64::NAN).is_none());
+        assert!(quantile_n01(1.2).is_none());
     }
 }
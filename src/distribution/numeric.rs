@@ -0,0 +1,342 @@
+use num_traits::{Float, FloatConst};
+
+use super::{from_f64, lngamma};
+
+/// Maximum number of subintervals of [`integrate`].
+const INTEGRATE_MAX_INTERVALS: usize = 200;
+
+/// Maximum number of iterations of [`find_root`].
+const ROOT_MAX_ITER: usize = 200;
+
+/// Nodes of the 15-point Kronrod rule on \[-1, 1\], the odd ones being the
+/// nodes of the 7-point Gauss rule.
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_4,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_5,
+    0.,
+];
+
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_22,
+    0.063_092_092_629_978_55,
+    0.104_790_010_322_250_2,
+    0.140_653_259_715_525_9,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_4,
+    0.204_432_940_075_298_9,
+    0.209_482_141_084_727_8,
+];
+
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_7,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+/// Gauss-Kronrod (7, 15) estimate of the integral of `f` on \[`a`, `b`\] and
+/// its error, the difference with the Gauss rule scaled as in QUADPACK.
+fn kronrod<T: Float, F: FnMut(T) -> T>(f: &mut F, a: T, b: T) -> (T, T) {
+    let half = T::from(0.5).unwrap();
+    let (centre, radius) = (half * (a + b), half * (b - a));
+    let mut values = [T::zero(); 15];
+    values[7] = f(centre);
+    for j in 0..7 {
+        let dx = radius * from_f64(KRONROD_NODES[j]);
+        values[j] = f(centre - dx);
+        values[14 - j] = f(centre + dx);
+    }
+    let weight = |j: usize| from_f64::<T>(KRONROD_WEIGHTS[j.min(14 - j)]);
+    let mut kronrod = T::zero();
+    let mut gauss = values[7] * from_f64(GAUSS_WEIGHTS[3]);
+    for (j, value) in values.iter().enumerate() {
+        kronrod = kronrod + *value * weight(j);
+    }
+    for j in (1..7).step_by(2) {
+        gauss = gauss + (values[j] + values[14 - j]) * from_f64(GAUSS_WEIGHTS[j / 2]);
+    }
+    // Mean absolute deviation of f, the scale of the error.
+    let mean = half * kronrod;
+    let deviation = values
+        .iter()
+        .enumerate()
+        .fold(T::zero(), |acc, (j, value)| {
+            acc + weight(j) * (*value - mean).abs()
+        });
+    let mut error = ((kronrod - gauss) * radius).abs();
+    let scale = deviation * radius.abs();
+    if scale > T::zero() && error > T::zero() {
+        let ratio = T::from(200.).unwrap() * error / scale;
+        error = scale * ratio.powf(T::from(1.5).unwrap()).min(T::one());
+    }
+    (kronrod * radius, error)
+}
+
+/// Integral of `f` on \[`points`<sub>0</sub>, `points`<sub>m</sub>\] by
+/// globally adaptive Gauss-Kronrod quadrature, starting from the subintervals
+/// between consecutive breakpoints `points` and bisecting the subinterval of
+/// largest error until the total error is below max(`abs_tol`, `rel_tol`
+/// |integral|). Returns the integral and the error estimate.
+pub(crate) fn integrate<T, F>(mut f: F, points: &[T], abs_tol: T, rel_tol: T) -> (T, T)
+where
+    T: Float,
+    F: FnMut(T) -> T,
+{
+    let mut intervals = points
+        .windows(2)
+        .map(|w| {
+            let (value, error) = kronrod(&mut f, w[0], w[1]);
+            (w[0], w[1], value, error)
+        })
+        .collect::<Vec<_>>();
+    let total = |intervals: &[(T, T, T, T)]| {
+        intervals
+            .iter()
+            .fold((T::zero(), T::zero()), |(v, e), interval| {
+                (v + interval.2, e + interval.3)
+            })
+    };
+    let (mut value, mut error) = total(&intervals);
+    while intervals.len() < INTEGRATE_MAX_INTERVALS {
+        if error <= abs_tol.max(rel_tol * value.abs()) || error.is_nan() {
+            break;
+        }
+        let worst = (0..intervals.len())
+            .max_by(|&i, &j| intervals[i].3.partial_cmp(&intervals[j].3).unwrap())
+            .unwrap();
+        let (lo, hi, v, e) = intervals.swap_remove(worst);
+        let mid = T::from(0.5).unwrap() * (lo + hi);
+        let (v1, e1) = kronrod(&mut f, lo, mid);
+        let (v2, e2) = kronrod(&mut f, mid, hi);
+        value = value - v + v1 + v2;
+        error = error - e + e1 + e2;
+        intervals.push((lo, mid, v1, e1));
+        intervals.push((mid, hi, v2, e2));
+    }
+    // Sums again to remove the rounding errors of the updates.
+    total(&intervals)
+}
+
+/// Relative accuracy targeted by the numerical integrals of the
+/// distribution functions, ε<sup>2/3</sup> for the machine epsilon ε.
+pub(crate) fn integration_tol<T: Float>() -> T {
+    T::epsilon().powf(T::from(2. / 3.).unwrap())
+}
+
+/// Expectation E\[f(Z)\] for Z ∼ N(0, 1), where the mass of f φ lies
+/// within `spread` + 9 of the origin, split in panels of width
+/// about 1.5.
+pub(crate) fn normal_expectation<T, F>(mut f: F, spread: T) -> T
+where
+    T: Float + FloatConst,
+    F: FnMut(T) -> T,
+{
+    let half = T::from(0.5).unwrap();
+    let norm = (T::PI() + T::PI()).sqrt();
+    let limit = T::from(9.).unwrap() + spread.abs();
+    let panels = (limit / T::from(1.5).unwrap()).ceil();
+    let points = (0..=2 * panels.to_usize().unwrap_or(6))
+        .map(|j| limit * (T::from(j).unwrap() / panels - T::one()))
+        .collect::<Vec<_>>();
+    let integrand = |z: T| {
+        let density = (-half * z * z).exp() / norm;
+        if density == T::zero() {
+            T::zero()
+        } else {
+            f(z) * density
+        }
+    };
+    integrate(integrand, &points, T::zero(), integration_tol()).0
+}
+
+/// Expectation E\[f(S)\] for S = (V/ν)<sup>1/2</sup> with V ∼
+/// χ<sup>2</sup>(ν) and ν = `df`, f(1) when ν is infinite.
+pub(crate) fn chi_expectation<T, F>(mut f: F, df: T) -> T
+where
+    T: Float + FloatConst,
+    F: FnMut(T) -> T,
+{
+    if df.is_infinite() {
+        return f(T::one());
+    }
+    let (zero, one) = (T::zero(), T::one());
+    let half = T::from(0.5).unwrap();
+    let two = one + one;
+    // Density 2 (ν/2)^(ν/2) s^(ν-1) e^(-ν s²/2) / Γ(ν/2).
+    let constant = two.ln() + half * df * (half * df).ln() - lngamma(half * df);
+    let density = |s: T| {
+        if s <= zero {
+            zero
+        } else {
+            (constant + (df - one) * s.ln() - half * df * s * s).exp()
+        }
+    };
+    // Panels of widths doubling away from the mode, in units of about the
+    // standard deviation, up to twelve standard deviations or so of V in the
+    // right tail.
+    let width = (one / (two * df).sqrt()).min(half);
+    let twelve = T::from(12.).unwrap();
+    let upper = (one + (twelve * (two * df).sqrt() + T::from(80.).unwrap()) / df).sqrt();
+    let mut points = vec![zero];
+    points.extend(
+        [-8., -4., -2., -1., 0., 1., 2., 4., 8.]
+            .iter()
+            .map(|j| one + from_f64::<T>(*j) * width)
+            .filter(|s| *s > zero && *s < upper),
+    );
+    points.push(upper);
+    let integrand = |s: T| {
+        let d = density(s);
+        if d == zero {
+            zero
+        } else {
+            f(s) * d
+        }
+    };
+    integrate(integrand, &points, zero, integration_tol()).0
+}
+
+/// Quantile of a distribution on (`lower`, ∞) given by its CDF and survival
+/// function, bracketed by steps from `start` and refined by [`find_root`].
+/// Returns `None` when `p` is not in \[0, 1\].
+pub(crate) fn invert_cdf<T, C, S>(p: T, lower: T, start: T, cdf: C, sf: S) -> Option<T>
+where
+    T: Float,
+    C: Fn(T) -> T,
+    S: Fn(T) -> T,
+{
+    let (zero, one) = (T::zero(), T::one());
+    if p.is_nan() || p < zero || p > one {
+        return None;
+    }
+    if p == zero {
+        return Some(lower);
+    }
+    if p == one {
+        return Some(T::infinity());
+    }
+    let half = T::from(0.5).unwrap();
+    // Increasing function whose root is the quantile.
+    let f = |x: T| {
+        if p <= half {
+            cdf(x) - p
+        } else {
+            (one - p) - sf(x)
+        }
+    };
+    let mut step = start.abs().max(one);
+    let (mut lo, mut hi) = (start, start);
+    while f(hi) < zero {
+        lo = hi;
+        hi = hi + step;
+        step = step + step;
+        if !hi.is_finite() {
+            return Some(hi);
+        }
+    }
+    if lo == hi {
+        step = start.abs().max(one);
+        while lo > lower && f(lo) > zero {
+            hi = lo;
+            lo = (lo - step).max(lower);
+            step = step + step;
+            if !lo.is_finite() {
+                return Some(lo);
+            }
+        }
+    }
+    let tol = T::epsilon().powf(T::from(2. / 3.).unwrap()) * hi.abs().max(lo.abs());
+    find_root(f, lo, hi, tol)
+}
+
+/// Root of `f` in the bracket \[`a`, `b`\] by Brent's method, up to an
+/// absolute error `tol`. Returns `None` unless f(`a`) and f(`b`) have
+/// opposite signs.
+pub(crate) fn find_root<T, F>(mut f: F, a: T, b: T, tol: T) -> Option<T>
+where
+    T: Float,
+    F: FnMut(T) -> T,
+{
+    let (zero, two) = (T::zero(), T::from(2.).unwrap());
+    let half = T::from(0.5).unwrap();
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if fa == zero {
+        return Some(a);
+    }
+    if fb == zero {
+        return Some(b);
+    }
+    if fa.is_nan() || fb.is_nan() || (fa > zero) == (fb > zero) {
+        return None;
+    }
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut e = d;
+    for _ in 0..ROOT_MAX_ITER {
+        if (fb > zero) == (fc > zero) {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+        let tol1 = two * T::epsilon() * b.abs() + half * tol;
+        let m = half * (c - b);
+        if m.abs() <= tol1 || fb == zero {
+            return Some(b);
+        }
+        if e.abs() >= tol1 && fa.abs() > fb.abs() {
+            // Secant or inverse quadratic interpolation.
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (two * m * s, T::one() - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (two * m * q * (q - r) - (b - a) * (r - T::one())),
+                    (q - T::one()) * (r - T::one()) * (s - T::one()),
+                )
+            };
+            if p > zero {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if two * p < (T::from(3.).unwrap() * m * q - (tol1 * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = m;
+                e = m;
+            }
+        } else {
+            d = m;
+            e = m;
+        }
+        a = b;
+        fa = fb;
+        b = if d.abs() > tol1 {
+            b + d
+        } else if m > zero {
+            b + tol1
+        } else {
+            b - tol1
+        };
+        fb = f(b);
+    }
+    Some(b)
+}
@@ -0,0 +1,127 @@
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{from_f64, open_unit, Continuous, Sample};
+
+/// Pareto (type I) distribution with scale x<sub>m</sub> = `scale` and shape α
+/// = `shape`, of survival function (x<sub>m</sub>/x)<sup>α</sup> on x ≥
+/// x<sub>m</sub>.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pareto<T> {
+    pub scale: T,
+    pub shape: T,
+}
+
+impl<T: Float> Pareto<T> {
+    /// Returns `None` unless `scale` and `shape` are positive and finite.
+    pub fn new(scale: T, shape: T) -> Option<Self> {
+        let valid = |p: T| p.is_finite() && p > T::zero();
+        if valid(scale) && valid(shape) {
+            Some(Self { scale, shape })
+        } else {
+            None
+        }
+    }
+
+    /// Maximum likelihood estimate x<sub>m</sub> = min x<sub>i</sub> and α = n
+    /// / ∑ ln(x<sub>i</sub>/x<sub>m</sub>). Returns `None` when the sample has
+    /// a non-positive value or less than two distinct values.
+    pub fn mle<S>(sample: &S) -> Option<Self>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let mut scale = T::infinity();
+        let mut n = 0usize;
+        for x in sample {
+            if *x <= T::zero() {
+                return None;
+            }
+            scale = scale.min(*x);
+            n += 1;
+        }
+        let sum_logs = sample
+            .into_iter()
+            .fold(T::zero(), |acc, x| acc + (*x / scale).ln());
+        Self::new(scale, T::from(n).unwrap() / sum_logs)
+    }
+}
+
+/// The mean is infinite for α ≤ 1 and the variance for α ≤ 2.
+impl<T: Float + FloatConst> Continuous<T> for Pareto<T> {
+    fn support(&self) -> (T, T) {
+        (self.scale, T::infinity())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        if x < self.scale {
+            return T::neg_infinity();
+        }
+        self.shape.ln() + self.shape * self.scale.ln() - (self.shape + T::one()) * x.ln()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        if x <= self.scale {
+            return T::zero();
+        }
+        -(self.shape * (self.scale / x).ln()).exp_m1()
+    }
+
+    fn sf(&self, x: T) -> T {
+        if x <= self.scale {
+            return T::one();
+        }
+        (self.scale / x).powf(self.shape)
+    }
+
+    fn mean(&self) -> T {
+        let one = T::one();
+        if self.shape <= one {
+            return T::infinity();
+        }
+        self.shape * self.scale / (self.shape - one)
+    }
+
+    fn variance(&self) -> T {
+        let one = T::one();
+        let two = one + one;
+        if self.shape <= two {
+            return T::infinity();
+        }
+        let a1 = self.shape - one;
+        self.scale * self.scale * self.shape / (a1 * a1 * (self.shape - two))
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        if p.is_nan() || p < T::zero() || p > T::one() {
+            return None;
+        }
+        Some(self.scale * (-(-p).ln_1p() / self.shape).exp())
+    }
+}
+
+/// Inversion of the CDF.
+impl<T: Float + FloatConst> Sample<T> for Pareto<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        let u: T = from_f64(open_unit(rng));
+        self.scale * (-u.ln() / self.shape).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    fn pareto_values() {
+        let pareto = Pareto::new(1.5, 3.).unwrap();
+        assert!(rel(pareto.pdf(2.5), 0.2592) < 1e-14);
+        assert!(rel(pareto.cdf(2.5), 0.784) < 1e-14);
+        assert!(rel(pareto.quantile(0.784).unwrap(), 2.5) < 1e-14);
+        assert_eq!(Pareto::new(1., 0.5).unwrap().mean(), f64::INFINITY);
+        let fitted = Pareto::mle(&[2., 4., 8.]).unwrap();
+        assert_eq!(fitted.scale, 2.);
+        assert!(rel(fitted.shape, 1. / 2f64.ln()) < 1e-15);
+        assert!(Pareto::mle(&[3., 3.]).is_none());
+    }
+}
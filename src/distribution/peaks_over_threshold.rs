@@ -0,0 +1,273 @@
+use ndarray::{arr1, Array1};
+use num_traits::{Float, FloatConst};
+
+use super::{quantile_n01, Fit, FitOutput, GeneralizedPareto};
+
+/// Largest |ξ ln((1 - p)/ζ)| below which the derivatives in the shape use
+/// their series.
+const RISK_SERIES_LIMIT: f64 = 0.1;
+
+/// Maximum number of terms of the series in the shape.
+const RISK_SERIES_MAX_TERMS: usize = 40;
+
+/// Mean of the excesses over a threshold, a point of the mean residual life
+/// plot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeanExcess<T> {
+    /// Threshold u.
+    pub threshold: T,
+    /// Mean of the excesses x - u of the values x > u.
+    pub mean_excess: T,
+    /// Standard error of the mean excess, the standard deviation of the
+    /// excesses over the square root of their number.
+    pub std_error: T,
+    /// Number of values above the threshold.
+    pub exceedances: usize,
+}
+
+/// Computes the mean residual life plot of `sample` at each of the
+/// `thresholds`, skipping the thresholds exceeded by less than two values.
+///
+/// Above a threshold u<sub>0</sub> where the excesses follow a generalized
+/// Pareto distribution of shape ξ < 1, the mean excess σ<sub>u</sub>/(1 - ξ)
+/// = (σ<sub>u<sub>0</sub></sub> + ξ (u - u<sub>0</sub>))/(1 - ξ) is linear in
+/// u: the plot guides the choice of the threshold of
+/// [`PeaksOverThreshold`] as the lowest one above which it is linear,
+/// within the uncertainty. Returns `None` when a value or a threshold is
+/// NaN.
+pub fn mean_residual_life<T: Float, S>(sample: &S, thresholds: &[T]) -> Option<Vec<MeanExcess<T>>>
+where
+    for<'a> &'a S: IntoIterator<Item = &'a T>,
+{
+    let mut values = Vec::new();
+    for &x in sample {
+        if x.is_nan() {
+            return None;
+        }
+        values.push(x);
+    }
+    let mut plot = Vec::new();
+    for &threshold in thresholds {
+        if threshold.is_nan() {
+            return None;
+        }
+        let excesses = values
+            .iter()
+            .filter(|x| **x > threshold)
+            .map(|x| *x - threshold)
+            .collect::<Vec<_>>();
+        let k = excesses.len();
+        if k < 2 {
+            continue;
+        }
+        let kf = T::from(k).unwrap();
+        let mean = excesses.iter().fold(T::zero(), |acc, y| acc + *y) / kf;
+        let var = excesses
+            .iter()
+            .fold(T::zero(), |acc, y| acc + (*y - mean).powi(2))
+            / (kf - T::one());
+        plot.push(MeanExcess {
+            threshold,
+            mean_excess: mean,
+            std_error: (var / kf).sqrt(),
+            exceedances: k,
+        });
+    }
+    Some(plot)
+}
+
+/// Estimate of a risk measure with its standard error and a confidence
+/// interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskMeasure<T> {
+    /// Estimate of the measure.
+    pub estimate: T,
+    /// Standard error by the delta method.
+    pub std_error: T,
+    /// Wald confidence interval, the estimate plus or minus the normal
+    /// quantile times the standard error.
+    pub interval: (T, T),
+}
+
+/// Peaks over threshold model: the values above the threshold u occur with
+/// probability ζ and their excesses follow a generalized Pareto distribution,
+/// fitted by maximum likelihood.
+///
+/// The tail P(X > x) = ζ (1 + ξ (x - u)/σ)<sup>-1/ξ</sup> for x > u gives the
+/// value at risk and the expected shortfall of [`value_at_risk`] and
+/// [`expected_shortfall`], with standard errors by the delta method from the
+/// binomial variance ζ(1 - ζ)/n of the rate and the covariance of (σ, ξ),
+/// treated as independent, as in Coles (2001).
+///
+/// [`value_at_risk`]: PeaksOverThreshold::value_at_risk
+/// [`expected_shortfall`]: PeaksOverThreshold::expected_shortfall
+#[derive(Debug, Clone)]
+pub struct PeaksOverThreshold<T> {
+    /// Threshold u.
+    pub threshold: T,
+    /// Number of observations n.
+    pub nobs: usize,
+    /// Fit of the generalized Pareto distribution to the excesses, of
+    /// estimates (`scale`, `shape`).
+    pub fit: FitOutput<GeneralizedPareto<T>, T>,
+}
+
+impl<T: Float + FloatConst> PeaksOverThreshold<T> {
+    /// Fits the model to the values of `sample` above `threshold`. Returns
+    /// `None` when a value is not finite, less than two values exceed the
+    /// threshold or the fit of the excesses fails.
+    pub fn fit<S>(sample: &S, threshold: T) -> Option<Self>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        if !threshold.is_finite() {
+            return None;
+        }
+        let mut nobs = 0;
+        let mut excesses = Vec::new();
+        for &x in sample {
+            if !x.is_finite() {
+                return None;
+            }
+            nobs += 1;
+            if x > threshold {
+                excesses.push(x - threshold);
+            }
+        }
+        let fit = GeneralizedPareto::fit::<Vec<T>>(&excesses)?;
+        Some(Self {
+            threshold,
+            nobs,
+            fit,
+        })
+    }
+
+    /// Estimate of the rate ζ of exceedances of the threshold.
+    pub fn rate(&self) -> T {
+        T::from(self.fit.nobs).unwrap() / T::from(self.nobs).unwrap()
+    }
+
+    /// Value at risk, the quantile x<sub>p</sub> = u + σ ((ζ/(1 -
+    /// p))<sup>ξ</sup> - 1)/ξ at the probability `p`, with a confidence
+    /// interval of confidence `level`. Returns `None` unless 1 - ζ < `p` <
+    /// 1 and 0 < `level` < 1.
+    pub fn value_at_risk(&self, p: T, level: T) -> Option<RiskMeasure<T>> {
+        let (estimate, grad) = self.quantile_gradient(p)?;
+        self.risk_measure(estimate, grad, level)
+    }
+
+    /// Expected shortfall E\[X | X > x<sub>p</sub>\] = (x<sub>p</sub> + σ -
+    /// ξ u)/(1 - ξ) beyond the value at risk at the probability `p`, with a
+    /// confidence interval of confidence `level`. Returns `None` unless 1 -
+    /// ζ < `p` < 1, 0 < `level` < 1 and ξ < 1.
+    pub fn expected_shortfall(&self, p: T, level: T) -> Option<RiskMeasure<T>> {
+        let one = T::one();
+        let (var, grad) = self.quantile_gradient(p)?;
+        let dist = self.fit.distribution;
+        let (sigma, xi, u) = (dist.scale, dist.shape, self.threshold);
+        if xi >= one {
+            return None;
+        }
+        let c = one - xi;
+        let estimate = (var + sigma - xi * u) / c;
+        let grad = arr1(&[
+            grad[0] / c,
+            (grad[1] + one) / c,
+            (grad[2] - u) / c + estimate / c,
+        ]);
+        self.risk_measure(estimate, grad, level)
+    }
+
+    /// Value at risk at `p` and its gradient in (ζ, σ, ξ).
+    fn quantile_gradient(&self, p: T) -> Option<(T, Array1<T>)> {
+        let (zero, one) = (T::zero(), T::one());
+        let zeta = self.rate();
+        if p.is_nan() || p <= one - zeta || p >= one {
+            return None;
+        }
+        let dist = self.fit.distribution;
+        let (sigma, xi) = (dist.scale, dist.shape);
+        // x_p = u + σ (exp(-ξ L) - 1)/ξ = u - σ L h(a) with L = ln((1 - p)/ζ),
+        // a = -ξ L and h(a) = (exp(a) - 1)/a.
+        let l = ((one - p) / zeta).ln();
+        let a = -xi * l;
+        let (h, dh) = if a.abs() >= T::from(RISK_SERIES_LIMIT).unwrap() {
+            let h = a.exp_m1() / a;
+            (h, (a.exp() - h) / a)
+        } else {
+            // h = 1 + ∑ a c_j and h' = ∑ j c_j with c_j = a^(j-1)/(j + 1)!, j ≥ 1.
+            let (mut h, mut dh) = (one, zero);
+            let mut c = T::from(0.5).unwrap();
+            for j in 1..=RISK_SERIES_MAX_TERMS {
+                let jf = T::from(j).unwrap();
+                h = h + a * c;
+                dh = dh + jf * c;
+                if (jf * c).abs() <= T::epsilon() * dh.abs() {
+                    break;
+                }
+                c = c * a / (jf + one + one);
+            }
+            (h, dh)
+        };
+        let g = -l * h;
+        let estimate = self.threshold + sigma * g;
+        let grad = arr1(&[sigma * a.exp() / zeta, g, sigma * l * l * dh]);
+        Some((estimate, grad))
+    }
+
+    /// Delta method standard error and interval of `estimate` of gradient
+    /// `grad` in (ζ, σ, ξ).
+    fn risk_measure(&self, estimate: T, grad: Array1<T>, level: T) -> Option<RiskMeasure<T>> {
+        let (zero, one) = (T::zero(), T::one());
+        if level.is_nan() || level <= zero || level >= one {
+            return None;
+        }
+        let zeta = self.rate();
+        let cov = &self.fit.covariance;
+        let var = grad[0] * grad[0] * zeta * (one - zeta) / T::from(self.nobs).unwrap()
+            + grad[1] * grad[1] * cov[[0, 0]]
+            + (grad[1] + grad[1]) * grad[2] * cov[[0, 1]]
+            + grad[2] * grad[2] * cov[[1, 1]];
+        let std_error = var.sqrt();
+        let z = quantile_n01((one + level) / (one + one))?;
+        Some(RiskMeasure {
+            estimate,
+            std_error,
+            interval: (estimate - z * std_error, estimate + z * std_error),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::lalonde::RE78;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn peaks_over_threshold_values() {
+        // Earnings in thousands of dollars.
+        let earnings = RE78.iter().map(|x| *x as f64 / 1000.).collect::<Vec<_>>();
+        let plot = mean_residual_life(&earnings, &[10., 15., 100.]).unwrap();
+        assert_eq!(plot.len(), 2);
+        assert_eq!(plot[1].exceedances, 48);
+        assert!(rel(plot[1].mean_excess, 6.0929210408528646) < 1e-14);
+        let pot = PeaksOverThreshold::fit(&earnings, 15.).unwrap();
+        assert_eq!(pot.fit.nobs, 48);
+        let var = pot.value_at_risk(0.99, 0.95).unwrap();
+        assert!(rel(var.estimate, 26.119647259379062) < 1e-8);
+        assert!(rel(var.std_error, 2.2453730333494494) < 1e-7);
+        let es = pot.expected_shortfall(0.99, 0.95).unwrap();
+        assert!(rel(es.estimate, 35.76288083245505) < 1e-8);
+        assert!(rel(es.std_error, 5.885079878413648) < 1e-7);
+        assert!(
+            rel(
+                es.interval.1 - es.estimate,
+                1.959963984540054 * es.std_error
+            ) < 1e-12
+        );
+        assert!(pot.value_at_risk(0.9, 0.95).is_none());
+        assert!(pot.value_at_risk(0.99, 1.).is_none());
+    }
+}
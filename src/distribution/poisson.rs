@@ -0,0 +1,90 @@
+use num_traits::{Float, FloatConst};
+
+use super::{inc_gamma, inc_gamma_upper, ln_factorial, Discrete};
+
+/// Poisson distribution with mean `lambda`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Poisson<T> {
+    pub lambda: T,
+}
+
+impl<T: Float> Poisson<T> {
+    /// Returns `None` unless `lambda` is non-negative and finite.
+    pub fn new(lambda: T) -> Option<Self> {
+        if lambda.is_finite() && lambda >= T::zero() {
+            Some(Self { lambda })
+        } else {
+            None
+        }
+    }
+}
+
+/// The CDF is the regularized upper incomplete Gamma function P(X ≤ k) =
+/// Q(k + 1, λ).
+impl<T: Float + FloatConst> Discrete<T> for Poisson<T> {
+    fn support(&self) -> (u64, Option<u64>) {
+        (0, None)
+    }
+
+    fn ln_pmf(&self, k: u64) -> T {
+        let k = T::from(k).unwrap();
+        if self.lambda == T::zero() {
+            return if k == T::zero() {
+                T::zero()
+            } else {
+                T::neg_infinity()
+            };
+        }
+        k * self.lambda.ln() - self.lambda - ln_factorial(k).unwrap()
+    }
+
+    fn cdf(&self, k: u64) -> T {
+        if self.lambda == T::zero() {
+            return T::one();
+        }
+        inc_gamma_upper(T::from(k).unwrap() + T::one(), self.lambda).unwrap()
+    }
+
+    fn sf(&self, k: u64) -> T {
+        if self.lambda == T::zero() {
+            return T::zero();
+        }
+        inc_gamma(T::from(k).unwrap() + T::one(), self.lambda).unwrap()
+    }
+
+    fn mean(&self) -> T {
+        self.lambda
+    }
+
+    fn variance(&self) -> T {
+        self.lambda
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn poisson_values() {
+        let poisson = Poisson::new(3.5).unwrap();
+        assert!(rel(poisson.pmf(2), 0.18495897346170082) < 1e-14);
+        assert!(rel(poisson.cdf(2), 0.32084719886213407) < 1e-14);
+        assert!(rel(poisson.sf(2), 0.67915280113786593) < 1e-14);
+        assert!(rel(Poisson::new(10.).unwrap().sf(40), 1.7773417493499444e-13) < 1e-12);
+        assert!(
+            rel(
+                Poisson::new(1000.).unwrap().cdf(900),
+                0.00069776732779630678
+            ) < 1e-11
+        );
+        assert_eq!(poisson.quantile(0.32084719886213407), Some(2));
+        assert_eq!(poisson.quantile(0.33), Some(3));
+        assert_eq!(Poisson::new(1000.).unwrap().quantile(0.5), Some(1000));
+        assert_eq!(poisson.quantile(1.), None);
+        assert_eq!(Poisson::new(0.).unwrap().pmf(0), 1.);
+        assert!(Poisson::new(-1.).is_none());
+    }
+}
@@ -0,0 +1,311 @@
+use num_traits::{Float, FloatConst};
+
+use super::{integrate, integration_tol, invert_cdf};
+
+/// Maximum number of terms of the series of the limiting distributions.
+const EDF_MAX_TERMS: usize = 100;
+
+/// Value of the Anderson-Darling statistic below which its CDF is computed
+/// by the series of Anderson and Darling, and above which its survival
+/// function is computed by Smirnov's formula.
+const ANDERSON_DARLING_SWITCH: f64 = 1.;
+
+/// Same as [`ANDERSON_DARLING_SWITCH`] for the Cramér-von Mises statistic.
+const CRAMER_VON_MISES_SWITCH: f64 = 0.15;
+
+/// Computes the CDF P(A<sup>2</sup> ≤ `z`) of the limiting distribution of
+/// the Anderson-Darling statistic A<sup>2</sup> = n ∫ (F<sub>n</sub> -
+/// F)<sup>2</sup> / (F (1 - F)) dF, the law of ∑<sub>j≥1</sub>
+/// ξ<sub>j</sub><sup>2</sup> / (j (j + 1)) with ξ<sub>j</sub> independent
+/// N(0, 1).
+///
+/// Below 1 the series of [Anderson and Darling (1954)][ad] is summed, its
+/// integrals being computed by adaptive quadrature; above, the upper tail
+/// is computed by Smirnov's formula P(A<sup>2</sup> > z) = π<sup>-1</sup>
+/// ∑<sub>k≥1</sub> (-1)<sup>k+1</sup> ∫ e<sup>-zu/2</sup> / (u (-D(u))<sup>1/2</sup>) du
+/// over (μ<sub>2k-1</sub>, μ<sub>2k</sub>), μ<sub>j</sub> = j (j + 1), with
+/// the Fredholm determinant D(u) = -cos(π (u + 1/4)<sup>1/2</sup>) / (πu),
+/// so that small p-values keep their relative accuracy. Returns `None` when
+/// `z` is NaN.
+///
+/// [ad]: https://doi.org/10.1080/01621459.1954.10501232
+pub fn cdf_anderson_darling_limit<T: Float + FloatConst>(z: T) -> Option<T> {
+    anderson_darling_tail(z, true)
+}
+
+/// Computes the survival function of the limiting distribution of the
+/// Anderson-Darling statistic, the p-value of the test with a fully
+/// specified distribution, see [`cdf_anderson_darling_limit`].
+pub fn sf_anderson_darling_limit<T: Float + FloatConst>(z: T) -> Option<T> {
+    anderson_darling_tail(z, false)
+}
+
+/// Computes the quantile function of the limiting distribution of the
+/// Anderson-Darling statistic, see [`cdf_anderson_darling_limit`]. Returns
+/// `None` when `p` is not in \[0, 1\].
+pub fn quantile_anderson_darling_limit<T: Float + FloatConst>(p: T) -> Option<T> {
+    invert_cdf(
+        p,
+        T::zero(),
+        T::from(2.5).unwrap(),
+        |z| cdf_anderson_darling_limit(z).unwrap(),
+        |z| sf_anderson_darling_limit(z).unwrap(),
+    )
+}
+
+/// Computes the CDF P(W<sup>2</sup> ≤ `x`) of the limiting distribution of
+/// the Cramér-von Mises statistic W<sup>2</sup> = n ∫ (F<sub>n</sub> -
+/// F)<sup>2</sup> dF, the law of ∑<sub>j≥1</sub> ξ<sub>j</sub><sup>2</sup>
+/// / (j<sup>2</sup>π<sup>2</sup>) with ξ<sub>j</sub> independent N(0, 1).
+///
+/// Below 0.15 the series of [Anderson and Darling (1952)][ad] in the
+/// modified Bessel functions K<sub>1/4</sub> is summed; above, the upper
+/// tail is computed by Smirnov's formula as in
+/// [`cdf_anderson_darling_limit`], with μ<sub>j</sub> =
+/// j<sup>2</sup>π<sup>2</sup> and D(u) = sin(u<sup>1/2</sup>) /
+/// u<sup>1/2</sup>. Returns `None` when `x` is NaN.
+///
+/// [ad]: https://doi.org/10.1214/aoms/1177729437
+pub fn cdf_cramer_von_mises_limit<T: Float + FloatConst>(x: T) -> Option<T> {
+    cramer_von_mises_tail(x, true)
+}
+
+/// Computes the survival function of the limiting distribution of the
+/// Cramér-von Mises statistic, see [`cdf_cramer_von_mises_limit`].
+pub fn sf_cramer_von_mises_limit<T: Float + FloatConst>(x: T) -> Option<T> {
+    cramer_von_mises_tail(x, false)
+}
+
+/// Computes the quantile function of the limiting distribution of the
+/// Cramér-von Mises statistic, see [`cdf_cramer_von_mises_limit`]. Returns
+/// `None` when `p` is not in \[0, 1\].
+pub fn quantile_cramer_von_mises_limit<T: Float + FloatConst>(p: T) -> Option<T> {
+    invert_cdf(
+        p,
+        T::zero(),
+        T::from(0.5).unwrap(),
+        |x| cdf_cramer_von_mises_limit(x).unwrap(),
+        |x| sf_cramer_von_mises_limit(x).unwrap(),
+    )
+}
+
+fn anderson_darling_tail<T: Float + FloatConst>(z: T, lower_tail: bool) -> Option<T> {
+    let (zero, one) = (T::zero(), T::one());
+    let quarter = T::from(0.25).unwrap();
+    if let Some(value) = edf_limit(z, lower_tail) {
+        return value;
+    }
+    if z < T::from(ANDERSON_DARLING_SWITCH).unwrap() {
+        let cdf = anderson_darling_series(z).max(zero).min(one);
+        return Some(if lower_tail { cdf } else { one - cdf });
+    }
+    let sf = smirnov_tail(
+        z,
+        |j| T::from(j * (j + 1)).unwrap(),
+        |u| (T::PI() * (u + quarter).sqrt()).cos() / (T::PI() * u),
+    );
+    Some(if lower_tail { one - sf } else { sf })
+}
+
+fn cramer_von_mises_tail<T: Float + FloatConst>(x: T, lower_tail: bool) -> Option<T> {
+    let (zero, one) = (T::zero(), T::one());
+    if let Some(value) = edf_limit(x, lower_tail) {
+        return value;
+    }
+    if x < T::from(CRAMER_VON_MISES_SWITCH).unwrap() {
+        let cdf = cramer_von_mises_series(x).max(zero).min(one);
+        return Some(if lower_tail { cdf } else { one - cdf });
+    }
+    let sf = smirnov_tail(
+        x,
+        |j| (T::from(j).unwrap() * T::PI()).powi(2),
+        |u| -u.sqrt().sin() / u.sqrt(),
+    );
+    Some(if lower_tail { one - sf } else { sf })
+}
+
+/// Values of the CDF (`lower_tail`) or survival function of a positive
+/// statistic at NaN, nonpositive and infinite values.
+fn edf_limit<T: Float>(x: T, lower_tail: bool) -> Option<Option<T>> {
+    let (zero, one) = (T::zero(), T::one());
+    let cdf = if x.is_nan() {
+        return Some(None);
+    } else if x <= zero {
+        zero
+    } else if x.is_infinite() {
+        one
+    } else {
+        return None;
+    };
+    Some(Some(if lower_tail { cdf } else { one - cdf }))
+}
+
+/// Series (√(2π) / z) ∑<sub>j≥0</sub> c<sub>j</sub> (4j + 1) exp(-(4j +
+/// 1)<sup>2</sup>π<sup>2</sup> / (8z)) ∫<sub>0</sub><sup>∞</sup> exp(z /
+/// (8(w<sup>2</sup> + 1)) - (4j + 1)<sup>2</sup>π<sup>2</sup>w<sup>2</sup> /
+/// (8z)) dw of the Anderson-Darling CDF, with c<sub>j</sub> =
+/// (-1)<sup>j</sup> (2j)! / (4<sup>j</sup> j!<sup>2</sup>).
+fn anderson_darling_series<T: Float + FloatConst>(z: T) -> T {
+    let (zero, one) = (T::zero(), T::one());
+    let eight = T::from(8.).unwrap();
+    let pi2 = T::PI() * T::PI();
+    let mut c = one;
+    let mut sum = zero;
+    for j in 0..EDF_MAX_TERMS {
+        if j > 0 {
+            let jf = T::from(j).unwrap();
+            c = -c * (jf + jf - one) / (jf + jf);
+        }
+        let a = T::from(4 * j + 1).unwrap();
+        // Standard deviation of the Gaussian factor of the integrand.
+        let sigma = (T::from(4.).unwrap() * z).sqrt() / (a * T::PI());
+        let points = [0., 1., 2., 4., 9.].map(|k| T::from(k).unwrap() * sigma);
+        let integral = integrate(
+            |w| (z / (eight * (w * w + one)) - a * a * pi2 * w * w / (eight * z)).exp(),
+            &points,
+            zero,
+            integration_tol(),
+        )
+        .0;
+        let term = c * a * (-a * a * pi2 / (eight * z)).exp() * integral;
+        sum = sum + term;
+        if term.abs() <= T::epsilon() * sum.abs() {
+            break;
+        }
+    }
+    (T::PI() + T::PI()).sqrt() / z * sum
+}
+
+/// Series (π x<sup>1/2</sup>)<sup>-1</sup> ∑<sub>j≥0</sub> c<sub>j</sub>
+/// (4j + 1)<sup>1/2</sup> e<sup>-y</sup> K<sub>1/4</sub>(y) with y = (4j +
+/// 1)<sup>2</sup> / (16x) and c<sub>j</sub> = Γ(j + 1/2) / (Γ(1/2) j!) of
+/// the Cramér-von Mises CDF, with e<sup>y</sup> K<sub>1/4</sub>(y) =
+/// ∫<sub>0</sub><sup>∞</sup> exp(-y (cosh t - 1)) cosh(t/4) dt.
+fn cramer_von_mises_series<T: Float + FloatConst>(x: T) -> T {
+    let (zero, one) = (T::zero(), T::one());
+    let half = T::from(0.5).unwrap();
+    let quarter = T::from(0.25).unwrap();
+    let mut c = one;
+    let mut sum = zero;
+    for j in 0..EDF_MAX_TERMS {
+        if j > 0 {
+            let jf = T::from(j).unwrap();
+            c = c * (jf - half) / jf;
+        }
+        let a = T::from(4 * j + 1).unwrap();
+        let y = a * a / (T::from(16.).unwrap() * x);
+        // Where y (cosh t - 1) reaches 1, 4, 16 and 50.
+        let points = [0., 1., 4., 16., 50.].map(|k| (one + T::from(k).unwrap() / y).acosh());
+        let bessel = integrate(
+            |t| (-y * (t.cosh() - one)).exp() * (quarter * t).cosh(),
+            &points,
+            zero,
+            integration_tol(),
+        )
+        .0;
+        let term = c * a.sqrt() * (-(y + y)).exp() * bessel;
+        sum = sum + term;
+        if term <= T::epsilon() * sum {
+            break;
+        }
+    }
+    sum / (T::PI() * x.sqrt())
+}
+
+/// Smirnov's formula for P(∑ λ<sub>j</sub> ξ<sub>j</sub><sup>2</sup> > `z`)
+/// with λ<sub>j</sub> = 1/μ<sub>j</sub> = 1/`mu`(j) decreasing and
+/// `neg_det` = -D the opposite of the Fredholm determinant
+/// ∏<sub>j</sub> (1 - λ<sub>j</sub>u). The integrals are computed with the
+/// substitution u = c - h cos θ, which removes the inverse square root
+/// singularities at the ends of the intervals.
+fn smirnov_tail<T, M, D>(z: T, mu: M, neg_det: D) -> T
+where
+    T: Float + FloatConst,
+    M: Fn(usize) -> T,
+    D: Fn(T) -> T,
+{
+    let zero = T::zero();
+    let half = T::from(0.5).unwrap();
+    let points = [zero, half * T::PI(), T::PI()];
+    let mut sum = zero;
+    for k in 1..=EDF_MAX_TERMS {
+        let (a, b) = (mu(2 * k - 1), mu(2 * k));
+        let (c, h) = (half * (a + b), half * (b - a));
+        let integral = integrate(
+            |theta: T| {
+                let u = c - h * theta.cos();
+                let d = neg_det(u);
+                if d <= zero {
+                    zero
+                } else {
+                    (-half * z * u).exp() * h * theta.sin() / (u * d.sqrt())
+                }
+            },
+            &points,
+            zero,
+            integration_tol(),
+        )
+        .0;
+        sum = if k % 2 == 1 {
+            sum + integral
+        } else {
+            sum - integral
+        };
+        if integral <= T::epsilon() * sum.abs() {
+            break;
+        }
+    }
+    (sum / T::PI()).max(zero).min(T::one())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn quadratic_edf_limits() {
+        // References from the series in multiple precision.
+        for (z, cdf) in [
+            (0.5, 0.253185626469655516),
+            (1., 0.642733326785980914),
+            (2.492, 0.949977813640392134),
+        ] {
+            let value = cdf_anderson_darling_limit(z).unwrap();
+            assert!((value - cdf).abs() < 1e-13, "A² = {z}");
+        }
+        for (z, sf) in [
+            (3.857, 0.0102411532298612125),
+            (6., 0.000967451941520922922),
+            (20., 4.46507153831192398e-10),
+        ] {
+            let value = sf_anderson_darling_limit(z).unwrap();
+            assert!((value - sf).abs() < 1e-11 * sf, "A² = {z}");
+        }
+        for (x, cdf) in [
+            (0.05, 0.123719068958651010),
+            (0.1, 0.415126561593205088),
+            (0.461, 0.949892872798243023),
+        ] {
+            let value = cdf_cramer_von_mises_limit(x).unwrap();
+            assert!((value - cdf).abs() < 1e-13, "W² = {x}");
+        }
+        for (x, sf) in [
+            (0.743, 0.0100255239814974133),
+            (1.5, 0.000172696219787948304),
+            (5., 3.05392903310116236e-12),
+        ] {
+            let value = sf_cramer_von_mises_limit(x).unwrap();
+            assert!((value - sf).abs() < 1e-11 * sf, "W² = {x}");
+        }
+        let q = quantile_anderson_darling_limit(0.95).unwrap();
+        assert!((cdf_anderson_darling_limit(q).unwrap() - 0.95).abs() < 1e-10);
+        assert!((q - 2.492).abs() < 1e-3);
+        let q = quantile_cramer_von_mises_limit(0.99).unwrap();
+        assert!((sf_cramer_von_mises_limit(q).unwrap() - 0.01).abs() < 1e-10);
+        assert!((q - 0.743).abs() < 1e-3);
+        assert_eq!(cdf_anderson_darling_limit(0.), Some(0.));
+        assert_eq!(sf_cramer_von_mises_limit(f64::INFINITY), Some(0.));
+        assert_eq!(cdf_cramer_von_mises_limit(f64::NAN), None);
+    }
+}
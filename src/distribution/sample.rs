@@ -0,0 +1,247 @@
+use std::sync::OnceLock;
+
+use ndarray::{Array, ArrayBase, DataMut, Dimension, ShapeBuilder};
+use num_traits::Float;
+use rand::Rng;
+
+/// Number of layers of the normal Ziggurat.
+const ZIGGURAT_LAYERS: usize = 256;
+/// Start of the tail of the normal Ziggurat with 256 layers.
+#[allow(clippy::excessive_precision)]
+const ZIGGURAT_R: f64 = 3.6541528853610088;
+/// Area of each layer of the normal Ziggurat with 256 layers.
+#[allow(clippy::excessive_precision)]
+const ZIGGURAT_V: f64 = 0.00492867323399;
+
+/// Random variate generation.
+///
+/// Draws only depend on the stream of the generator `rng`, so a seedable
+/// generator such as `rand::rngs::StdRng::seed_from_u64(seed)` always gives
+/// the same draws for the same seed.
+pub trait Sample<T> {
+    /// Draws one random variate.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T;
+
+    /// Draws an array of independent random variates with the given shape.
+    fn sample_array<R, Sh>(&self, rng: &mut R, shape: Sh) -> Array<T, Sh::Dim>
+    where
+        R: Rng + ?Sized,
+        Sh: ShapeBuilder,
+    {
+        Array::from_shape_simple_fn(shape, || self.sample(rng))
+    }
+
+    /// Fills `out` with independent random variates.
+    fn fill<R, S, D>(&self, rng: &mut R, out: &mut ArrayBase<S, D>)
+    where
+        R: Rng + ?Sized,
+        S: DataMut<Elem = T>,
+        D: Dimension,
+    {
+        out.map_inplace(|x| *x = self.sample(rng));
+    }
+}
+
+/// Draws a uniform variate on the open interval (0, 1).
+pub(crate) fn open_unit<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    loop {
+        let u: f64 = rng.gen();
+        if u > 0. {
+            return u;
+        }
+    }
+}
+
+/// Abscissae x<sub>i</sub> of the layers of the normal Ziggurat and the
+/// ratios x<sub>i+1</sub> / x<sub>i</sub>, see [Doornik (2005)][zignor].
+///
+/// [zignor]: https://www.doornik.com/research/ziggurat.pdf
+fn ziggurat_tables() -> &'static (Vec<f64>, Vec<f64>) {
+    static TABLES: OnceLock<(Vec<f64>, Vec<f64>)> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let density = |x: f64| (-0.5 * x * x).exp();
+        let mut x = vec![0.; ZIGGURAT_LAYERS + 1];
+        x[0] = ZIGGURAT_V / density(ZIGGURAT_R);
+        x[1] = ZIGGURAT_R;
+        for i in 2..ZIGGURAT_LAYERS {
+            x[i] = (-2. * (ZIGGURAT_V / x[i - 1] + density(x[i - 1])).ln()).sqrt();
+        }
+        let ratio = (0..ZIGGURAT_LAYERS).map(|i| x[i + 1] / x[i]).collect();
+        (x, ratio)
+    })
+}
+
+/// Draws a standard normal variate with the Ziggurat method of Marsaglia and
+/// Tsang (2000), in the formulation of [Doornik (2005)][zignor].
+///
+/// [zignor]: https://www.doornik.com/research/ziggurat.pdf
+pub(crate) fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let (x, ratio) = ziggurat_tables();
+    loop {
+        let bits = rng.next_u64();
+        let i = (bits & 0xff) as usize;
+        // 53 random bits, disjoint from the layer index.
+        let u = 2. * ((bits >> 11) as f64 / (1u64 << 53) as f64) - 1.;
+        if u.abs() < ratio[i] {
+            return u * x[i];
+        }
+        if i == 0 {
+            // Tail beyond R, Marsaglia (1964).
+            loop {
+                let t = -open_unit(rng).ln() / ZIGGURAT_R;
+                let y = -open_unit(rng).ln();
+                if y + y > t * t {
+                    return if u < 0. {
+                        -ZIGGURAT_R - t
+                    } else {
+                        ZIGGURAT_R + t
+                    };
+                }
+            }
+        }
+        let z = u * x[i];
+        let f0 = (-0.5 * (x[i] * x[i] - z * z)).exp();
+        let f1 = (-0.5 * (x[i + 1] * x[i + 1] - z * z)).exp();
+        if f1 + rng.gen::<f64>() * (f0 - f1) < 1. {
+            return z;
+        }
+    }
+}
+
+/// Draws a Gamma(`shape`, 1) variate with the method of [Marsaglia and Tsang
+/// (2000)][mt]. Shapes below 1 use Gamma(`shape` + 1) U<sup>1/`shape`</sup>.
+///
+/// [mt]: https://doi.org/10.1145/358407.358414
+pub(crate) fn standard_gamma<R: Rng + ?Sized>(rng: &mut R, shape: f64) -> f64 {
+    if shape < 1. {
+        let u = open_unit(rng);
+        return standard_gamma(rng, shape + 1.) * u.powf(1. / shape);
+    }
+    let d = shape - 1. / 3.;
+    let c = 1. / (9. * d).sqrt();
+    loop {
+        let (x, v) = loop {
+            let x = standard_normal(rng);
+            let v = 1. + c * x;
+            if v > 0. {
+                break (x, v * v * v);
+            }
+        };
+        let u = open_unit(rng);
+        let x2 = x * x;
+        if u < 1. - 0.0331 * x2 * x2 || u.ln() < 0.5 * x2 + d * (1. - v + v.ln()) {
+            return d * v;
+        }
+    }
+}
+
+/// Draws a χ<sup>2</sup>(`df`) variate as 2 Gamma(`df`/2, 1).
+pub(crate) fn standard_chi2<R: Rng + ?Sized>(rng: &mut R, df: f64) -> f64 {
+    2. * standard_gamma(rng, 0.5 * df)
+}
+
+/// Converts a draw computed in `f64` to `T`.
+pub(crate) fn from_f64<T: Float>(x: f64) -> T {
+    T::from(x).unwrap_or_else(T::nan)
+}
+
+/// Converts a parameter to `f64` for the generators.
+pub(crate) fn to_f64<T: Float>(x: T) -> f64 {
+    x.to_f64().unwrap_or(f64::NAN)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array2;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::distribution::{Beta, ChiSquared, Gamma, Normal, StudentsT};
+
+    fn moments(draws: &[f64]) -> (f64, f64) {
+        let n = draws.len() as f64;
+        let mean = draws.iter().sum::<f64>() / n;
+        let var = draws.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.);
+        (mean, var)
+    }
+
+    #[test]
+    fn ziggurat_layers_cover_the_density() {
+        let (x, _) = ziggurat_tables();
+        // The top layer ends at the mode.
+        let top = x[ZIGGURAT_LAYERS - 1];
+        let area = top * (1. - (-0.5 * top * top).exp());
+        assert!((area - ZIGGURAT_V).abs() < 1e-9);
+    }
+
+    #[test]
+    fn standard_normal_moments() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let draws = (0..200_000)
+            .map(|_| standard_normal(&mut rng))
+            .collect::<Vec<_>>();
+        let (mean, var) = moments(&draws);
+        assert!(mean.abs() < 0.01);
+        assert!((var - 1.).abs() < 0.015);
+        let tail = draws.iter().filter(|x| x.abs() > ZIGGURAT_R).count() as f64;
+        // P(|Z| > R) = 2.58e-4
+        assert!((tail / 200_000. - 2.58e-4).abs() < 1.5e-4);
+    }
+
+    #[test]
+    fn standard_gamma_moments() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for shape in [0.3, 1., 4.5] {
+            let draws = (0..100_000)
+                .map(|_| standard_gamma(&mut rng, shape))
+                .collect::<Vec<_>>();
+            let (mean, var) = moments(&draws);
+            assert!((mean - shape).abs() < 0.02 * shape.max(1.));
+            assert!((var - shape).abs() < 0.05 * shape.max(1.));
+        }
+    }
+
+    #[test]
+    fn same_seed_same_draws() {
+        let normal = Normal::new(1., 2.).unwrap();
+        let first = normal.sample_array(&mut StdRng::seed_from_u64(42), (3, 4));
+        let second = normal.sample_array(&mut StdRng::seed_from_u64(42), (3, 4));
+        assert_eq!(first, second);
+        let mut filled = Array2::zeros((3, 4));
+        normal.fill(&mut StdRng::seed_from_u64(42), &mut filled);
+        assert_eq!(first, filled);
+        let other = normal.sample_array(&mut StdRng::seed_from_u64(43), (3, 4));
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn distribution_sample_moments() {
+        let mut rng = StdRng::seed_from_u64(2024);
+        let n = 100_000;
+        let check = |draws: Vec<f64>, mean: f64, var: f64| {
+            let (m, v) = moments(&draws);
+            assert!((m - mean).abs() < 0.02 * (1. + mean.abs()), "{m} vs {mean}");
+            assert!((v - var).abs() < 0.05 * var, "{v} vs {var}");
+        };
+        let normal = Normal::new(-3., 0.5).unwrap();
+        check((0..n).map(|_| normal.sample(&mut rng)).collect(), -3., 0.25);
+        let gamma = Gamma::new(2.5, 3.).unwrap();
+        check((0..n).map(|_| gamma.sample(&mut rng)).collect(), 7.5, 22.5);
+        let chi2 = ChiSquared::new(7.).unwrap();
+        check((0..n).map(|_| chi2.sample(&mut rng)).collect(), 7., 14.);
+        let beta = Beta::new(2., 5.).unwrap();
+        check(
+            (0..n).map(|_| beta.sample(&mut rng)).collect(),
+            2. / 7.,
+            10. / 392.,
+        );
+        let t = StudentsT::new(10.).unwrap();
+        check((0..n).map(|_| t.sample(&mut rng)).collect(), 0., 1.25);
+        let draws = (0..n)
+            .map(|_| t.sample(&mut rng) as f32)
+            .collect::<Vec<_>>();
+        assert!(draws.iter().all(|x| x.is_finite()));
+        assert!(Normal::new(0., 0.).is_none());
+        assert!(Gamma::new(-1., 1.).is_none());
+    }
+}
@@ -0,0 +1,226 @@
+#[cfg(feature = "rayon")]
+use ndarray::Zip;
+use ndarray::{Array, ArrayBase, Data, Dimension};
+use num_traits::Float;
+
+use super::{Continuous, Discrete};
+
+/// Number of elements from which the `par_` methods evaluate in parallel:
+/// below it, the cost of splitting the work exceeds the gain.
+#[cfg(feature = "rayon")]
+const PARALLEL_MIN_LEN: usize = 4096;
+
+/// Element-wise evaluation of a [`Continuous`] distribution over arrays of
+/// any dimension, implemented for every continuous distribution, including
+/// `dyn Continuous<T>`.
+///
+/// With the `rayon` feature, the `par_` methods split the arrays of at least
+/// 4096 elements across the threads of the global rayon pool, and evaluate
+/// the smaller ones sequentially.
+pub trait ContinuousArray<T: Float>: Continuous<T> {
+    /// Densities at the elements of `x`.
+    fn pdf_array<S, D>(&self, x: &ArrayBase<S, D>) -> Array<T, D>
+    where
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        x.map(|x| self.pdf(*x))
+    }
+
+    /// CDFs at the elements of `x`.
+    fn cdf_array<S, D>(&self, x: &ArrayBase<S, D>) -> Array<T, D>
+    where
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        x.map(|x| self.cdf(*x))
+    }
+
+    /// Survival functions at the elements of `x`, for instance the p-values
+    /// of upper one-sided tests.
+    fn sf_array<S, D>(&self, x: &ArrayBase<S, D>) -> Array<T, D>
+    where
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        x.map(|x| self.sf(*x))
+    }
+
+    /// Quantiles at the probabilities of `p`. Returns `None` when an element
+    /// of `p` is not in \[0, 1\].
+    fn quantile_array<S, D>(&self, p: &ArrayBase<S, D>) -> Option<Array<T, D>>
+    where
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        transpose(p.map(|p| self.quantile(*p)))
+    }
+
+    /// Parallel [`pdf_array`](ContinuousArray::pdf_array).
+    #[cfg(feature = "rayon")]
+    fn par_pdf_array<S, D>(&self, x: &ArrayBase<S, D>) -> Array<T, D>
+    where
+        Self: Sync,
+        T: Send + Sync,
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        par_map(x, |x| self.pdf(x))
+    }
+
+    /// Parallel [`cdf_array`](ContinuousArray::cdf_array).
+    #[cfg(feature = "rayon")]
+    fn par_cdf_array<S, D>(&self, x: &ArrayBase<S, D>) -> Array<T, D>
+    where
+        Self: Sync,
+        T: Send + Sync,
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        par_map(x, |x| self.cdf(x))
+    }
+
+    /// Parallel [`sf_array`](ContinuousArray::sf_array).
+    #[cfg(feature = "rayon")]
+    fn par_sf_array<S, D>(&self, x: &ArrayBase<S, D>) -> Array<T, D>
+    where
+        Self: Sync,
+        T: Send + Sync,
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        par_map(x, |x| self.sf(x))
+    }
+
+    /// Parallel [`quantile_array`](ContinuousArray::quantile_array).
+    #[cfg(feature = "rayon")]
+    fn par_quantile_array<S, D>(&self, p: &ArrayBase<S, D>) -> Option<Array<T, D>>
+    where
+        Self: Sync,
+        T: Send + Sync,
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        transpose(par_map(p, |p| self.quantile(p)))
+    }
+}
+
+impl<T: Float, C: Continuous<T> + ?Sized> ContinuousArray<T> for C {}
+
+/// Element-wise evaluation of a [`Discrete`] distribution over arrays of
+/// any dimension, implemented for every discrete distribution, see
+/// [`ContinuousArray`].
+pub trait DiscreteArray<T: Float>: Discrete<T> {
+    /// Probability masses at the elements of `k`.
+    fn pmf_array<S, D>(&self, k: &ArrayBase<S, D>) -> Array<T, D>
+    where
+        S: Data<Elem = u64>,
+        D: Dimension,
+    {
+        k.map(|k| self.pmf(*k))
+    }
+
+    /// CDFs at the elements of `k`.
+    fn cdf_array<S, D>(&self, k: &ArrayBase<S, D>) -> Array<T, D>
+    where
+        S: Data<Elem = u64>,
+        D: Dimension,
+    {
+        k.map(|k| self.cdf(*k))
+    }
+
+    /// Survival functions at the elements of `k`.
+    fn sf_array<S, D>(&self, k: &ArrayBase<S, D>) -> Array<T, D>
+    where
+        S: Data<Elem = u64>,
+        D: Dimension,
+    {
+        k.map(|k| self.sf(*k))
+    }
+
+    /// Quantiles at the probabilities of `p`. Returns `None` when the
+    /// quantile of an element of `p` is `None`, see [`Discrete::quantile`].
+    fn quantile_array<S, D>(&self, p: &ArrayBase<S, D>) -> Option<Array<u64, D>>
+    where
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        transpose(p.map(|p| self.quantile(*p)))
+    }
+}
+
+impl<T: Float, C: Discrete<T> + ?Sized> DiscreteArray<T> for C {}
+
+/// The array of the values of `values`, or `None` when one of them is
+/// `None`.
+fn transpose<A: Copy, D: Dimension>(values: Array<Option<A>, D>) -> Option<Array<A, D>> {
+    if values.iter().any(Option::is_none) {
+        return None;
+    }
+    Some(values.mapv(Option::unwrap))
+}
+
+/// Maps `f` over `x`, in parallel when `x` has at least
+/// [`PARALLEL_MIN_LEN`] elements.
+#[cfg(feature = "rayon")]
+fn par_map<T, B, S, D, F>(x: &ArrayBase<S, D>, f: F) -> Array<B, D>
+where
+    T: Copy + Send + Sync,
+    B: Send,
+    S: Data<Elem = T>,
+    D: Dimension,
+    F: Fn(T) -> B + Sync + Send,
+{
+    if x.len() < PARALLEL_MIN_LEN {
+        x.map(|x| f(*x))
+    } else {
+        Zip::from(x).par_map_collect(|x| f(*x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, arr2, Array1};
+
+    use super::*;
+    use crate::distribution::{Binomial, StudentsT};
+
+    #[test]
+    fn vectorized_values() {
+        let t = StudentsT::new(4.5).unwrap();
+        let x = arr2(&[[-2.5, 0.], [1.3, 40.]]);
+        let cdf = t.cdf_array(&x);
+        assert_eq!(cdf.dim(), (2, 2));
+        for (c, x) in cdf.iter().zip(&x) {
+            assert_eq!(*c, t.cdf(*x));
+        }
+        assert_eq!(t.sf_array(&x.view())[[1, 0]], t.sf(1.3));
+        assert_eq!(t.pdf_array(&x.row(0))[1], t.pdf(0.));
+        let p = arr1(&[0.025, 0.5, 0.975]);
+        let q = t.quantile_array(&p).unwrap();
+        assert_eq!(q[2], t.quantile(0.975).unwrap());
+        assert!(t.quantile_array(&arr1(&[0.5, 1.5])).is_none());
+        // Trait objects.
+        let dist: &dyn Continuous<f32> = &StudentsT::new(4.5f32).unwrap();
+        assert_eq!(dist.cdf_array(&arr1(&[1.3f32]))[0], dist.cdf(1.3));
+        let binomial = Binomial::new(20, 0.3).unwrap();
+        let k = arr1(&[0u64, 6, 20]);
+        let pmf: Array1<f64> = binomial.pmf_array(&k);
+        assert_eq!(pmf[1], binomial.pmf(6));
+        let quantiles = DiscreteArray::<f64>::quantile_array(&binomial, &arr1(&[0., 0.5, 1.]));
+        assert_eq!(quantiles, Some(arr1(&[0, 6, 20])));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_values() {
+        let t = StudentsT::new(7.5).unwrap();
+        let x = Array1::linspace(-10., 10., 2 * PARALLEL_MIN_LEN + 1);
+        assert_eq!(t.par_cdf_array(&x), t.cdf_array(&x));
+        assert_eq!(t.par_sf_array(&x), t.sf_array(&x));
+        assert_eq!(t.par_pdf_array(&x), t.pdf_array(&x));
+        let p = Array1::linspace(0., 1., PARALLEL_MIN_LEN + 1);
+        assert_eq!(t.par_quantile_array(&p), t.quantile_array(&p));
+        assert!(t.par_quantile_array(&(&p - 0.5)).is_none());
+    }
+}
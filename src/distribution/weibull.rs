@@ -0,0 +1,165 @@
+use ndarray::{arr1, arr2};
+use num_traits::{Float, FloatConst};
+use rand::Rng;
+
+use super::{
+    fit_output, from_f64, lngamma, maximize, open_unit, Continuous, Fit, FitOutput, Sample,
+    EULER_GAMMA,
+};
+
+/// Weibull distribution with shape k = `shape` and scale λ = `scale`, of
+/// survival function e<sup>-(x/λ)<sup>k</sup></sup> on x ≥ 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weibull<T> {
+    pub shape: T,
+    pub scale: T,
+}
+
+impl<T: Float> Weibull<T> {
+    /// Returns `None` unless `shape` and `scale` are positive and finite.
+    pub fn new(shape: T, scale: T) -> Option<Self> {
+        let valid = |p: T| p.is_finite() && p > T::zero();
+        if valid(shape) && valid(scale) {
+            Some(Self { shape, scale })
+        } else {
+            None
+        }
+    }
+}
+
+/// Estimates (`shape`, `scale`), starting from the moments of ln x, which
+/// follows a Gumbel distribution of minima. Requires positive values that are
+/// not all equal.
+impl<T: Float + FloatConst> Fit<T> for Weibull<T> {
+    fn fit<S>(sample: &S) -> Option<FitOutput<Self, T>>
+    where
+        for<'a> &'a S: IntoIterator<Item = &'a T>,
+    {
+        let zero = T::zero();
+        let mut logs = Vec::new();
+        for &x in sample {
+            if !(x.is_finite() && x > zero) {
+                return None;
+            }
+            logs.push(x.ln());
+        }
+        let n = logs.len();
+        let nf = T::from(n).unwrap();
+        let mean_ln = logs.iter().fold(zero, |acc, l| acc + *l) / nf;
+        let var_ln = logs
+            .iter()
+            .fold(zero, |acc, l| acc + (*l - mean_ln).powi(2))
+            / nf;
+        if var_ln.is_nan() || var_ln <= zero {
+            return None;
+        }
+        // ln x has standard deviation π / (k √6) and mean ln λ - γ/k.
+        let shape = T::PI() / (T::from(6.).unwrap() * var_ln).sqrt();
+        let scale = (mean_ln + T::from(EULER_GAMMA).unwrap() / shape).exp();
+        let (theta, loglik, hessian) = maximize(arr1(&[shape, scale]), |p| {
+            let (k, scale) = (p[0], p[1]);
+            Self::new(k, scale)?;
+            let ln_scale = scale.ln();
+            // Sums of u^k, u^k ln u and u^k ln²u with u = x/λ.
+            let (mut a, mut b, mut c, mut sum_ln_u) = (zero, zero, zero, zero);
+            for l in &logs {
+                let z = *l - ln_scale;
+                let uk = (k * z).exp();
+                a = a + uk;
+                b = b + uk * z;
+                c = c + uk * z * z;
+                sum_ln_u = sum_ln_u + z;
+            }
+            let loglik = nf * (k.ln() - ln_scale) + (k - T::one()) * sum_ln_u - a;
+            let grad = arr1(&[nf / k + sum_ln_u - b, k * (a - nf) / scale]);
+            let cross = (a - nf + k * b) / scale;
+            let hessian = arr2(&[
+                [-nf / (k * k) - c, cross],
+                [cross, k * (nf - a - k * a) / (scale * scale)],
+            ]);
+            Some((loglik, grad, hessian))
+        })?;
+        fit_output(Self::new(theta[0], theta[1])?, theta, &hessian, loglik, n)
+    }
+}
+
+impl<T: Float + FloatConst> Continuous<T> for Weibull<T> {
+    fn support(&self) -> (T, T) {
+        (T::zero(), T::infinity())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let (zero, one) = (T::zero(), T::one());
+        if x < zero {
+            return T::neg_infinity();
+        }
+        if x == zero {
+            return if self.shape < one {
+                T::infinity()
+            } else if self.shape == one {
+                -self.scale.ln()
+            } else {
+                T::neg_infinity()
+            };
+        }
+        let z = x / self.scale;
+        (self.shape / self.scale).ln() + (self.shape - one) * z.ln() - z.powf(self.shape)
+    }
+
+    fn cdf(&self, x: T) -> T {
+        if x <= T::zero() {
+            return T::zero();
+        }
+        -(-(x / self.scale).powf(self.shape)).exp_m1()
+    }
+
+    fn sf(&self, x: T) -> T {
+        if x <= T::zero() {
+            return T::one();
+        }
+        (-(x / self.scale).powf(self.shape)).exp()
+    }
+
+    fn mean(&self) -> T {
+        self.scale * lngamma(T::one() + self.shape.recip()).exp()
+    }
+
+    fn variance(&self) -> T {
+        let one = T::one();
+        let g1 = lngamma(one + self.shape.recip()).exp();
+        let g2 = lngamma(one + (one + one) / self.shape).exp();
+        self.scale * self.scale * (g2 - g1 * g1)
+    }
+
+    fn quantile(&self, p: T) -> Option<T> {
+        if p.is_nan() || p < T::zero() || p > T::one() {
+            return None;
+        }
+        Some(self.scale * (-(-p).ln_1p()).powf(self.shape.recip()))
+    }
+}
+
+/// Inversion of the CDF.
+impl<T: Float + FloatConst> Sample<T> for Weibull<T> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        let u: T = from_f64(open_unit(rng));
+        self.scale * (-u.ln()).powf(self.shape.recip())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn weibull_values() {
+        let weibull = Weibull::new(1.7, 3.).unwrap();
+        assert!(rel(weibull.pdf(2.5), 0.23952554297733805) < 1e-14);
+        assert!(rel(weibull.cdf(2.5), 0.51976806275629602) < 1e-14);
+        assert!(rel(weibull.mean(), 2.6767335074979726) < 1e-14);
+        assert!(rel(weibull.variance(), 2.6266934452126802) < 1e-13);
+        assert!(rel(weibull.quantile(0.51976806275629602).unwrap(), 2.5) < 1e-14);
+    }
+}
@@ -3,19 +3,89 @@ pub mod conditional_binary_treatment;
 pub mod data;
 pub mod distribution;
 pub mod statistical_test;
-use num_traits::NumCast;
 
 use distribution::*;
 
+/// Helpers shared by the unit tests. Unless a test states otherwise, its
+/// reference values are computed in mpmath at 40 digits.
+#[cfg(test)]
+pub(crate) mod test_util {
+    use ndarray::Array2;
+
+    use crate::{
+        data::lalonde::{RE75, RE78, TREAT},
+        distribution::FitOutput,
+    };
+
+    /// Relative error of `value` against the nonzero `reference`.
+    pub(crate) fn rel(value: f64, reference: f64) -> f64 {
+        ((value - reference) / reference).abs()
+    }
+
+    /// Checks a maximum-likelihood fit against the reference estimates,
+    /// standard errors and log-likelihood, and its information criteria
+    /// against the log-likelihood.
+    pub(crate) fn assert_fit<D>(
+        fit: &FitOutput<D, f64>,
+        estimates: &[f64],
+        std_errors: &[f64],
+        loglik: f64,
+    ) {
+        assert_fit_tol(fit, estimates, std_errors, loglik, 1e-7);
+    }
+
+    /// Same as [`assert_fit`] with the relative tolerance of the standard
+    /// errors, for Hessians computed by finite differences.
+    pub(crate) fn assert_fit_tol<D>(
+        fit: &FitOutput<D, f64>,
+        estimates: &[f64],
+        std_errors: &[f64],
+        loglik: f64,
+        std_error_tol: f64,
+    ) {
+        for (a, b) in fit.estimates.iter().zip(estimates) {
+            assert!(rel(*a, *b) < 1e-8, "estimate {a} vs {b}");
+        }
+        for (a, b) in fit.std_errors.iter().zip(std_errors) {
+            assert!(rel(*a, *b) < std_error_tol, "standard error {a} vs {b}");
+        }
+        assert!(rel(fit.loglik, loglik) < 1e-12);
+        let k = estimates.len() as f64;
+        assert!(rel(fit.aic, 2. * k - 2. * loglik) < 1e-12);
+        assert!(rel(fit.bic, k * (fit.nobs as f64).ln() - 2. * loglik) < 1e-12);
+    }
+
+    /// Earnings in 1978 of the treated workers if `treat` is 1, and of the
+    /// controls if it is 0.
+    pub(crate) fn earnings(treat: i8) -> Vec<f64> {
+        RE78.iter()
+            .zip(TREAT)
+            .filter(|(_, t)| *t == treat)
+            .map(|(x, _)| *x as f64)
+            .collect()
+    }
+
+    /// Earnings in 1975 and 1978 of the workers with positive earnings in
+    /// both years, one worker per row.
+    pub(crate) fn earnings_pairs() -> Array2<f64> {
+        let pairs = RE75
+            .iter()
+            .zip(&RE78)
+            .filter(|(a, b)| **a > 0. && **b > 0.)
+            .flat_map(|(a, b)| [*a as f64, *b as f64])
+            .collect::<Vec<_>>();
+        Array2::from_shape_vec((pairs.len() / 2, 2), pairs).unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         binary_treatment::BinaryTreatment,
-        cdf_t,
         conditional_binary_treatment::ConditionalBinaryTreatment,
         data::lalonde::*,
-        statistical_test::{two_sample_homoscedastic_ttest, TestTSide},
+        statistical_test::{one_sample_ttest, two_sample_homoscedastic_ttest, TestTSide},
     };
     use ndarray::*;
     #[test]
@@ -25,6 +95,14 @@ mod tests {
         let mut binary_treatment: BinaryTreatment<f32> = BinaryTreatment::new();
         binary_treatment.fit(&treatment, &income);
         println!("{:#?}", binary_treatment);
+        // The same estimate in double precision.
+        let income = income.mapv(f64::from);
+        let treatment = treatment.mapv(f64::from);
+        let mut double: BinaryTreatment<f64> = BinaryTreatment::new();
+        double.fit(&treatment, &income);
+        let effect = double.candidate_causal_effect.unwrap();
+        let single = binary_treatment.candidate_causal_effect.unwrap() as f64;
+        assert!(test_util::rel(single, effect) < 1e-5);
     }
 
     #[test]
@@ -62,10 +140,22 @@ mod tests {
                 (1180., 1353.75),
                 (8., 8.),
                 (26.32218f64.powi(2), 28.02661f64.powi(2)),
-                TestTSide::TwoSided
+                TestTSide::TwoSided,
+                0.95
             )
         );
         // println!("{}", lgamma(0.001));
         println!("{:?}", cdf_nt(1., 10., 1.));
+        // The p-values agree in single and double precision.
+        for side in [
+            TestTSide::UpperOneSided,
+            TestTSide::LowerOneSided,
+            TestTSide::TwoSided,
+        ] {
+            let single = one_sample_ttest(3000f32, 2800., 25., 360000., side, 0.95).unwrap();
+            let double = one_sample_ttest(3000f64, 2800., 25., 360000., side, 0.95).unwrap();
+            let (p32, p64) = (single.pvalue.unwrap() as f64, double.pvalue.unwrap());
+            assert!(test_util::rel(p32, p64) < 64. * f32::EPSILON as f64);
+        }
     }
 }
@@ -1,25 +1,304 @@
+mod chi_squared;
+mod edf;
+mod effect_size;
+mod exact_2x2;
 mod one_sample;
+mod paired;
+mod power;
+mod proportion;
+mod rank_sum;
+mod signed_rank;
 mod two_sample;
+pub use chi_squared::*;
+pub use edf::*;
+pub use effect_size::*;
+pub use exact_2x2::*;
 pub use one_sample::*;
+pub use paired::*;
+pub use power::*;
+pub use proportion::*;
+pub use rank_sum::*;
+pub use signed_rank::*;
 pub use two_sample::*;
 
+use std::borrow::Borrow;
+
+use num_traits::{Float, FloatConst};
+
+use crate::{cdf_n01, cdf_t, quantile_n01, sf_n01, sf_t, Continuous, StudentsT};
+
+/// Relative tolerance under which two probabilities or two statistics are
+/// considered tied, as in R, so that rounding does not split outcomes of
+/// equal evidence.
+pub(crate) const TIE_TOLERANCE: f64 = 1e-7;
+
 /// Which side to test.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TestTSide {
     /// - For one sample tests:
-    /// H<sub>0</sub> : μ ≤ μ<sub>0</sub> vs H<sub>1</sub> : μ > μ<sub>0</sub>
+    ///   H<sub>0</sub> : μ ≤ μ<sub>0</sub> vs H<sub>1</sub> : μ > μ<sub>0</sub>
     UpperOneSided,
     /// - For one sample tests:
-    /// H<sub>0</sub> : μ ≥ μ<sub>0</sub> vs H<sub>1</sub> : μ < μ<sub>0</sub>
+    ///   H<sub>0</sub> : μ ≥ μ<sub>0</sub> vs H<sub>1</sub> : μ < μ<sub>0</sub>
     LowerOneSided,
     /// - For one sample tests:
-    /// H<sub>0</sub> : μ = μ<sub>0</sub> vs H<sub>1</sub> : μ != μ<sub>0</sub>
+    ///   H<sub>0</sub> : μ = μ<sub>0</sub> vs H<sub>1</sub> : μ != μ<sub>0</sub>
     TwoSided,
 }
 
+/// How to compute the p-value of a test whose statistic has an exact null
+/// distribution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PValueMethod {
+    /// The exact null distribution, whose cost grows with the sample size.
+    Exact,
+    /// The asymptotic, usually normal, approximation of the null
+    /// distribution.
+    Asymptotic,
+    /// Exact for small samples and asymptotic otherwise; each test documents
+    /// its threshold.
+    Auto,
+}
+
 /// Output values of a test.
 #[derive(Debug)]
 pub struct TestOutput<S, P> {
     pub statistics: S,
     pub pvalue: Option<P>,
+    /// Point estimate of the tested parameter, such as a mean or a spread of
+    /// means.
+    pub estimate: Option<S>,
+    /// Standard error of the estimate.
+    pub std_error: Option<S>,
+    /// Degrees of freedom of the null distribution of the statistic.
+    pub df: Option<S>,
+    /// Confidence interval of the tested parameter at the requested level:
+    /// two-sided for the two-sided tests, and with an upper (resp. lower)
+    /// bound at the end of the range of the parameter, infinite when it is
+    /// unbounded, for the lower (resp. upper) one-sided tests.
+    pub interval: Option<(S, S)>,
+}
+
+/// P-value of the statistic `stat` of a test whose null distribution is the
+/// standard normal.
+pub(crate) fn pvalue_z<T: Float + FloatConst>(stat: T, test_type: TestTSide) -> Option<T> {
+    match test_type {
+        TestTSide::UpperOneSided => sf_n01(stat),
+        TestTSide::LowerOneSided => cdf_n01(stat),
+        TestTSide::TwoSided => sf_n01(stat.abs()).map(|sf| sf + sf),
+    }
+}
+
+/// P-value of the statistic `stat` of a test whose null distribution is the
+/// central Student's t-distribution with `df` degrees of freedom.
+pub(crate) fn pvalue_t<T: Float + FloatConst>(stat: T, df: T, test_type: TestTSide) -> Option<T> {
+    match test_type {
+        TestTSide::UpperOneSided => sf_t(stat, df),
+        TestTSide::LowerOneSided => cdf_t(stat, df),
+        TestTSide::TwoSided => sf_t(stat.abs(), df).map(|sf| sf + sf),
+    }
+}
+
+/// Confidence interval at `level` of a parameter whose `estimate` has the
+/// standard error `std_error`, from the quantiles of the central Student's
+/// t-distribution with `df` degrees of freedom, or of the standard normal
+/// when `df` is `None`. The interval matches the alternative of `test_type`,
+/// see [`TestOutput::interval`]. Returns `None` unless `level` is in (0, 1).
+pub(crate) fn wald_interval<T: Float + FloatConst>(
+    estimate: T,
+    std_error: T,
+    df: Option<T>,
+    test_type: TestTSide,
+    level: T,
+) -> Option<(T, T)> {
+    let one = T::one();
+    if level.is_nan() || level <= T::zero() || level >= one {
+        return None;
+    }
+    let p = match test_type {
+        TestTSide::TwoSided => (one + level) / (one + one),
+        _ => level,
+    };
+    let q = match df {
+        Some(df) => StudentsT::new(df)?.quantile(p)?,
+        None => quantile_n01(p)?,
+    };
+    let margin = q * std_error;
+    Some(match test_type {
+        TestTSide::UpperOneSided => (estimate - margin, T::infinity()),
+        TestTSide::LowerOneSided => (T::neg_infinity(), estimate + margin),
+        TestTSide::TwoSided => (estimate - margin, estimate + margin),
+    })
+}
+
+/// Differences x<sub>i</sub> - y<sub>i</sub> of the paired samples `sample1`
+/// and `sample2`. Returns `None` when their lengths differ.
+pub(crate) fn differences<T, I1, I2>(sample1: I1, sample2: I2) -> Option<Vec<T>>
+where
+    T: Float,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let mut sample2 = sample2.into_iter();
+    let mut diffs = Vec::new();
+    for x in sample1 {
+        diffs.push(*x.borrow() - *sample2.next()?.borrow());
+    }
+    if sample2.next().is_some() {
+        return None;
+    }
+    Some(diffs)
+}
+
+/// Ranks from 1 of `values`, which must not contain NaN, ties receiving
+/// their average rank, with the sizes of the groups of ties in increasing
+/// order of the values.
+pub(crate) fn average_ranks<T: Float>(values: &[T]) -> (Vec<T>, Vec<usize>) {
+    let n = values.len();
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by(|a, b| values[*a].partial_cmp(&values[*b]).unwrap());
+    let mut ranks = vec![T::zero(); n];
+    let mut groups = Vec::new();
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        while end < n && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        // Ranks start + 1, ..., end share their average.
+        let rank = T::from(start + end + 1).unwrap() / T::from(2.).unwrap();
+        for i in &order[start..end] {
+            ranks[*i] = rank;
+        }
+        groups.push(end - start);
+        start = end;
+    }
+    (ranks, groups)
+}
+
+/// Element of rank `k`, from 0, among the values `value(i, j)` of the rows
+/// `i` < `rows` and the columns `first(i)` ≤ `j` < `cols`, when `value` is
+/// nondecreasing in both `i` and `j`, `first` is nondecreasing, and `k` is
+/// less than the number of values.
+///
+/// This is the selection algorithm of [Monahan (1984)][monahan] for the
+/// Walsh averages, without building the sorted matrix: each step counts the
+/// values below a pivot row by row in linear time, the pivot being the
+/// weighted median of the row medians of the remaining candidates, so that
+/// every step discards at least a quarter of them.
+///
+/// [monahan]: https://doi.org/10.1145/1271.319414
+pub(crate) fn select_sorted_matrix<T, F, V>(
+    rows: usize,
+    cols: usize,
+    first: F,
+    value: V,
+    k: usize,
+) -> T
+where
+    T: Float,
+    F: Fn(usize) -> usize,
+    V: Fn(usize, usize) -> T,
+{
+    // Candidates: the columns lo[i] ≤ j < hi[i] of each row.
+    let mut lo = (0..rows).map(&first).collect::<Vec<_>>();
+    let mut hi = vec![cols; rows];
+    // Columns from which the values of each row are not below (resp. not
+    // below or equal to) the pivot.
+    let positions = |pivot: T, strict: bool| {
+        let above = |v: T| if strict { v >= pivot } else { v > pivot };
+        let mut pos = cols;
+        (0..rows)
+            .map(|i| {
+                // The values grow with i: the position only moves left.
+                pos = pos.max(first(i));
+                while pos > first(i) && above(value(i, pos - 1)) {
+                    pos -= 1;
+                }
+                pos
+            })
+            .collect::<Vec<_>>()
+    };
+    loop {
+        let below = (0..rows).map(|i| lo[i] - first(i)).sum::<usize>();
+        let remaining = (0..rows).map(|i| hi[i] - lo[i]).sum::<usize>();
+        if remaining <= rows.max(16) {
+            let mut candidates = (0..rows)
+                .flat_map(|i| (lo[i]..hi[i]).map(move |j| (i, j)))
+                .map(|(i, j)| value(i, j))
+                .collect::<Vec<_>>();
+            candidates.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            return candidates[k - below];
+        }
+        let mut medians = (0..rows)
+            .filter(|i| hi[*i] > lo[*i])
+            .map(|i| (value(i, (lo[i] + hi[i]) / 2), hi[i] - lo[i]))
+            .collect::<Vec<_>>();
+        medians.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let mut weight = 0;
+        let pivot = medians
+            .iter()
+            .find(|(_, w)| {
+                weight += w;
+                2 * weight >= remaining
+            })
+            .unwrap()
+            .0;
+        let less = positions(pivot, true);
+        let count_less = (0..rows).map(|i| less[i] - first(i)).sum::<usize>();
+        if k < count_less {
+            for (h, l) in hi.iter_mut().zip(&less) {
+                *h = (*h).min(*l);
+            }
+            continue;
+        }
+        let not_greater = positions(pivot, false);
+        let count_not_greater = (0..rows).map(|i| not_greater[i] - first(i)).sum::<usize>();
+        if k < count_not_greater {
+            return pivot;
+        }
+        for (l, g) in lo.iter_mut().zip(&not_greater) {
+            *l = (*l).max(*g);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_sorted_matrix_values() {
+        // Walsh averages and differences of samples with ties, against the
+        // sorted lists.
+        let x = (0..60)
+            .map(|i| f64::from((i * 37 % 23) / 2) - 3.5)
+            .collect::<Vec<_>>();
+        let mut sorted = x.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = sorted.len();
+        let mut walsh = Vec::new();
+        for i in 0..n {
+            for j in i..n {
+                walsh.push((sorted[i] + sorted[j]) / 2.);
+            }
+        }
+        walsh.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for (k, w) in walsh.iter().enumerate() {
+            let value = |i: usize, j: usize| (sorted[i] + sorted[j]) / 2.;
+            assert_eq!(select_sorted_matrix(n, n, |i| i, value, k), *w);
+        }
+        let y = [0.5, 2., 2., 7.];
+        let mut diffs = sorted
+            .iter()
+            .flat_map(|a| y.iter().map(move |b| a - b))
+            .collect::<Vec<_>>();
+        diffs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for (k, d) in diffs.iter().enumerate() {
+            let value = |i: usize, j: usize| sorted[i] - y[y.len() - 1 - j];
+            assert_eq!(select_sorted_matrix(n, y.len(), |_| 0, value, k), *d);
+        }
+    }
 }
@@ -0,0 +1,416 @@
+use ndarray::{Array1, Array2, ArrayBase, Axis, Data, Ix1, Ix2, Zip};
+use num_traits::{Float, FloatConst};
+use rand::{seq::SliceRandom, Rng};
+
+use super::TestOutput;
+use crate::distribution::{inc_gamma_upper, to_f64};
+
+/// Statistic of a test on counts O<sub>i</sub> with expected counts
+/// E<sub>i</sub> under H<sub>0</sub>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountStatistic {
+    /// Pearson's X<sup>2</sup> = ∑ (O<sub>i</sub> -
+    /// E<sub>i</sub>)<sup>2</sup> / E<sub>i</sub>.
+    Pearson,
+    /// The likelihood-ratio statistic of the G-test, G = 2 ∑ O<sub>i</sub>
+    /// ln(O<sub>i</sub> / E<sub>i</sub>).
+    LikelihoodRatio,
+}
+
+/// Table of observed counts with their expected counts under the null
+/// hypothesis of a goodness-of-fit or an independence test.
+///
+/// Both statistics of [`CountStatistic`] asymptotically follow the
+/// χ<sup>2</sup> distribution with k - 1 degrees of freedom for the
+/// goodness of fit of k categories, and (r - 1)(c - 1) for the independence
+/// of the rows and the columns of an r×c table. The goodness-of-fit counts
+/// are stored as a table with one row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContingencyTable<T> {
+    pub observed: Array2<T>,
+    pub expected: Array2<T>,
+    /// Degrees of freedom of the asymptotic χ<sup>2</sup> distribution.
+    pub df: T,
+    /// Whether the table tests the independence of its rows and columns.
+    pub independence: bool,
+}
+
+impl<T: Float + FloatConst> ContingencyTable<T> {
+    /// Goodness of fit of the `observed` counts of k ≥ 2 categories to the
+    /// expected `proportions`, which are normalized, for instance 1:1 for a
+    /// sample ratio mismatch check. Returns `None` when the lengths differ,
+    /// a count is negative or not finite, a proportion is not positive and
+    /// finite, or there is no observation.
+    pub fn goodness_of_fit<S1, S2>(
+        observed: &ArrayBase<S1, Ix1>,
+        proportions: &ArrayBase<S2, Ix1>,
+    ) -> Option<Self>
+    where
+        S1: Data<Elem = T>,
+        S2: Data<Elem = T>,
+    {
+        let k = observed.len();
+        if k < 2 || proportions.len() != k || !valid_counts(observed.iter()) {
+            return None;
+        }
+        if proportions
+            .iter()
+            .any(|p| !p.is_finite() || *p <= T::zero())
+        {
+            return None;
+        }
+        let n = observed.sum();
+        let total = proportions.sum();
+        if n <= T::zero() {
+            return None;
+        }
+        let expected = proportions.mapv(|p| n * p / total);
+        Some(Self {
+            observed: observed.to_owned().insert_axis(Axis(0)),
+            expected: expected.insert_axis(Axis(0)),
+            df: T::from(k - 1).unwrap(),
+            independence: false,
+        })
+    }
+
+    /// Independence of the rows and the columns of the r×c `table` of
+    /// counts, r, c ≥ 2, with the expected counts R<sub>i</sub>
+    /// C<sub>j</sub> / n from the row sums R<sub>i</sub> and the column sums
+    /// C<sub>j</sub>. Returns `None` when a count is negative or not finite,
+    /// or a row or a column is empty.
+    pub fn independence<S: Data<Elem = T>>(table: &ArrayBase<S, Ix2>) -> Option<Self> {
+        let (r, c) = table.dim();
+        if r < 2 || c < 2 || !valid_counts(table.iter()) {
+            return None;
+        }
+        let rows = table.sum_axis(Axis(1));
+        let cols = table.sum_axis(Axis(0));
+        if rows.iter().chain(&cols).any(|s| *s <= T::zero()) {
+            return None;
+        }
+        let n = rows.sum();
+        let expected = Array2::from_shape_fn((r, c), |(i, j)| rows[i] * cols[j] / n);
+        Some(Self {
+            observed: table.to_owned(),
+            expected,
+            df: T::from((r - 1) * (c - 1)).unwrap(),
+            independence: true,
+        })
+    }
+
+    /// Asymptotic χ<sup>2</sup> test. The output holds the degrees of
+    /// freedom.
+    pub fn test(&self, statistic: CountStatistic) -> TestOutput<T, T> {
+        let stat = compute_statistic(&self.observed, &self.expected, statistic);
+        self.asymptotic_output(stat)
+    }
+
+    /// Asymptotic χ<sup>2</sup> test of the independence of a 2×2 table with
+    /// Pearson's statistic after the continuity correction of Yates (1934),
+    /// which moves each count towards its expected count by min(1/2,
+    /// |O<sub>i</sub> - E<sub>i</sub>|), as R's `chisq.test` does. Returns
+    /// `None` for the other tables.
+    pub fn yates_test(&self) -> Option<TestOutput<T, T>> {
+        if !self.independence || self.observed.dim() != (2, 2) {
+            return None;
+        }
+        let half = T::from(0.5).unwrap();
+        let observed = Zip::from(&self.observed)
+            .and(&self.expected)
+            .map_collect(|o, e| {
+                let shift = (*o - *e).abs().min(half);
+                if o > e {
+                    *o - shift
+                } else {
+                    *o + shift
+                }
+            });
+        let stat = compute_statistic(&observed, &self.expected, CountStatistic::Pearson);
+        Some(self.asymptotic_output(stat))
+    }
+
+    /// Output of the asymptotic test of the statistic `stat`.
+    fn asymptotic_output(&self, stat: T) -> TestOutput<T, T> {
+        let half = T::from(0.5).unwrap();
+        TestOutput {
+            statistics: stat,
+            pvalue: inc_gamma_upper(self.df * half, stat * half),
+            estimate: None,
+            std_error: None,
+            df: Some(self.df),
+            interval: None,
+        }
+    }
+
+    /// Monte Carlo test: the p-value is (1 + b) / (B + 1), where b counts the
+    /// statistics at least as large as the observed one among `replicates`
+    /// = B tables drawn under H<sub>0</sub> with `rng`, as in
+    /// [Hope (1968)][hope]. The goodness-of-fit tables are multinomial draws
+    /// of the n observations, at a cost of O(n) each; the independence tables
+    /// permute the column labels of the n observations, and keep the margins.
+    /// Returns `None` when a count is not an integer or `replicates` is zero.
+    ///
+    /// [hope]: https://doi.org/10.1111/j.2517-6161.1968.tb00759.x
+    pub fn monte_carlo_test<R: Rng + ?Sized>(
+        &self,
+        statistic: CountStatistic,
+        replicates: usize,
+        rng: &mut R,
+    ) -> Option<TestOutput<T, T>> {
+        if replicates == 0 || self.observed.iter().any(|o| *o != o.floor()) {
+            return None;
+        }
+        let stat = compute_statistic(&self.observed, &self.expected, statistic);
+        // Tolerance on the ties of the statistics, as in R.
+        let threshold = stat * (T::one() - T::from(64.).unwrap() * T::epsilon());
+        let (r, c) = self.observed.dim();
+        let mut draws = Array2::zeros((r, c));
+        let mut exceed = 0usize;
+        if self.independence {
+            let (mut rows, mut cols) = (Vec::new(), Vec::new());
+            for ((i, j), o) in self.observed.indexed_iter() {
+                let count = o.to_usize()?;
+                rows.extend((0..count).map(|_| i));
+                cols.extend((0..count).map(|_| j));
+            }
+            for _ in 0..replicates {
+                cols.shuffle(rng);
+                draws.fill(T::zero());
+                for (i, j) in rows.iter().zip(&cols) {
+                    draws[[*i, *j]] = draws[[*i, *j]] + T::one();
+                }
+                if compute_statistic(&draws, &self.expected, statistic) >= threshold {
+                    exceed += 1;
+                }
+            }
+        } else {
+            let n = self.observed.sum().to_usize()?;
+            let total = to_f64(self.expected.sum());
+            let cumulated = self
+                .expected
+                .iter()
+                .scan(0., |s, e| {
+                    *s += to_f64(*e) / total;
+                    Some(*s)
+                })
+                .collect::<Vec<_>>();
+            for _ in 0..replicates {
+                draws.fill(T::zero());
+                for _ in 0..n {
+                    let u = rng.gen::<f64>();
+                    let j = cumulated.partition_point(|p| *p <= u).min(c - 1);
+                    draws[[0, j]] = draws[[0, j]] + T::one();
+                }
+                if compute_statistic(&draws, &self.expected, statistic) >= threshold {
+                    exceed += 1;
+                }
+            }
+        }
+        let pvalue = T::from(1 + exceed).unwrap() / T::from(1 + replicates).unwrap();
+        Some(TestOutput {
+            statistics: stat,
+            pvalue: Some(pvalue),
+            estimate: None,
+            std_error: None,
+            df: None,
+            interval: None,
+        })
+    }
+
+    /// Standardized (Pearson) residuals (O<sub>i</sub> - E<sub>i</sub>) /
+    /// E<sub>i</sub><sup>1/2</sup>, whose squares sum to X<sup>2</sup>.
+    pub fn standardized_residuals(&self) -> Array2<T> {
+        (&self.observed - &self.expected) / self.expected.mapv(T::sqrt)
+    }
+
+    /// Adjusted residuals of [Haberman (1973)][haberman], the residuals
+    /// divided by their asymptotic standard deviation, which are standard
+    /// normal under H<sub>0</sub>: (O<sub>ij</sub> - E<sub>ij</sub>) /
+    /// (E<sub>ij</sub> (1 - R<sub>i</sub>/n)(1 - C<sub>j</sub>/n))<sup>1/2</sup>
+    /// for the independence, and (O<sub>i</sub> - E<sub>i</sub>) /
+    /// (E<sub>i</sub> (1 - p<sub>i</sub>))<sup>1/2</sup> for the goodness of
+    /// fit.
+    ///
+    /// [haberman]: https://doi.org/10.2307/2529319
+    pub fn adjusted_residuals(&self) -> Array2<T> {
+        let one = T::one();
+        let n = self.observed.sum();
+        let rows = self.observed.sum_axis(Axis(1));
+        let cols = self.observed.sum_axis(Axis(0));
+        let col_shares: Array1<T> = if self.independence {
+            cols.mapv(|c| c / n)
+        } else {
+            self.expected.row(0).mapv(|e| e / n)
+        };
+        Array2::from_shape_fn(self.observed.dim(), |(i, j)| {
+            let e = self.expected[[i, j]];
+            let row_share = if self.independence {
+                rows[i] / n
+            } else {
+                T::zero()
+            };
+            (self.observed[[i, j]] - e) / (e * (one - row_share) * (one - col_shares[j])).sqrt()
+        })
+    }
+
+    /// Cramér's V = (X<sup>2</sup> / (n (min(r, c) - 1)))<sup>1/2</sup>, in
+    /// \[0, 1\], from Pearson's X<sup>2</sup> without correction. Returns
+    /// `None` for a goodness of fit.
+    pub fn cramers_v(&self) -> Option<T> {
+        if !self.independence {
+            return None;
+        }
+        let (r, c) = self.observed.dim();
+        let stat = compute_statistic(&self.observed, &self.expected, CountStatistic::Pearson);
+        let n = self.observed.sum();
+        Some((stat / (n * T::from(r.min(c) - 1).unwrap())).sqrt())
+    }
+
+    /// Pearson's contingency coefficient C = (X<sup>2</sup> /
+    /// (X<sup>2</sup> + n))<sup>1/2</sup>, from Pearson's X<sup>2</sup>
+    /// without correction. Returns `None` for a goodness of fit.
+    pub fn contingency_coefficient(&self) -> Option<T> {
+        if !self.independence {
+            return None;
+        }
+        let stat = compute_statistic(&self.observed, &self.expected, CountStatistic::Pearson);
+        Some((stat / (stat + self.observed.sum())).sqrt())
+    }
+}
+
+/// Whether the counts are non-negative and finite.
+fn valid_counts<'a, T: Float + 'a>(mut counts: impl Iterator<Item = &'a T>) -> bool {
+    counts.all(|o| o.is_finite() && *o >= T::zero())
+}
+
+fn compute_statistic<T: Float>(
+    observed: &Array2<T>,
+    expected: &Array2<T>,
+    statistic: CountStatistic,
+) -> T {
+    let terms = observed.iter().zip(expected);
+    match statistic {
+        CountStatistic::Pearson => terms.fold(T::zero(), |s, (o, e)| s + (*o - *e).powi(2) / *e),
+        CountStatistic::LikelihoodRatio => {
+            // The terms O ln(O/E) - (O - E) are non-negative and the O - E
+            // sum to zero: their sum avoids the cancellations of ∑ O ln(O/E).
+            let sum = terms.fold(T::zero(), |s, (o, e)| {
+                let d = *o - *e;
+                if *o > T::zero() {
+                    s + *o * (d / *e).ln_1p() - d
+                } else {
+                    s + *e
+                }
+            });
+            sum + sum
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, arr2};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        data::lalonde::{BLACK, HISPANIC, MARRIED, TREAT},
+        test_util::rel,
+    };
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn chi_squared_values() {
+        // The upper tails are gammainc(k/2, x/2, inf, regularized=True).
+        // Sample ratio mismatch of the Lalonde treatment against 1:1.
+        let treated = TREAT.iter().filter(|t| **t == 1).count() as f64;
+        let counts = arr1(&[treated, TREAT.len() as f64 - treated]);
+        let srm = ContingencyTable::goodness_of_fit(&counts, &arr1(&[1., 1.])).unwrap();
+        let output = srm.test(CountStatistic::Pearson);
+        assert!(rel(output.statistics, 22.692520775623269) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 1.9010638466454347e-6) < 1e-12);
+        let output = srm.test(CountStatistic::LikelihoodRatio);
+        assert!(rel(output.statistics, 22.812912201877830) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 1.7856199176084651e-6) < 1e-12);
+        assert!(srm.cramers_v().is_none());
+        // Race against the proportions 0.2, 0.1, 0.7.
+        let race = |black: i8, hispanic: i8| 2 * black as usize + hispanic as usize;
+        let mut counts = arr1(&[0., 0., 0.]);
+        for (b, h) in BLACK.iter().zip(HISPANIC) {
+            counts[race(*b, h)] += 1.;
+        }
+        let fit = ContingencyTable::goodness_of_fit(&counts, &arr1(&[2., 1., 7.])).unwrap();
+        let output = fit.test(CountStatistic::Pearson);
+        assert_eq!(output.df, Some(2.));
+        assert!(rel(output.statistics, 51.051048674317372) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 8.2111811370127376e-12) < 1e-12);
+        let output = fit.test(CountStatistic::LikelihoodRatio);
+        assert!(rel(output.pvalue.unwrap(), 7.1422293857309818e-14) < 1e-12);
+        let adjusted = fit.adjusted_residuals();
+        assert!(rel(adjusted[[0, 0]], -7.1082839582437146) < 1e-12);
+        assert!(rel(adjusted[[0, 2]], 5.8960122143409856) < 1e-12);
+        assert!(rel(fit.standardized_residuals()[[0, 1]], 0.44721359549995794) < 1e-12);
+        // Balance of race across the treatment groups.
+        let mut table = Array2::zeros((3, 2));
+        for ((b, h), t) in BLACK.iter().zip(HISPANIC).zip(TREAT) {
+            table[[race(*b, h), t as usize]] += 1.;
+        }
+        let balance = ContingencyTable::independence(&table).unwrap();
+        let output = balance.test(CountStatistic::Pearson);
+        assert!(rel(output.statistics, 1.1357454592056668) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.56672974471224052) < 1e-12);
+        let output = balance.test(CountStatistic::LikelihoodRatio);
+        assert!(rel(output.statistics, 1.1374511872253966) < 1e-12);
+        let standardized = balance.standardized_residuals();
+        assert!(rel(standardized[[0, 1]], 0.57246491876403475) < 1e-12);
+        let adjusted = balance.adjusted_residuals();
+        assert!(rel(adjusted[[1, 0]], 0.80417050476789041) < 1e-12);
+        assert!(rel(adjusted[[2, 1]], 0.044565590486079068) < 1e-12);
+        assert!(rel(balance.cramers_v().unwrap(), 0.039661753020251484) < 1e-12);
+        let coefficient = balance.contingency_coefficient().unwrap();
+        assert!(rel(coefficient, 0.039630594723154435) < 1e-12);
+        // Yates correction of a 2×2 table.
+        let mut table = Array2::zeros((2, 2));
+        for (m, t) in MARRIED.iter().zip(TREAT) {
+            table[[*m as usize, t as usize]] += 1.;
+        }
+        let married = ContingencyTable::independence(&table).unwrap();
+        let output = married.yates_test().unwrap();
+        assert!(rel(output.statistics, 0.079199232140408611) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.77838553337591600) < 1e-12);
+        // The correction only applies to the independence of a 2×2 table.
+        assert!(srm.yates_test().is_none());
+        assert!(balance.yates_test().is_none());
+        assert!(rel(married.adjusted_residuals()[[1, 1]], 0.38404369535875421) < 1e-12);
+        // Monte Carlo p-values agree with the asymptotic ones, and only
+        // depend on the seed.
+        let mut rng = StdRng::seed_from_u64(46);
+        let replicates = 2000;
+        for (table, pvalue) in [(&balance, 0.56672974471224052), (&fit, 1. / 2001.)] {
+            let output = table
+                .monte_carlo_test(CountStatistic::Pearson, replicates, &mut rng)
+                .unwrap();
+            let p = output.pvalue.unwrap();
+            assert!((p - pvalue).abs() < 4. * (pvalue * (1. - pvalue) / 2000.).sqrt() + 1e-3);
+        }
+        let draw = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            srm.monte_carlo_test(CountStatistic::LikelihoodRatio, 100, &mut rng)
+                .unwrap()
+                .pvalue
+        };
+        assert_eq!(draw(1), draw(1));
+        // Invalid tables.
+        assert!(ContingencyTable::independence(&arr2(&[[1., 2.], [0., 0.]])).is_none());
+        assert!(ContingencyTable::independence(&arr2(&[[1., -2.], [1., 3.]])).is_none());
+        let counts = arr1(&[1., 2.]);
+        assert!(ContingencyTable::goodness_of_fit(&counts, &arr1(&[1., 0.])).is_none());
+        assert!(ContingencyTable::goodness_of_fit(&counts, &arr1(&[1.])).is_none());
+        let fractional = ContingencyTable::goodness_of_fit(&arr1(&[1.5, 2.]), &arr1(&[1., 1.]));
+        let mut rng = StdRng::seed_from_u64(0);
+        let test = fractional
+            .unwrap()
+            .monte_carlo_test(CountStatistic::Pearson, 10, &mut rng);
+        assert!(test.is_none());
+    }
+}
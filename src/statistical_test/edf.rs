@@ -0,0 +1,598 @@
+use std::borrow::Borrow;
+
+use ndarray::{Array1, Array2};
+use num_traits::{Float, FloatConst};
+
+use super::{average_ranks, PValueMethod, TestOutput, TestTSide};
+use crate::distribution::{
+    cholesky, cholesky_solve, ln_binomial, sf_anderson_darling_limit, sf_cramer_von_mises_limit,
+    sf_kolmogorov, sf_kolmogorov_limit, Continuous,
+};
+
+/// Sample size from which [`PValueMethod::Auto`] uses the asymptotic
+/// distribution of the one-sample Kolmogorov-Smirnov statistic.
+const EXACT_MAX_LEN: usize = 100;
+/// Product of the sample sizes from which [`PValueMethod::Auto`] uses the
+/// asymptotic distribution of the two-sample Kolmogorov-Smirnov statistic.
+const EXACT_MAX_PRODUCT: usize = 10_000;
+/// Significance levels of the critical values of the standardized k-sample
+/// Anderson-Darling statistic, with the coefficients b<sub>0</sub>,
+/// b<sub>1</sub> and b<sub>2</sub> of their interpolation b<sub>0</sub> +
+/// b<sub>1</sub> / m<sup>1/2</sup> + b<sub>2</sub> / m in the number m of
+/// samples minus one.
+const ANDERSON_DARLING_CRITICAL: [(f64, f64, f64, f64); 7] = [
+    (0.25, 0.675, -0.245, -0.105),
+    (0.1, 1.281, 0.25, -0.305),
+    (0.05, 1.645, 0.678, -0.362),
+    (0.025, 1.96, 1.149, -0.391),
+    (0.01, 2.326, 1.822, -0.396),
+    (0.005, 2.573, 2.364, -0.345),
+    (0.001, 3.085, 3.615, -0.154),
+];
+
+/// One-sample Kolmogorov-Smirnov test.
+///
+/// Tests whether `sample` is drawn from the continuous `distribution`, of
+/// any type implementing [`Continuous`]. The two-sided statistic is D =
+/// sup |F<sub>n</sub> - F|; the one-sided statistics are D<sup>-</sup> =
+/// sup (F - F<sub>n</sub>) for [`TestTSide::UpperOneSided`], against a
+/// sample stochastically larger than F, and D<sup>+</sup> = sup
+/// (F<sub>n</sub> - F) for [`TestTSide::LowerOneSided`].
+///
+/// The exact p-values come from [`sf_kolmogorov`] for the two-sided test and
+/// from the formula of [Birnbaum and Tingey (1951)][bt] for the one-sided
+/// tests, and the asymptotic ones from the Kolmogorov distribution and from
+/// exp(-2nd<sup>2</sup>). [`PValueMethod::Auto`] is exact below 100
+/// observations.
+///
+/// `sample` can be a slice, an ndarray view or an iterator of values.
+/// Returns `None` when the sample is empty or has a NaN.
+///
+/// [bt]: https://doi.org/10.1214/aoms/1177729550
+pub fn kolmogorov_smirnov_test<T, D, I>(
+    sample: I,
+    distribution: &D,
+    test_type: TestTSide,
+    method: PValueMethod,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    D: Continuous<T> + ?Sized,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+{
+    let sorted = sorted_sample(sample)?;
+    let n = sorted.len();
+    let nf = T::from(n).unwrap();
+    let (mut above, mut below) = (T::zero(), T::zero());
+    for (i, x) in sorted.iter().enumerate() {
+        let cdf = distribution.cdf(*x);
+        above = above.max(T::from(i + 1).unwrap() / nf - cdf);
+        below = below.max(cdf - T::from(i).unwrap() / nf);
+    }
+    let stat = match test_type {
+        TestTSide::TwoSided => above.max(below),
+        TestTSide::UpperOneSided => below,
+        TestTSide::LowerOneSided => above,
+    };
+    let exact = match method {
+        PValueMethod::Exact => true,
+        PValueMethod::Asymptotic => false,
+        PValueMethod::Auto => n < EXACT_MAX_LEN,
+    };
+    let pvalue = match (test_type, exact) {
+        (TestTSide::TwoSided, true) => sf_kolmogorov(stat, n)?,
+        (TestTSide::TwoSided, false) => sf_kolmogorov_limit(nf.sqrt() * stat)?,
+        (_, true) => one_sided_kolmogorov_sf(stat, n),
+        (_, false) => (-(nf + nf) * stat * stat).exp(),
+    };
+    Some(statistic_output(stat, pvalue))
+}
+
+/// Two-sample Kolmogorov-Smirnov test.
+///
+/// Tests whether `sample1` and `sample2` are drawn from the same
+/// distribution, with the statistic D = sup |F<sub>1</sub> -
+/// F<sub>2</sub>| of their empirical distribution functions, or the
+/// one-sided sup (F<sub>2</sub> - F<sub>1</sub>) for
+/// [`TestTSide::UpperOneSided`], against a first sample stochastically
+/// larger, and sup (F<sub>1</sub> - F<sub>2</sub>) for
+/// [`TestTSide::LowerOneSided`].
+///
+/// The exact p-value is the probability over the equally likely
+/// assignments of the pooled values to the samples that the statistic
+/// reaches its observed value, computed by counting lattice paths as in
+/// [Hodges (1958)][hodges], which stays exact with ties by only checking the
+/// boundary between distinct values. The asymptotic p-values come from the
+/// Kolmogorov distribution and from exp(-2 m n d<sup>2</sup> / (m + n)).
+/// [`PValueMethod::Auto`] is exact when the product of the sample sizes is
+/// below 10000.
+///
+/// `sample1` and `sample2` can be slices, ndarray views or iterators of
+/// values. Returns `None` when a sample is empty or has a NaN.
+///
+/// [hodges]: https://doi.org/10.1007/BF02589501
+pub fn two_sample_kolmogorov_smirnov_test<T, I1, I2>(
+    sample1: I1,
+    sample2: I2,
+    test_type: TestTSide,
+    method: PValueMethod,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let (x, y) = (sorted_sample(sample1)?, sorted_sample(sample2)?);
+    let (m, n) = (x.len(), y.len());
+    // Pooled values in increasing order, flagged by their sample, and the
+    // positions after which the empirical distribution functions are
+    // compared, the last of each group of ties.
+    let mut pooled = x
+        .iter()
+        .map(|v| (*v, true))
+        .chain(y.iter().map(|v| (*v, false)))
+        .collect::<Vec<_>>();
+    pooled.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let total = m + n;
+    let checked = (0..total)
+        .map(|k| k + 1 == total || pooled[k].0 != pooled[k + 1].0)
+        .collect::<Vec<_>>();
+    // The statistic in units of 1/(mn) at i values of the first sample and
+    // j of the second.
+    let (mi, ni) = (m as i64, n as i64);
+    let spread = |i: usize, j: usize| {
+        let diff = i as i64 * ni - j as i64 * mi;
+        match test_type {
+            TestTSide::TwoSided => diff.abs(),
+            TestTSide::UpperOneSided => -diff,
+            TestTSide::LowerOneSided => diff,
+        }
+    };
+    let (mut i, mut observed) = (0, 0);
+    for (k, (_, first)) in pooled.iter().enumerate() {
+        i += usize::from(*first);
+        if checked[k] {
+            observed = observed.max(spread(i, k + 1 - i));
+        }
+    }
+    let stat = T::from(observed).unwrap() / (T::from(m).unwrap() * T::from(n).unwrap());
+    let exact = match method {
+        PValueMethod::Exact => true,
+        PValueMethod::Asymptotic => false,
+        PValueMethod::Auto => m * n < EXACT_MAX_PRODUCT,
+    };
+    let pvalue = if exact {
+        // probs[i]: probability of the paths to i values of the first
+        // sample among the first k which did not reach the statistic yet.
+        let mut probs = vec![T::one()];
+        let mut reached = T::zero();
+        for (k, check) in checked.iter().enumerate() {
+            let left = T::from(total - k).unwrap();
+            let mut next = vec![T::zero(); probs.len() + 1];
+            for (i, p) in probs.iter().enumerate() {
+                let j = k - i;
+                if i < m {
+                    next[i + 1] = next[i + 1] + *p * T::from(m - i).unwrap() / left;
+                }
+                if j < n {
+                    next[i] = next[i] + *p * T::from(n - j).unwrap() / left;
+                }
+            }
+            if *check {
+                for (i, p) in next.iter_mut().enumerate() {
+                    if i <= m && k + 1 - i <= n && spread(i, k + 1 - i) >= observed {
+                        reached = reached + *p;
+                        *p = T::zero();
+                    }
+                }
+            }
+            probs = next;
+        }
+        reached.min(T::one())
+    } else {
+        let size = T::from(m * n).unwrap() / T::from(total).unwrap();
+        match test_type {
+            TestTSide::TwoSided => sf_kolmogorov_limit(size.sqrt() * stat)?,
+            _ => (-(size + size) * stat * stat).exp(),
+        }
+    };
+    Some(statistic_output(stat, pvalue))
+}
+
+/// One-sample Anderson-Darling test.
+///
+/// Tests whether `sample` is drawn from the continuous `distribution`, of
+/// any type implementing [`Continuous`], with the statistic A<sup>2</sup> =
+/// -n - ∑ (2i - 1) (ln F(x<sub>(i)</sub>) + ln(1 - F(x<sub>(n+1-i)</sub>)))
+/// / n, which weighs the tails more than [`kolmogorov_smirnov_test`]. The
+/// p-value comes from the limiting distribution, see
+/// [`sf_anderson_darling_limit`], accurate from a few observations.
+///
+/// `sample` can be a slice, an ndarray view or an iterator of values.
+/// Returns `None` when the sample is empty or has a NaN.
+pub fn anderson_darling_test<T, D, I>(sample: I, distribution: &D) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    D: Continuous<T> + ?Sized,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+{
+    let sorted = sorted_sample(sample)?;
+    let n = sorted.len();
+    let sum = (0..n).fold(T::zero(), |s, i| {
+        let lower = distribution.cdf(sorted[i]).ln();
+        let upper = distribution.sf(sorted[n - 1 - i]).ln();
+        s + T::from(2 * i + 1).unwrap() * (lower + upper)
+    });
+    let nf = T::from(n).unwrap();
+    let stat = -nf - sum / nf;
+    Some(statistic_output(stat, sf_anderson_darling_limit(stat)?))
+}
+
+/// k-sample Anderson-Darling test.
+///
+/// Tests whether the k ≥ 2 `samples` are drawn from the same distribution,
+/// with the statistic A<sup>2</sup><sub>akN</sub> of [Scholz and Stephens
+/// (1987)][ss] for samples with ties, standardized by its mean k - 1 and its
+/// standard deviation under H<sub>0</sub>. The statistic of the output is
+/// the standardized one, and the p-value is interpolated, as in SciPy, from
+/// the critical values of Scholz and Stephens at the levels 0.25 to 0.001,
+/// by a quadratic in the statistic of the logarithm of the level; it is
+/// capped to this range.
+///
+/// Each sample can be a slice, an ndarray view or an iterator of values.
+/// Returns `None` when there are less than two samples, when a sample is
+/// empty or has a NaN, when there are less than four values or when all the
+/// values are equal.
+///
+/// [ss]: https://doi.org/10.1080/01621459.1987.10478517
+pub fn k_sample_anderson_darling_test<T, I>(samples: I) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    <I::Item as IntoIterator>::Item: Borrow<T>,
+{
+    let samples = samples
+        .into_iter()
+        .map(sorted_sample)
+        .collect::<Option<Vec<_>>>()?;
+    let k = samples.len();
+    let mut pooled = samples.concat();
+    let total = pooled.len();
+    if k < 2 || total < 4 {
+        return None;
+    }
+    pooled.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    // Distinct values and their multiplicities.
+    let mut values: Vec<T> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+    for v in &pooled {
+        match values.last() {
+            Some(last) if *last == *v => *counts.last_mut().unwrap() += 1,
+            _ => {
+                values.push(*v);
+                counts.push(1);
+            }
+        }
+    }
+    if values.len() < 2 {
+        return None;
+    }
+    let (zero, one) = (T::zero(), T::one());
+    let (two, four) = (one + one, T::from(4.).unwrap());
+    let nf = T::from(total).unwrap();
+    let mut stat = zero;
+    for sample in &samples {
+        let size = T::from(sample.len()).unwrap();
+        let (mut below, mut pooled_below, mut start) = (0, 0, 0);
+        let mut inner = zero;
+        for (v, l) in values.iter().zip(&counts) {
+            let ties = sample[start..].iter().take_while(|x| **x == *v).count();
+            start += ties;
+            below += ties;
+            pooled_below += l;
+            // Midrank versions of the counts at or below the value.
+            let l = T::from(*l).unwrap();
+            let m = T::from(below).unwrap() - T::from(ties).unwrap() / two;
+            let b = T::from(pooled_below).unwrap() - l / two;
+            inner = inner + l / nf * (nf * m - size * b).powi(2) / (b * (nf - b) - nf * l / four);
+        }
+        stat = stat + inner / size;
+    }
+    stat = stat * (nf - one) / nf;
+    // Variance of the statistic under H0, see Scholz and Stephens (1987).
+    let kf = T::from(k).unwrap();
+    let inverse_sizes = samples
+        .iter()
+        .fold(zero, |s, x| s + T::from(x.len()).unwrap().recip());
+    let harmonic = (1..total).fold(zero, |s, i| s + T::from(i).unwrap().recip());
+    // g = ∑_{i=1}^{N-2} (h_{N-1} - h_i) / (N - i), h_i the harmonic numbers.
+    let (mut g, mut partial) = (zero, zero);
+    for i in 1..total - 1 {
+        partial = partial + T::from(i).unwrap().recip();
+        g = g + (harmonic - partial) / T::from(total - i).unwrap();
+    }
+    let (h, c) = (harmonic, |x: f64| T::from(x).unwrap());
+    let a = (c(4.) * g - c(6.)) * (kf - one) + (c(10.) - c(6.) * g) * inverse_sizes;
+    let b =
+        (two * g - four) * kf * kf + c(8.) * h * kf + (two * g - c(14.) * h - four) * inverse_sizes
+            - c(8.) * h
+            + four * g
+            - c(6.);
+    let cc = (c(6.) * h + two * g - two) * kf * kf
+        + (four * h - four * g + c(6.)) * kf
+        + (two * h - c(6.)) * inverse_sizes
+        + four * h;
+    let d = (two * h + c(6.)) * kf * kf - four * h * kf;
+    let var = (((a * nf + b) * nf + cc) * nf + d) / ((nf - one) * (nf - two) * (nf - c(3.)));
+    let standardized = (stat - (kf - one)) / var.sqrt();
+    let pvalue = interpolate_anderson_darling(standardized, kf - one)?;
+    Some(statistic_output(standardized, pvalue))
+}
+
+/// One-sample Cramér-von Mises test.
+///
+/// Tests whether `sample` is drawn from the continuous `distribution`, of
+/// any type implementing [`Continuous`], with the statistic W<sup>2</sup> =
+/// 1/(12n) + ∑ (F(x<sub>(i)</sub>) - (2i - 1)/(2n))<sup>2</sup>. The p-value
+/// comes from the limiting distribution, see
+/// [`sf_cramer_von_mises_limit`].
+///
+/// `sample` can be a slice, an ndarray view or an iterator of values.
+/// Returns `None` when the sample is empty or has a NaN.
+pub fn cramer_von_mises_test<T, D, I>(sample: I, distribution: &D) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    D: Continuous<T> + ?Sized,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+{
+    let sorted = sorted_sample(sample)?;
+    let nf = T::from(sorted.len()).unwrap();
+    let stat = sorted
+        .iter()
+        .enumerate()
+        .fold((T::from(12.).unwrap() * nf).recip(), |s, (i, x)| {
+            let position = T::from(2 * i + 1).unwrap() / (nf + nf);
+            s + (distribution.cdf(*x) - position).powi(2)
+        });
+    Some(statistic_output(stat, sf_cramer_von_mises_limit(stat)?))
+}
+
+/// Two-sample Cramér-von Mises test.
+///
+/// Tests whether `sample1` and `sample2` are drawn from the same
+/// distribution, with the statistic T = U / (m n (m + n)) - (4mn - 1) /
+/// (6(m + n)) of [Anderson (1962)][anderson], where U = m ∑
+/// (r<sub>i</sub> - i)<sup>2</sup> + n ∑ (s<sub>j</sub> - j)<sup>2</sup>
+/// from the pooled ranks r<sub>i</sub> and s<sub>j</sub> of the sorted
+/// samples, ties receiving their average rank. The p-value comes from the limiting distribution,
+/// see [`sf_cramer_von_mises_limit`], of T standardized by its exact mean
+/// and variance.
+///
+/// `sample1` and `sample2` can be slices, ndarray views or iterators of
+/// values. Returns `None` when a sample is empty or has a NaN, or when both
+/// have a single value.
+///
+/// [anderson]: https://doi.org/10.1214/aoms/1177704477
+pub fn two_sample_cramer_von_mises_test<T, I1, I2>(
+    sample1: I1,
+    sample2: I2,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let (x, y) = (sorted_sample(sample1)?, sorted_sample(sample2)?);
+    let (m, n) = (x.len(), y.len());
+    if m + n < 3 {
+        return None;
+    }
+    let (ranks, _) = average_ranks(&[x, y].concat());
+    let squares = |ranks: &[T]| {
+        ranks.iter().enumerate().fold(T::zero(), |s, (i, r)| {
+            s + (*r - T::from(i + 1).unwrap()).powi(2)
+        })
+    };
+    // The ranks of each sorted sample are increasing.
+    let (mf, nf) = (T::from(m).unwrap(), T::from(n).unwrap());
+    let (one, c) = (T::one(), |x: f64| T::from(x).unwrap());
+    let total = mf + nf;
+    let product = mf * nf;
+    let u = mf * squares(&ranks[..m]) + nf * squares(&ranks[m..]);
+    let stat = u / (product * total) - (c(4.) * product - one) / (c(6.) * total);
+    let mean = (one + total.recip()) / c(6.);
+    let var = (total + one)
+        * (c(4.) * product * total - c(3.) * (mf * mf + nf * nf) - c(2.) * product)
+        / (c(180.) * total * total * product);
+    let standardized = (stat - mean) / (c(45.) * var).sqrt() + one / c(6.);
+    Some(statistic_output(
+        stat,
+        sf_cramer_von_mises_limit(standardized)?,
+    ))
+}
+
+/// Values of `sample` in increasing order, or `None` when it is empty or
+/// has a NaN.
+fn sorted_sample<T, I>(sample: I) -> Option<Vec<T>>
+where
+    T: Float,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+{
+    let mut sorted = sample.into_iter().map(|x| *x.borrow()).collect::<Vec<_>>();
+    if sorted.is_empty() || sorted.iter().any(|x| x.is_nan()) {
+        return None;
+    }
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Some(sorted)
+}
+
+fn statistic_output<T>(stat: T, pvalue: T) -> TestOutput<T, T> {
+    TestOutput {
+        statistics: stat,
+        pvalue: Some(pvalue),
+        estimate: None,
+        std_error: None,
+        df: None,
+        interval: None,
+    }
+}
+
+/// P(D<sup>+</sup><sub>n</sub> ≥ `d`) = d ∑<sub>j ≤ n(1-d)</sub> C(n, j)
+/// (1 - d - j/n)<sup>n-j</sup> (d + j/n)<sup>j-1</sup>, see [Birnbaum and
+/// Tingey (1951)][bt].
+///
+/// [bt]: https://doi.org/10.1214/aoms/1177729550
+fn one_sided_kolmogorov_sf<T: Float + FloatConst>(d: T, n: usize) -> T {
+    let (zero, one) = (T::zero(), T::one());
+    if d <= zero {
+        return one;
+    }
+    if d >= one {
+        return zero;
+    }
+    let nf = T::from(n).unwrap();
+    let last = (nf * (one - d)).floor().to_usize().unwrap_or(0).min(n);
+    let sum = (0..=last).fold(zero, |s, j| {
+        let jf = T::from(j).unwrap();
+        let rest = one - d - jf / nf;
+        if rest <= zero && j < n {
+            return s;
+        }
+        let ln_term =
+            ln_binomial(nf, jf).unwrap() + (nf - jf) * rest.ln() + (jf - one) * (d + jf / nf).ln();
+        s + ln_term.exp()
+    });
+    (d * sum).min(one)
+}
+
+/// P-value of the standardized k-sample Anderson-Darling statistic `stat`
+/// with `m` + 1 samples, see [`k_sample_anderson_darling_test`].
+fn interpolate_anderson_darling<T: Float>(stat: T, m: T) -> Option<T> {
+    let critical = ANDERSON_DARLING_CRITICAL.map(|(level, b0, b1, b2)| {
+        let c = |x: f64| T::from(x).unwrap();
+        (c(level), c(b0) + c(b1) / m.sqrt() + c(b2) / m)
+    });
+    let (first, last) = (critical[0], critical[critical.len() - 1]);
+    if stat < first.1 {
+        return Some(first.0);
+    }
+    if stat > last.1 {
+        return Some(last.0);
+    }
+    // Least squares of ln(level) on (t^2, t, 1) by the normal equations.
+    let mut gram = Array2::<T>::zeros((3, 3));
+    let mut rhs = Array1::<T>::zeros(3);
+    for (level, t) in critical {
+        let row = [t * t, t, T::one()];
+        for r in 0..3 {
+            rhs[r] = rhs[r] + row[r] * level.ln();
+            for c in 0..3 {
+                gram[[r, c]] = gram[[r, c]] + row[r] * row[c];
+            }
+        }
+    }
+    let coefficients = cholesky_solve(&cholesky(&gram)?, &rhs);
+    Some((coefficients[0] * stat * stat + coefficients[1] * stat + coefficients[2]).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{earnings, rel};
+    use crate::Normal;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn edf_values() {
+        // The limiting distributions are summed from their series, the exact
+        // one-sample Kolmogorov distribution is computed from the matrix of
+        // Marsaglia, Tsang and Wang, and the exact two-sample ones by
+        // enumerating the assignments to the samples, or by counting the
+        // lattice paths for the 20 Lalonde units.
+        // Permeability constants of the human chorioamnion at term and at 12
+        // to 26 weeks of gestational age, from Hollander and Wolfe.
+        let x = [0.80, 0.83, 1.89, 1.04, 1.45, 1.38, 1.91, 1.64, 0.73, 1.46];
+        let y = [1.15, 0.88, 0.90, 0.74, 1.21];
+        let normal = Normal::new(1.3, 0.4).unwrap();
+        let test = |side, method| kolmogorov_smirnov_test(x, &normal, side, method).unwrap();
+        let output = test(TestTSide::TwoSided, PValueMethod::Auto);
+        assert!(rel(output.statistics, 0.180002642100987530) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.847268588364562967) < 1e-12);
+        let output = test(TestTSide::TwoSided, PValueMethod::Asymptotic);
+        assert!(rel(output.pvalue.unwrap(), 0.902233786932183106) < 1e-12);
+        let output = test(TestTSide::UpperOneSided, PValueMethod::Exact);
+        assert!(rel(output.statistics, 0.179259709439102919) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.470922797253711508) < 1e-12);
+        let output = test(TestTSide::UpperOneSided, PValueMethod::Asymptotic);
+        assert!(rel(output.pvalue.unwrap(), 0.525880715507504137) < 1e-12);
+        let output = test(TestTSide::LowerOneSided, PValueMethod::Exact);
+        assert!(rel(output.pvalue.unwrap(), 0.468142379339409447) < 1e-12);
+        let output = anderson_darling_test(x, &normal).unwrap();
+        assert!(rel(output.statistics, 0.477473431769606699) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.769882803539151946) < 1e-10);
+        let output = cramer_von_mises_test(x, &normal).unwrap();
+        assert!(rel(output.statistics, 0.0717206263656400466) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.741047090620305820) < 1e-10);
+
+        let test =
+            |side| two_sample_kolmogorov_smirnov_test(x, y, side, PValueMethod::Auto).unwrap();
+        let output = test(TestTSide::TwoSided);
+        assert!(rel(output.statistics, 0.6) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.165834165834165834) < 1e-12);
+        assert!(
+            rel(
+                test(TestTSide::UpperOneSided).pvalue.unwrap(),
+                0.0829170829170829171
+            ) < 1e-12
+        );
+        let output = test(TestTSide::LowerOneSided);
+        assert!(rel(output.statistics, 0.1) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 10. / 11.) < 1e-12);
+        let side = TestTSide::TwoSided;
+        let output = two_sample_kolmogorov_smirnov_test(x, y, side, PValueMethod::Asymptotic);
+        assert!(rel(output.unwrap().pvalue.unwrap(), 0.181300449938122736) < 1e-12);
+        let output = two_sample_cramer_von_mises_test(x, y).unwrap();
+        assert!(rel(output.statistics, 0.275555555555555556) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.164788844869315036) < 1e-10);
+        let z = [1.83, 0.50, 1.62, 2.48, 1.68, 1.88, 1.55, 3.06, 1.30];
+        let output = k_sample_anderson_darling_test([&x[..], &y, &z]).unwrap();
+        assert!(rel(output.statistics, 1.93491788055510632) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.0501747927065150564) < 1e-12);
+
+        // Lalonde earnings in 1978 of the treated against the controls, with
+        // many zeros.
+        let (treated, controls) = (earnings(1), earnings(0));
+        let test = |side, len: Option<usize>| {
+            let len = |sample: &[f64]| len.unwrap_or(sample.len());
+            let (x, y) = (&treated[..len(&treated)], &controls[..len(&controls)]);
+            two_sample_kolmogorov_smirnov_test(x, y, side, PValueMethod::Auto).unwrap()
+        };
+        let output = test(TestTSide::TwoSided, None);
+        assert!(rel(output.statistics, 0.0940463458110516934) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.0907602344923749988) < 1e-12);
+        let output = test(TestTSide::UpperOneSided, None);
+        assert!(rel(output.pvalue.unwrap(), 0.0453843597747002624) < 1e-12);
+        let output = test(TestTSide::TwoSided, Some(20));
+        assert!(rel(output.statistics, 0.15) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.978140396455432486) < 1e-12);
+        let output = two_sample_cramer_von_mises_test(&treated, &controls).unwrap();
+        assert!(rel(output.statistics, 0.738319658482604547) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.0102777415529784923) < 1e-9);
+        let output = k_sample_anderson_darling_test([&treated, &controls]).unwrap();
+        assert!(rel(output.statistics, 2.36797988985460321) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.0346247058495206170) < 1e-11);
+
+        assert!(kolmogorov_smirnov_test([f64::NAN], &normal, side, PValueMethod::Auto).is_none());
+        assert!(k_sample_anderson_darling_test::<f64, _>([&x[..]]).is_none());
+        assert!(k_sample_anderson_darling_test([[1., 1.], [1., 1.]]).is_none());
+    }
+}
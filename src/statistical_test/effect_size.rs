@@ -0,0 +1,124 @@
+use std::borrow::Borrow;
+
+use num_traits::{Float, FloatConst};
+
+use crate::distribution::{ln_beta, variance};
+
+/// Standardized spreads of the means of two samples, the second being the
+/// control group.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectSize<T> {
+    /// Cohen's d = (m<sub>1</sub> - m<sub>2</sub>) / s<sub>p</sub>, with
+    /// s<sub>p</sub><sup>2</sup> the pooled variance of the two samples.
+    pub cohens_d: T,
+    /// Hedges' g = J(ν) d, with the exact small-sample correction J(ν) =
+    /// Γ(ν/2) / ((ν/2)<sup>1/2</sup> Γ((ν - 1)/2)) for ν = n<sub>1</sub> +
+    /// n<sub>2</sub> - 2, which removes the bias of d under normality.
+    pub hedges_g: T,
+    /// Glass's ∆ = (m<sub>1</sub> - m<sub>2</sub>) / s<sub>2</sub>,
+    /// standardized by the standard deviation of the control group only.
+    pub glass_delta: T,
+}
+
+/// Computes Cohen's d, Hedges' g and Glass's ∆ of two samples from their
+/// means, sizes and unbiased variances, see [Hedges and Olkin
+/// (1985)][hedges]. The second sample is the control group of Glass's ∆.
+/// Returns `None` when a sample has less than two observations or a variance
+/// is not positive.
+///
+/// [hedges]: https://doi.org/10.1016/C2009-0-03396-0
+pub fn effect_size<T: Float + FloatConst>(
+    sample_means: (T, T),
+    sample_sizes: (T, T),
+    sample_vars: (T, T),
+) -> Option<EffectSize<T>> {
+    let (m1, m2) = sample_means;
+    let (n1, n2) = sample_sizes;
+    let (v1, v2) = sample_vars;
+    let one = T::one();
+    let two = one + one;
+    if n1.is_nan() || n2.is_nan() || n1 < two || n2 < two || v1.is_nan() || v2.is_nan() {
+        return None;
+    }
+    if v1 <= T::zero() || v2 <= T::zero() {
+        return None;
+    }
+    let df = n1 + n2 - two;
+    let pooled = ((n1 - one) * v1 + (n2 - one) * v2) / df;
+    let cohens_d = (m1 - m2) / pooled.sqrt();
+    // Γ(ν/2) / Γ((ν - 1)/2) = Γ(1/2) / B((ν - 1)/2, 1/2), whose logarithm
+    // avoids the cancellation of two large ln Γ.
+    let (half, shifted) = (one / two, df / two);
+    let ln_beta = ln_beta(shifted - half, half)?;
+    let correction = (half * T::PI().ln() - ln_beta).exp() / shifted.sqrt();
+    Some(EffectSize {
+        cohens_d,
+        hedges_g: correction * cohens_d,
+        glass_delta: (m1 - m2) / v2.sqrt(),
+    })
+}
+
+/// [`effect_size`] computing the means, the sizes and the unbiased variances
+/// of `sample1` and of the control `sample2`, which can be slices, ndarray
+/// views or iterators of values.
+pub fn effect_size_from_samples<T, I1, I2>(sample1: I1, sample2: I2) -> Option<EffectSize<T>>
+where
+    T: Float + FloatConst,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let (v1, m1, n1) = variance(sample1)?;
+    let (v2, m2, n2) = variance(sample2)?;
+    effect_size((m1, m2), (n1, n2), (v1, v2))
+}
+
+/// Cohen's h = 2 arcsin p<sub>1</sub><sup>1/2</sup> - 2 arcsin
+/// p<sub>2</sub><sup>1/2</sup>, the spread of two proportions on the scale
+/// where their sampling variance is about 1/n. Returns `None` unless both
+/// proportions are in \[0, 1\].
+pub fn cohens_h<T: Float>(p1: T, p2: T) -> Option<T> {
+    let valid = |p: T| p >= T::zero() && p <= T::one();
+    if !valid(p1) || !valid(p2) {
+        return None;
+    }
+    let two = T::one() + T::one();
+    Some(two * (p1.sqrt().asin() - p2.sqrt().asin()))
+}
+
+/// The proportion p<sub>1</sub> at Cohen's h from `p2`, see [`cohens_h`],
+/// for instance to express a minimum detectable effect as a conversion rate.
+/// Returns `None` unless `p2` is in \[0, 1\] and p<sub>1</sub> exists.
+pub fn proportion_from_cohens_h<T: Float + FloatConst>(p2: T, h: T) -> Option<T> {
+    if !(p2 >= T::zero() && p2 <= T::one()) {
+        return None;
+    }
+    let angle = p2.sqrt().asin() + h / (T::one() + T::one());
+    if !(angle >= T::zero() && angle <= T::FRAC_PI_2()) {
+        return None;
+    }
+    let p1 = angle.sin();
+    Some(p1 * p1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{earnings, rel};
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn effect_size_values() {
+        // Lalonde earnings in 1978 of the treated and the controls.
+        let effect = effect_size_from_samples(earnings(1), earnings(0)).unwrap();
+        assert!(rel(effect.cohens_d, 0.14198979236457758) < 1e-13);
+        assert!(rel(effect.hedges_g, 0.14184182638849303) < 1e-13);
+        assert!(rel(effect.glass_delta, 0.15499999374896891) < 1e-13);
+        // J(2) = Γ(1) / Γ(1/2) = π^(-1/2).
+        let small = effect_size((1., 0.), (2., 2.), (1., 1.)).unwrap();
+        assert!(rel(small.hedges_g, 1. / f64::PI().sqrt()) < 1e-14);
+        assert!(effect_size((1., 0.), (1., 2.), (1., 1.)).is_none());
+        assert!(effect_size((1., 0.), (2., 2.), (1., 0.)).is_none());
+    }
+}
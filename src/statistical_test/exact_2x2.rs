@@ -0,0 +1,469 @@
+use num_traits::{Float, FloatConst};
+
+use super::{TestOutput, TestTSide, TIE_TOLERANCE};
+use crate::distribution::{find_root, ln_binomial};
+
+/// Number of steps of the grid over the nuisance proportion of the
+/// unconditional tests, before a local refinement of the best point.
+const NUISANCE_GRID: usize = 200;
+
+/// Fisher's exact test of the 2×2 `table` \[\[a, b\], \[c, d\]\], whose rows
+/// are two groups and whose columns are successes and failures.
+///
+/// Conditionally on the margins, the count a follows the non-central
+/// hypergeometric distribution with odds ratio ψ = p<sub>1</sub>(1 -
+/// p<sub>2</sub>) / (p<sub>2</sub>(1 - p<sub>1</sub>)), which is central under
+/// H<sub>0</sub> : ψ = 1. The one-sided tests sum the probabilities of the
+/// tail of a, and the two-sided test, following [Agresti (1992)][agresti],
+/// sums the probabilities of the tables at most as likely as the observed
+/// one. The statistic is a, the estimate the conditional maximum likelihood
+/// estimate of ψ, and the interval the exact interval inverting the two
+/// one-sided tests at `level`, see [`TestOutput::interval`].
+///
+/// When a margin is zero, the table is the only one with its margins: as in
+/// R's `fisher.test`, the p-value is then 1, the estimate 0 and the interval
+/// (0, ∞). Returns `None` when `level` is not in (0, 1).
+///
+/// [agresti]: https://doi.org/10.1214/ss/1177011454
+pub fn fisher_exact_test<T: Float + FloatConst>(
+    table: [[u64; 2]; 2],
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>> {
+    let (zero, one) = (T::zero(), T::one());
+    if level.is_nan() || level <= zero || level >= one {
+        return None;
+    }
+    let [[a, b], [c, d]] = table;
+    let law = Conditional::new(a + b, c + d, a + c)?;
+    let last = law.ln_pmf.len() - 1;
+    if last == 0 {
+        return Some(TestOutput {
+            statistics: T::from(a).unwrap(),
+            pvalue: Some(one),
+            estimate: Some(zero),
+            std_error: None,
+            df: None,
+            interval: Some((zero, T::infinity())),
+        });
+    }
+    let x = (a - law.lower) as usize;
+    let pvalue = fisher_pvalues(&law.tilted(zero), test_type)[x];
+    let alpha = match test_type {
+        TestTSide::TwoSided => (one - level) / (one + one),
+        _ => one - level,
+    };
+    let count = T::from(a).unwrap();
+    let estimate = if x == 0 {
+        zero
+    } else if x == last {
+        T::infinity()
+    } else {
+        law.solve(|pmf| law.mean(pmf) - count)?.exp()
+    };
+    let lower = if x == 0 || test_type == TestTSide::LowerOneSided {
+        zero
+    } else {
+        law.solve(|pmf| sum(&pmf[x..]) - alpha)?.exp()
+    };
+    let upper = if x == last || test_type == TestTSide::UpperOneSided {
+        T::infinity()
+    } else {
+        law.solve(|pmf| alpha - sum(&pmf[..=x]))?.exp()
+    };
+    Some(TestOutput {
+        statistics: count,
+        pvalue: Some(pvalue),
+        estimate: Some(estimate),
+        std_error: None,
+        df: None,
+        interval: Some((lower, upper)),
+    })
+}
+
+/// Barnard's unconditional exact test of the 2×2 `table` \[\[a, b\], \[c,
+/// d\]\], whose rows are two groups of fixed sizes n<sub>1</sub> = a + b and
+/// n<sub>2</sub> = c + d, and whose columns are successes and failures.
+///
+/// The statistic is the pooled Wald statistic (p̂<sub>1</sub> -
+/// p̂<sub>2</sub>) / (p̂(1 - p̂)(1/n<sub>1</sub> +
+/// 1/n<sub>2</sub>))<sup>1/2</sup>, zero when p̂ is 0 or 1, and the p-value
+/// is, following [Barnard (1947)][barnard], the largest probability over the
+/// common success probability π of H<sub>0</sub> : p<sub>1</sub> =
+/// p<sub>2</sub> = π of the tables at least as extreme as the observed one.
+/// Unlike [`fisher_exact_test`], only the group sizes are fixed, which makes
+/// the test more powerful. The estimate is p̂<sub>1</sub> - p̂<sub>2</sub>.
+///
+/// The maximization evaluates a grid of π before refining its best point,
+/// which costs O(n<sub>1</sub> n<sub>2</sub>). Returns `None` when a group
+/// is empty.
+///
+/// [barnard]: https://doi.org/10.1093/biomet/34.1-2.123
+pub fn barnard_exact_test<T: Float + FloatConst>(
+    table: [[u64; 2]; 2],
+    test_type: TestTSide,
+) -> Option<TestOutput<T, T>> {
+    let [[a, b], [c, d]] = table;
+    let (n1, n2) = (a + b, c + d);
+    if n1 == 0 || n2 == 0 {
+        return None;
+    }
+    let (size1, size2) = (T::from(n1).unwrap(), T::from(n2).unwrap());
+    let wald = |x1: u64, x2: u64| {
+        let (x1, x2) = (T::from(x1).unwrap(), T::from(x2).unwrap());
+        let pooled = (x1 + x2) / (size1 + size2);
+        let var = pooled * (T::one() - pooled) * (size1.recip() + size2.recip());
+        if var > T::zero() {
+            (x1 / size1 - x2 / size2) / var.sqrt()
+        } else {
+            T::zero()
+        }
+    };
+    let observed = wald(a, c);
+    let tol = observed.abs() * T::from(TIE_TOLERANCE).unwrap();
+    let pvalue = unconditional_pvalue(n1, n2, |x1, x2| {
+        let stat = wald(x1, x2);
+        match test_type {
+            TestTSide::UpperOneSided => stat >= observed - tol,
+            TestTSide::LowerOneSided => stat <= observed + tol,
+            TestTSide::TwoSided => stat.abs() >= observed.abs() - tol,
+        }
+    });
+    Some(TestOutput {
+        statistics: observed,
+        pvalue: Some(pvalue),
+        estimate: Some(T::from(a).unwrap() / size1 - T::from(c).unwrap() / size2),
+        std_error: None,
+        df: None,
+        interval: None,
+    })
+}
+
+/// Boschloo's unconditional exact test of the 2×2 `table` \[\[a, b\], \[c,
+/// d\]\], whose rows are two groups of fixed sizes and whose columns are
+/// successes and failures.
+///
+/// As [`barnard_exact_test`], the p-value is the largest probability over
+/// the common success probability π of the tables at least as extreme as
+/// the observed one, but the statistic is the p-value of
+/// [`fisher_exact_test`] for the same alternative, small values being
+/// extreme. Following [Boschloo (1970)][boschloo], the test is uniformly
+/// more powerful than Fisher's test. The estimate is p̂<sub>1</sub> -
+/// p̂<sub>2</sub>. Returns `None` when a group is empty.
+///
+/// [boschloo]: https://doi.org/10.1111/j.1467-9574.1970.tb00104.x
+pub fn boschloo_exact_test<T: Float + FloatConst>(
+    table: [[u64; 2]; 2],
+    test_type: TestTSide,
+) -> Option<TestOutput<T, T>> {
+    let [[a, b], [c, d]] = table;
+    let (n1, n2) = (a + b, c + d);
+    if n1 == 0 || n2 == 0 {
+        return None;
+    }
+    // Fisher's p-values of all the tables, grouped by number of successes.
+    let width = n2 as usize + 1;
+    let mut fisher = vec![T::one(); (n1 as usize + 1) * width];
+    for s in 0..=n1 + n2 {
+        let law = Conditional::new(n1, n2, s)?;
+        let pvalues = fisher_pvalues(&law.tilted(T::zero()), test_type);
+        for (x1, p) in (law.lower..).zip(pvalues) {
+            fisher[x1 as usize * width + (s - x1) as usize] = p;
+        }
+    }
+    let observed = fisher[a as usize * width + c as usize];
+    let cutoff = observed * (T::one() + T::from(TIE_TOLERANCE).unwrap());
+    let pvalue = unconditional_pvalue(n1, n2, |x1, x2| {
+        fisher[x1 as usize * width + x2 as usize] <= cutoff
+    });
+    let (size1, size2) = (T::from(n1).unwrap(), T::from(n2).unwrap());
+    Some(TestOutput {
+        statistics: observed,
+        pvalue: Some(pvalue),
+        estimate: Some(T::from(a).unwrap() / size1 - T::from(c).unwrap() / size2),
+        std_error: None,
+        df: None,
+        interval: None,
+    })
+}
+
+/// Distribution of the number of successes of the first group among 2×2
+/// tables with fixed margins, tilted by an odds ratio.
+struct Conditional<T> {
+    /// Smallest count of the support.
+    lower: u64,
+    /// ln P(X = x) under independence, for x from `lower`.
+    ln_pmf: Vec<T>,
+}
+
+impl<T: Float + FloatConst> Conditional<T> {
+    /// Hypergeometric distribution of the successes of the first group given
+    /// the group sizes `n1` and `n2` and the `successes` of both.
+    fn new(n1: u64, n2: u64, successes: u64) -> Option<Self> {
+        let lower = successes.saturating_sub(n2);
+        let upper = successes.min(n1);
+        let float = |n: u64| T::from(n).unwrap();
+        let ln_total = ln_binomial(float(n1 + n2), float(successes))?;
+        let ln_pmf = (lower..=upper)
+            .map(|x| {
+                let ln1 = ln_binomial(float(n1), float(x))?;
+                let ln2 = ln_binomial(float(n2), float(successes - x))?;
+                Some(ln1 + ln2 - ln_total)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { lower, ln_pmf })
+    }
+
+    /// Probabilities of the support at the log odds ratio `theta`.
+    fn tilted(&self, theta: T) -> Vec<T> {
+        let offset = T::from(self.lower).unwrap();
+        let mut pmf = self
+            .ln_pmf
+            .iter()
+            .enumerate()
+            .map(|(i, ln_p)| *ln_p + theta * (offset + T::from(i).unwrap()))
+            .collect::<Vec<_>>();
+        let max = pmf.iter().fold(T::neg_infinity(), |m, v| m.max(*v));
+        pmf.iter_mut().for_each(|v| *v = (*v - max).exp());
+        let total = sum(&pmf);
+        pmf.iter_mut().for_each(|v| *v = *v / total);
+        pmf
+    }
+
+    fn mean(&self, pmf: &[T]) -> T {
+        let offset = T::from(self.lower).unwrap();
+        pmf.iter().enumerate().fold(T::zero(), |acc, (i, p)| {
+            acc + (offset + T::from(i).unwrap()) * *p
+        })
+    }
+
+    /// Log odds ratio where `f` of the tilted probabilities vanishes, `f`
+    /// increasing with the log odds ratio.
+    fn solve<F: Fn(&[T]) -> T>(&self, f: F) -> Option<T> {
+        let g = |theta: T| f(&self.tilted(theta));
+        let (mut lo, mut hi) = (-T::one(), T::one());
+        for _ in 0..64 {
+            if g(lo) <= T::zero() {
+                break;
+            }
+            lo = lo + lo;
+        }
+        for _ in 0..64 {
+            if g(hi) >= T::zero() {
+                break;
+            }
+            hi = hi + hi;
+        }
+        find_root(g, lo, hi, T::epsilon())
+    }
+}
+
+/// Fisher's p-values of all the tables of the support of `pmf`, the
+/// probabilities of the central conditional distribution.
+fn fisher_pvalues<T: Float>(pmf: &[T], test_type: TestTSide) -> Vec<T> {
+    let one = T::one();
+    let mut pvalues = match test_type {
+        TestTSide::UpperOneSided => {
+            let mut tail = T::zero();
+            let mut pvalues = pmf
+                .iter()
+                .rev()
+                .map(|p| {
+                    tail = tail + *p;
+                    tail
+                })
+                .collect::<Vec<_>>();
+            pvalues.reverse();
+            pvalues
+        }
+        TestTSide::LowerOneSided => {
+            let mut tail = T::zero();
+            pmf.iter()
+                .map(|p| {
+                    tail = tail + *p;
+                    tail
+                })
+                .collect()
+        }
+        TestTSide::TwoSided => {
+            // Sums of the probabilities up to each one, from the smallest.
+            let mut sorted = pmf.to_vec();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let mut cumulated = Vec::with_capacity(sorted.len());
+            let mut total = T::zero();
+            for p in sorted.iter() {
+                total = total + *p;
+                cumulated.push(total);
+            }
+            let factor = one + T::from(TIE_TOLERANCE).unwrap();
+            pmf.iter()
+                .map(|p| {
+                    let count = sorted.partition_point(|q| *q <= *p * factor);
+                    cumulated[count - 1]
+                })
+                .collect()
+        }
+    };
+    pvalues.iter_mut().for_each(|p| *p = p.min(one));
+    pvalues
+}
+
+/// Largest probability over the common success probability π of two groups
+/// of sizes `n1` and `n2` of the tables of successes (x<sub>1</sub>,
+/// x<sub>2</sub>) in the rejection region.
+fn unconditional_pvalue<T, F>(n1: u64, n2: u64, rejected: F) -> T
+where
+    T: Float + FloatConst,
+    F: Fn(u64, u64) -> bool,
+{
+    let float = |n: u64| T::from(n).unwrap();
+    let ln_choose = |n: u64, k: u64| ln_binomial(float(n), float(k)).unwrap();
+    // ln of the sum of C(n1, x1) C(n2, x2) over the rejected tables with s
+    // successes, whose probability is then π^s (1 - π)^(n1 + n2 - s).
+    let total = n1 + n2;
+    let mut ln_weights = vec![T::neg_infinity(); total as usize + 1];
+    for x1 in 0..=n1 {
+        for x2 in 0..=n2 {
+            if rejected(x1, x2) {
+                let w = &mut ln_weights[(x1 + x2) as usize];
+                *w = ln_add_exp(*w, ln_choose(n1, x1) + ln_choose(n2, x2));
+            }
+        }
+    }
+    let probability = |pi: T| {
+        let (ln_pi, ln_rest) = (pi.ln(), (-pi).ln_1p());
+        ln_weights
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| w.is_finite())
+            .fold(T::zero(), |acc, (s, w)| {
+                let s = float(s as u64);
+                acc + (*w + s * ln_pi + (float(total) - s) * ln_rest).exp()
+            })
+    };
+    // The probabilities at π = 0 and π = 1 are those of the tables without
+    // successes and without failures.
+    let mut best = ln_weights[0].exp().max(ln_weights[total as usize].exp());
+    let steps = float(NUISANCE_GRID as u64);
+    let mut argmax = None;
+    for i in 1..NUISANCE_GRID {
+        let p = probability(float(i as u64) / steps);
+        if p > best {
+            best = p;
+            argmax = Some(i);
+        }
+    }
+    if let Some(i) = argmax {
+        // Golden-section search between the neighbours of the best point.
+        let ratio = (T::from(5.).unwrap().sqrt() - T::one()) / (T::one() + T::one());
+        let (mut lo, mut hi) = (float(i as u64 - 1) / steps, float(i as u64 + 1) / steps);
+        let mut x1 = hi - ratio * (hi - lo);
+        let mut x2 = lo + ratio * (hi - lo);
+        let (mut f1, mut f2) = (probability(x1), probability(x2));
+        while hi - lo > T::epsilon().sqrt() * T::epsilon().sqrt().sqrt() {
+            if f1 > f2 {
+                hi = x2;
+                x2 = x1;
+                f2 = f1;
+                x1 = hi - ratio * (hi - lo);
+                f1 = probability(x1);
+            } else {
+                lo = x1;
+                x1 = x2;
+                f1 = f2;
+                x2 = lo + ratio * (hi - lo);
+                f2 = probability(x2);
+            }
+        }
+        best = best.max(f1).max(f2);
+    }
+    best.min(T::one())
+}
+
+fn ln_add_exp<T: Float>(a: T, b: T) -> T {
+    let (max, min) = if a > b { (a, b) } else { (b, a) };
+    if min == T::neg_infinity() {
+        max
+    } else {
+        max + (min - max).exp().ln_1p()
+    }
+}
+
+fn sum<T: Float>(values: &[T]) -> T {
+    values.iter().fold(T::zero(), |acc, v| acc + *v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn exact_2x2_values() {
+        // The non-central hypergeometric law is solved by bisection for
+        // Fisher's test, and the tail probabilities are maximized over the
+        // nuisance proportion for Barnard's and Boschloo's tests. R's
+        // fisher.test gives the upper bound 621.93 for the tea, from a loose
+        // root finder.
+        // Fisher's lady tasting tea.
+        let tea = [[3, 1], [1, 3]];
+        let output = fisher_exact_test(tea, TestTSide::TwoSided, 0.95).unwrap();
+        assert_eq!(output.statistics, 3.);
+        assert!(rel(output.pvalue.unwrap(), 0.485714285714285714) < 1e-12);
+        assert!(rel(output.estimate.unwrap(), 6.40831965819967432) < 1e-12);
+        let (lower, upper) = output.interval.unwrap();
+        assert!(rel(lower, 0.211735595446578567) < 1e-12);
+        assert!(rel(upper, 626.243530588814042) < 1e-12);
+        let output = fisher_exact_test(tea, TestTSide::UpperOneSided, 0.95).unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.242857142857142857) < 1e-12);
+        let (lower, upper) = output.interval.unwrap();
+        assert!(rel(lower, 0.313573767504985158) < 1e-12);
+        assert_eq!(upper, f64::INFINITY);
+        let table = [[7, 3], [2, 9]];
+        let output = fisher_exact_test(table, TestTSide::TwoSided, 0.95).unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.0299731228523798183) < 1e-12);
+        assert!(rel(output.estimate.unwrap(), 9.14053912052956569) < 1e-12);
+        let (lower, upper) = output.interval.unwrap();
+        assert!(rel(lower, 1.01218700425556370) < 1e-12);
+        assert!(rel(upper, 140.569248141686144) < 1e-12);
+        let output = fisher_exact_test(table, TestTSide::LowerOneSided, 0.95).unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.998281903854659273) < 1e-12);
+        assert_eq!(output.interval.unwrap().0, 0.);
+        assert!(rel(output.interval.unwrap().1, 93.0570785084546128) < 1e-12);
+        let output = fisher_exact_test([[0, 6], [5, 4]], TestTSide::TwoSided, 0.95).unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.0439560439560439560) < 1e-12);
+        assert_eq!(output.estimate.unwrap(), 0.);
+        assert!(rel(output.interval.unwrap().1, 1.27873752448799103) < 1e-12);
+        // A single table with these margins, as R's
+        // fisher.test(matrix(c(0, 3, 0, 4), 2)).
+        for side in [TestTSide::TwoSided, TestTSide::LowerOneSided] {
+            let output = fisher_exact_test([[0, 0], [3, 4]], side, 0.95).unwrap();
+            assert_eq!(output.pvalue, Some(1.));
+            assert_eq!(output.estimate, Some(0.));
+            assert_eq!(output.interval, Some((0., f64::INFINITY)));
+        }
+        assert!(fisher_exact_test::<f64>([[0, 0], [3, 4]], TestTSide::TwoSided, 1.).is_none());
+
+        // 7 successes out of 15 against 12 out of 15.
+        let table = [[7, 8], [12, 3]];
+        let output = barnard_exact_test(table, TestTSide::LowerOneSided).unwrap();
+        assert!(rel(output.statistics, -1.894338076060206116) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.034109154661597289) < 1e-12);
+        assert!(rel(output.estimate.unwrap(), -1. / 3.) < 1e-12);
+        let output = barnard_exact_test::<f64>(table, TestTSide::TwoSided).unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.068218309323194578) < 1e-12);
+        let output = barnard_exact_test::<f64>(tea, TestTSide::TwoSided).unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.2890625) < 1e-12);
+        let output = boschloo_exact_test(table, TestTSide::LowerOneSided).unwrap();
+        assert!(rel(output.statistics, 0.064067966016991504) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.034109154661597289) < 1e-12);
+        let output = boschloo_exact_test(table, TestTSide::TwoSided).unwrap();
+        assert!(rel(output.statistics, 0.12813593203398301) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.068218309323194578) < 1e-12);
+        let output = boschloo_exact_test::<f64>(tea, TestTSide::UpperOneSided).unwrap();
+        assert!(rel(output.statistics, 0.242857142857142857) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.14453125) < 1e-12);
+        assert!(barnard_exact_test::<f64>([[0, 0], [5, 4]], TestTSide::TwoSided).is_none());
+    }
+}
@@ -1,8 +1,10 @@
-use num_traits::{Float, FloatConst, NumCast};
+use std::borrow::Borrow;
 
-use crate::{cdf_n01, cdf_t, mean, variance};
+use num_traits::{Float, FloatConst};
 
-use super::{TestOutput, TestTSide};
+use crate::distribution::{mean, variance};
+
+use super::{pvalue_t, pvalue_z, wald_interval, TestOutput, TestTSide};
 
 /// One sample Z-test (also called Gauss-test) with known population variance.
 ///
@@ -19,28 +21,37 @@ use super::{TestOutput, TestTSide};
 ///
 /// (c) H<sub>0</sub> : μ = μ<sub>0</sub> vs H<sub>1</sub> : μ != μ<sub>0</sub>
 /// (two-sided test).
-pub fn one_sample_ztest<T: Float>(
+///
+/// The output holds the estimate `sample_mean` of μ, its standard error σ /
+/// n<sup>1/2</sup> and its normal confidence interval at `level`. Returns
+/// `None` unless `sigma` is positive and `level` is in (0, 1).
+pub fn one_sample_ztest<T: Float + FloatConst>(
     mu0: T,
     sample_mean: T,
     sample_size: T,
     sigma: T,
     test_type: TestTSide,
+    level: T,
 ) -> Option<TestOutput<T, T>> {
-    let stat = if sigma > T::zero() {
-        (sample_mean - mu0) * sample_size.sqrt() / sigma
+    let std_error = if sigma > T::zero() {
+        sigma / sample_size.sqrt()
     } else {
         return None;
     };
-    let one = T::one();
-    let two = one + one;
-    let pvalue = match test_type {
-        TestTSide::UpperOneSided => cdf_n01(stat).map(|cdf_stat| one - cdf_stat),
-        TestTSide::LowerOneSided => cdf_n01(stat),
-        TestTSide::TwoSided => cdf_n01(stat.abs()).map(|cdf_abs_stat| two * (one - cdf_abs_stat)),
-    };
+    let stat = (sample_mean - mu0) / std_error;
     Some(TestOutput {
         statistics: stat,
-        pvalue,
+        pvalue: pvalue_z(stat, test_type),
+        estimate: Some(sample_mean),
+        std_error: Some(std_error),
+        df: None,
+        interval: Some(wald_interval(
+            sample_mean,
+            std_error,
+            None,
+            test_type,
+            level,
+        )?),
     })
 }
 
@@ -49,7 +60,8 @@ pub fn one_sample_ztest<T: Float>(
 ///
 /// Assuming the samples X<sub>1</sub>, . . . , X<sub>n</sub> ∼ N(μ,
 /// σ<sup>2</sup>) are i.i.d., where N(μ, σ<sup>2</sup>) is the gaussian law
-/// with mean μ and **known variance** σ<sup>2</sup>. This function can test the
+/// with mean μ and **unknown variance** σ<sup>2</sup>, estimated by the
+/// unbiased sample variance `sample_var`. This function can test the
 /// following:
 ///
 /// (a) H<sub>0</sub> : μ ≤ μ<sub>0</sub> vs H<sub>1</sub> : μ > μ<sub>0</sub>
@@ -60,29 +72,135 @@ pub fn one_sample_ztest<T: Float>(
 ///
 /// (c) H<sub>0</sub> : μ = μ<sub>0</sub> vs H<sub>1</sub> : μ != μ<sub>0</sub>
 /// (two-sided test).
+///
+/// The output holds the estimate `sample_mean` of μ, its standard error
+/// (`sample_var` / n)<sup>1/2</sup>, the n - 1 degrees of freedom and the
+/// Student's confidence interval at `level`. Returns `None` unless
+/// `sample_var` is positive and `level` is in (0, 1).
 pub fn one_sample_ttest<T: Float + FloatConst>(
     mu0: T,
     sample_mean: T,
     sample_size: T,
     sample_var: T,
     test_type: TestTSide,
+    level: T,
 ) -> Option<TestOutput<T, T>> {
-    let stat = if sample_var > T::zero() {
-        (sample_mean - mu0) * sample_size.sqrt() / sample_var.sqrt()
+    let std_error = if sample_var > T::zero() {
+        (sample_var / sample_size).sqrt()
     } else {
         return None;
     };
-    let one = T::one();
-    let two = one + one;
-    let pvalue = match test_type {
-        TestTSide::UpperOneSided => cdf_t(stat, sample_size - one).map(|cdf_stat| one - cdf_stat),
-        TestTSide::LowerOneSided => cdf_t(stat, sample_size - one),
-        TestTSide::TwoSided => {
-            cdf_t(stat.abs(), sample_size - one).map(|cdf_abs_stat| two * (one - cdf_abs_stat))
-        }
-    };
+    let stat = (sample_mean - mu0) / std_error;
+    let df = sample_size - T::one();
     Some(TestOutput {
         statistics: stat,
-        pvalue,
+        pvalue: pvalue_t(stat, df, test_type),
+        estimate: Some(sample_mean),
+        std_error: Some(std_error),
+        df: Some(df),
+        interval: Some(wald_interval(
+            sample_mean,
+            std_error,
+            Some(df),
+            test_type,
+            level,
+        )?),
     })
 }
+
+/// [`one_sample_ztest`] computing the mean and the size of `sample`, which
+/// can be a slice, an ndarray view or an iterator of values. Returns `None`
+/// when the sample is empty.
+pub fn one_sample_ztest_from_sample<T, I>(
+    mu0: T,
+    sample: I,
+    sigma: T,
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+{
+    let (sample_mean, sample_size) = mean(sample)?;
+    one_sample_ztest(mu0, sample_mean, sample_size, sigma, test_type, level)
+}
+
+/// [`one_sample_ttest`] computing the mean, the size and the unbiased
+/// variance of `sample`, which can be a slice, an ndarray view or an
+/// iterator of values. Returns `None` when the sample has less than two
+/// values or all its values equal.
+pub fn one_sample_ttest_from_sample<T, I>(
+    mu0: T,
+    sample: I,
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+{
+    let (sample_var, sample_mean, sample_size) = variance(sample)?;
+    one_sample_ttest(mu0, sample_mean, sample_size, sample_var, test_type, level)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array1;
+
+    use super::*;
+    use crate::data::lalonde::RE78;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn one_sample_test_values() {
+        // Lalonde earnings in 1978.
+        let earnings = RE78.iter().map(|x| *x as f64).collect::<Vec<_>>();
+        let side = TestTSide::TwoSided;
+        let ttest = one_sample_ttest_from_sample(5000., &earnings, side, 0.95).unwrap();
+        assert!(rel(ttest.statistics, 1.9536545488683436) < 1e-12);
+        assert!(rel(ttest.pvalue.unwrap(), 0.051128424572544205) < 1e-12);
+        assert!(rel(ttest.std_error.unwrap(), 232.71045733469181) < 1e-12);
+        assert_eq!(ttest.df, Some(721.));
+        let (lower, upper) = ttest.interval.unwrap();
+        assert!(rel(lower, 4997.7647873411760) < 1e-12);
+        assert!(
+            rel(
+                upper - ttest.estimate.unwrap(),
+                ttest.estimate.unwrap() - lower
+            ) < 1e-12
+        );
+        let bound = one_sample_ttest_from_sample(5000., &earnings, TestTSide::LowerOneSided, 0.95)
+            .unwrap()
+            .interval
+            .unwrap();
+        assert_eq!(bound.0, f64::NEG_INFINITY);
+        assert!(rel(bound.1, 5837.9029324732961) < 1e-12);
+        let ztest = one_sample_ztest_from_sample(5000., &earnings, 6000., side, 0.95).unwrap();
+        assert!(rel(ztest.statistics, 2.0360152236099661) < 1e-12);
+        assert!(rel(ztest.pvalue.unwrap(), 0.041748826608567843) < 1e-12);
+        assert_eq!(ztest.df, None);
+        let (lower, upper) = ztest.interval.unwrap();
+        assert!(rel(lower, 5016.9820042728339) < 1e-12);
+        assert!(rel(upper, 5892.2896828094726) < 1e-12);
+        // Iterators, ndarray views and single precision.
+        let upper_side = TestTSide::UpperOneSided;
+        let view = Array1::from(earnings.clone());
+        let from_view = one_sample_ttest_from_sample(5000., view.view(), upper_side, 0.9).unwrap();
+        let from_iter =
+            one_sample_ttest_from_sample(5000., RE78.iter().map(|x| *x as f64), upper_side, 0.9)
+                .unwrap();
+        assert_eq!(from_view.pvalue, from_iter.pvalue);
+        assert_eq!(from_view.interval, from_iter.interval);
+        let single = one_sample_ttest_from_sample(5000f32, RE78, side, 0.95).unwrap();
+        assert!(rel(single.pvalue.unwrap() as f64, 0.051128424572544205) < 1e-4);
+        assert!(rel(single.interval.unwrap().0 as f64, 4997.7647873411760) < 1e-5);
+        assert!(one_sample_ttest_from_sample(0., [1.], side, 0.95).is_none());
+        assert!(one_sample_ttest_from_sample(0., [1., 1.], side, 0.95).is_none());
+        assert!(one_sample_ttest_from_sample(0., [1., 2.], side, 1.).is_none());
+        assert!(one_sample_ztest_from_sample(0., &[] as &[f64], 1., side, 0.95).is_none());
+    }
+}
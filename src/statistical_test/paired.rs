@@ -0,0 +1,68 @@
+use std::borrow::Borrow;
+
+use num_traits::{Float, FloatConst};
+
+use super::{differences, one_sample_ttest_from_sample, TestOutput, TestTSide};
+
+/// Paired T-test on the within-unit differences.
+///
+/// Assuming the pairs (X<sub>1</sub>, Y<sub>1</sub>), . . . , (X<sub>n</sub>,
+/// Y<sub>n</sub>) are i.i.d., for instance the same units before and after a
+/// treatment, and the differences D<sub>i</sub> = X<sub>i</sub> -
+/// Y<sub>i</sub> ∼ N(∆, σ<sup>2</sup>) with unknown variance σ<sup>2</sup>,
+/// this function applies [`one_sample_ttest`](super::one_sample_ttest) to the
+/// differences to test ∆ against ∆<sub>0</sub>, see [`TestTSide`]. The
+/// statistic follows under H<sub>0</sub> the central Student's
+/// t-distribution with n - 1 degrees of freedom. The output holds the mean
+/// difference, its standard error and its confidence interval at `level`.
+///
+/// `sample1` and `sample2` can be slices, ndarray views or iterators of
+/// values. Returns `None` when their lengths differ, when they have less
+/// than two pairs, when all the differences are equal or when `level` is not
+/// in (0, 1).
+pub fn paired_ttest<T, I1, I2>(
+    delta0: T,
+    sample1: I1,
+    sample2: I2,
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let diffs = differences(sample1, sample2)?;
+    one_sample_ttest_from_sample(delta0, &diffs, test_type, level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::lalonde::{RE75, RE78},
+        test_util::rel,
+    };
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn paired_ttest_values() {
+        // Lalonde earnings in 1978 against 1975, per person.
+        let re78 = RE78.iter().map(|x| *x as f64);
+        let re75 = RE75.iter().map(|x| *x as f64);
+        let output = paired_ttest(0., re78, re75, TestTSide::TwoSided, 0.95).unwrap();
+        assert!(rel(output.statistics, 8.7419842441469685) < 1e-12);
+        assert!(rel(output.estimate.unwrap(), 2411.7392588533523) < 1e-12);
+        assert!(rel(output.std_error.unwrap(), 275.88007384800391) < 1e-12);
+        let (lower, upper) = output.interval.unwrap();
+        assert!(rel(lower, 1870.1150354855640) < 1e-12);
+        assert!(rel(upper, 2953.3634822211406) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 1.5979668932349117e-17) < 1e-12);
+        let upper = paired_ttest(0f32, RE78, RE75, TestTSide::UpperOneSided, 0.95).unwrap();
+        assert!(rel(upper.pvalue.unwrap() as f64, 7.9898344661745583e-18) < 1e-3);
+        assert!(paired_ttest(0., [1., 2.], [1.], TestTSide::TwoSided, 0.95).is_none());
+        assert!(paired_ttest(0., [1., 2.], [0., 1.], TestTSide::TwoSided, 0.95).is_none());
+    }
+}
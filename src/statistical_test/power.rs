@@ -0,0 +1,388 @@
+use ndarray::Array2;
+use num_traits::{Float, FloatConst};
+
+use super::TestTSide;
+use crate::distribution::{
+    cdf_n01, cdf_nt, find_root, inc_beta_xy, lngamma, quantile_n01, sf_n01, sf_nt, Beta,
+    Continuous, StudentsT,
+};
+
+/// Maximal number of doublings of the upper bound when bracketing a sample
+/// size or an effect size.
+const BRACKET_MAX_ITER: usize = 128;
+
+/// Maximal number of terms on each side of the mode of the Poisson mixture
+/// of the non-central F-distribution.
+const NCF_MAX_ITER: usize = 10000;
+
+/// Test studied by [`PowerAnalysis`], with its standardized effect size and
+/// its sample size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerTest<T> {
+    /// One-sample or paired t-test, with n observations or pairs and the
+    /// effect size d = (μ - μ<sub>0</sub>) / σ.
+    OneSampleT,
+    /// One-sample z-test, with n observations and d = (μ - μ<sub>0</sub>) /
+    /// σ.
+    OneSampleZ,
+    /// Two-sample t-test with equal variances, with n<sub>1</sub> and
+    /// n<sub>2</sub> = `ratio` n<sub>1</sub> observations and Cohen's d =
+    /// (μ<sub>1</sub> - μ<sub>2</sub> - ∆<sub>0</sub>) / σ.
+    TwoSampleT { ratio: T },
+    /// Two-sample z-test, with n<sub>1</sub> and n<sub>2</sub> = `ratio`
+    /// n<sub>1</sub> observations and d = (μ<sub>1</sub> - μ<sub>2</sub> -
+    /// ∆<sub>0</sub>) / σ.
+    TwoSampleZ { ratio: T },
+    /// Two-proportion z-test on the arcsine-transformed proportions, with
+    /// n<sub>1</sub> and n<sub>2</sub> = `ratio` n<sub>1</sub> observations
+    /// and Cohen's h, see [`cohens_h`](super::cohens_h).
+    TwoProportions { ratio: T },
+    /// One-way ANOVA F-test of `groups` groups of n observations each, with
+    /// Cohen's f = σ<sub>μ</sub> / σ, σ<sub>μ</sub> being the standard
+    /// deviation of the group means. The F-test being upper one-sided, the
+    /// [`TestTSide`] is ignored.
+    Anova { groups: usize },
+}
+
+/// Known parameters of a [`PowerAnalysis`], all but one of them being set.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PowerParameters<T> {
+    /// Standardized effect size, in the direction of the alternative: negative
+    /// for the lower one-sided tests.
+    pub effect_size: Option<T>,
+    /// Number of observations n, of the first group n<sub>1</sub> for the
+    /// two-sample tests, or of each group for the ANOVA. It is real: round it
+    /// up to plan an experiment.
+    pub sample_size: Option<T>,
+    /// Significance level.
+    pub alpha: Option<T>,
+    /// Probability of rejecting H<sub>0</sub> at the effect size.
+    pub power: Option<T>,
+}
+
+/// Power analysis of a test: relation between the power, the sample size,
+/// the effect size and the significance level, see [Cohen
+/// (1988)][cohen].
+///
+/// The power of the t-tests comes from the non-central t-distribution
+/// ([`cdf_nt`]), that of the ANOVA from the non-central F-distribution with
+/// non-centrality f<sup>2</sup> k n, and that of the z-tests from the normal
+/// distribution.
+///
+/// [cohen]: https://doi.org/10.4324/9780203771587
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerAnalysis<T> {
+    pub test: PowerTest<T>,
+    pub test_type: TestTSide,
+}
+
+impl<T: Float + FloatConst> PowerAnalysis<T> {
+    /// Returns `None` unless the allocation ratio is positive and finite and
+    /// the ANOVA has at least two groups.
+    pub fn new(test: PowerTest<T>, test_type: TestTSide) -> Option<Self> {
+        let valid = match test {
+            PowerTest::TwoSampleT { ratio }
+            | PowerTest::TwoSampleZ { ratio }
+            | PowerTest::TwoProportions { ratio } => ratio.is_finite() && ratio > T::zero(),
+            PowerTest::Anova { groups } => groups >= 2,
+            PowerTest::OneSampleT | PowerTest::OneSampleZ => true,
+        };
+        valid.then_some(Self { test, test_type })
+    }
+
+    /// Power of the test at `effect_size` with `sample_size` observations
+    /// and the significance level `alpha`. Returns `None` when `alpha` is not
+    /// in (0, 1), the effect size is not finite or the sample size leaves no
+    /// degree of freedom.
+    pub fn power(&self, effect_size: T, sample_size: T, alpha: T) -> Option<T> {
+        let (zero, one) = (T::zero(), T::one());
+        if alpha.is_nan() || alpha <= zero || alpha >= one || !effect_size.is_finite() {
+            return None;
+        }
+        if sample_size.is_nan() || sample_size <= self.min_sample_size() {
+            return None;
+        }
+        let level = match self.test_type {
+            TestTSide::TwoSided => alpha / (one + one),
+            _ => alpha,
+        };
+        let n = sample_size;
+        let power = match self.test {
+            PowerTest::OneSampleT => {
+                t_power(effect_size * n.sqrt(), n - one, level, self.test_type)
+            }
+            PowerTest::TwoSampleT { ratio } => {
+                let ncp = effect_size / (one / n + one / (ratio * n)).sqrt();
+                t_power(ncp, n + ratio * n - one - one, level, self.test_type)
+            }
+            PowerTest::OneSampleZ => z_power(effect_size * n.sqrt(), level, self.test_type),
+            PowerTest::TwoSampleZ { ratio } | PowerTest::TwoProportions { ratio } => {
+                let ncp = effect_size / (one / n + one / (ratio * n)).sqrt();
+                z_power(ncp, level, self.test_type)
+            }
+            PowerTest::Anova { groups } => anova_power(effect_size, groups, n, alpha),
+        }?;
+        Some(power.max(zero).min(one))
+    }
+
+    /// Solves for the unknown parameter of `parameters`, the others being
+    /// set. The effect size solved for is positive, except for the lower
+    /// one-sided tests where it is negative. Returns `None` when not exactly
+    /// one parameter is unknown, a known parameter is invalid, or no value
+    /// of the unknown reaches the power, for instance a power below `alpha`.
+    pub fn solve(&self, parameters: PowerParameters<T>) -> Option<T> {
+        let (zero, one) = (T::zero(), T::one());
+        let PowerParameters {
+            effect_size,
+            sample_size,
+            alpha,
+            power,
+        } = parameters;
+        match (effect_size, sample_size, alpha, power) {
+            (Some(d), Some(n), Some(alpha), None) => self.power(d, n, alpha),
+            (Some(d), Some(n), None, Some(power)) => {
+                let f = |alpha| self.power(d, n, alpha).map_or(T::nan(), |p| p - power);
+                let eps = T::epsilon();
+                find_root(f, eps, one - eps, eps * eps)
+            }
+            (Some(d), None, Some(alpha), Some(power)) => {
+                let lower = self.min_sample_size();
+                let f = |n| self.power(d, n, alpha).map_or(T::nan(), |p| p - power);
+                bracket_root(f, lower, lower + one)
+            }
+            (None, Some(n), Some(alpha), Some(power)) => {
+                let sign = match (self.test, self.test_type) {
+                    (PowerTest::Anova { .. }, _) => one,
+                    (_, TestTSide::LowerOneSided) => -one,
+                    _ => one,
+                };
+                let f = |d| {
+                    self.power(sign * d, n, alpha)
+                        .map_or(T::nan(), |p| p - power)
+                };
+                bracket_root(f, zero, one).map(|d| sign * d)
+            }
+            _ => None,
+        }
+    }
+
+    /// Minimum detectable effects at the significance level `alpha`: the
+    /// effect sizes reaching each power of `powers` (columns) with each
+    /// sample size of `sample_sizes` (rows), see [`PowerAnalysis::solve`].
+    /// Returns `None` when one of them cannot be solved for.
+    pub fn mde_table(&self, alpha: T, powers: &[T], sample_sizes: &[T]) -> Option<Array2<T>> {
+        let mut table = Array2::zeros((sample_sizes.len(), powers.len()));
+        for (i, n) in sample_sizes.iter().enumerate() {
+            for (j, power) in powers.iter().enumerate() {
+                table[[i, j]] = self.solve(PowerParameters {
+                    effect_size: None,
+                    sample_size: Some(*n),
+                    alpha: Some(alpha),
+                    power: Some(*power),
+                })?;
+            }
+        }
+        Some(table)
+    }
+
+    /// Sample size below which the test has no degree of freedom.
+    fn min_sample_size(&self) -> T {
+        let one = T::one();
+        match self.test {
+            PowerTest::OneSampleT => one,
+            PowerTest::TwoSampleT { ratio } => (one + one) / (one + ratio),
+            PowerTest::Anova { .. } => one,
+            _ => T::zero(),
+        }
+    }
+}
+
+/// Power of a t-test with non-centrality `ncp`, `df` degrees of freedom and
+/// the level `level` in each rejected tail.
+fn t_power<T: Float + FloatConst>(ncp: T, df: T, level: T, test_type: TestTSide) -> Option<T> {
+    let critical = -StudentsT::new(df)?.quantile(level)?;
+    Some(match test_type {
+        TestTSide::UpperOneSided => sf_nt(critical, df, ncp)?,
+        TestTSide::LowerOneSided => cdf_nt(-critical, df, ncp)?,
+        TestTSide::TwoSided => sf_nt(critical, df, ncp)? + cdf_nt(-critical, df, ncp)?,
+    })
+}
+
+/// Power of a z-test with the mean `ncp` of the statistic, see [`t_power`].
+fn z_power<T: Float + FloatConst>(ncp: T, level: T, test_type: TestTSide) -> Option<T> {
+    let critical = -quantile_n01(level)?;
+    Some(match test_type {
+        TestTSide::UpperOneSided => sf_n01(critical - ncp)?,
+        TestTSide::LowerOneSided => cdf_n01(-critical - ncp)?,
+        TestTSide::TwoSided => sf_n01(critical - ncp)? + cdf_n01(-critical - ncp)?,
+    })
+}
+
+/// Power of the one-way ANOVA: P(F > F<sub>1-α</sub>) for F following the
+/// non-central F-distribution with k - 1 and k(n - 1) degrees of freedom and
+/// non-centrality λ = f<sup>2</sup> k n, summed as the Poisson(λ/2) mixture
+/// of the Beta tails P(B<sub>j</sub> > x<sub>α</sub>) with B<sub>j</sub> ∼
+/// Beta((k - 1)/2 + j, k(n - 1)/2), outwards from the mode of the weights.
+fn anova_power<T: Float + FloatConst>(f: T, groups: usize, n: T, alpha: T) -> Option<T> {
+    let (zero, one) = (T::zero(), T::one());
+    let half = one / (one + one);
+    let k = T::from(groups).unwrap();
+    let (a, b) = (half * (k - one), half * k * (n - one));
+    // 1 - x_α, whose Beta(b, a) CDF is alpha.
+    let y = Beta::new(b, a)?.quantile(alpha)?;
+    let mean = half * f * f * k * n;
+    let weight = |j: T| {
+        if mean == zero {
+            if j == zero {
+                one
+            } else {
+                zero
+            }
+        } else {
+            (j * mean.ln() - mean - lngamma(j + one)).exp()
+        }
+    };
+    let term = |j: T| Some(weight(j) * inc_beta_xy(y, one - y, b, a + j)?);
+    let mode = mean.floor();
+    let mut sum = term(mode)?;
+    let mut mass = weight(mode);
+    let mut j = mode;
+    for _ in 0..NCF_MAX_ITER {
+        j = j + one;
+        let w = weight(j);
+        sum = sum + term(j)?;
+        mass = mass + w;
+        if w <= T::epsilon() * mass && j > mean {
+            break;
+        }
+    }
+    let mut j = mode;
+    while j > zero {
+        j = j - one;
+        let w = weight(j);
+        sum = sum + term(j)?;
+        if w <= T::epsilon() * sum {
+            break;
+        }
+    }
+    Some(sum)
+}
+
+/// Root of the increasing `f` above `lower`, where it may be undefined,
+/// bracketed by doubling an upper bound from `start`, or by halving its
+/// distance to `lower`. Returns `None` when no bracket is found.
+fn bracket_root<T: Float, F: Fn(T) -> T>(f: F, lower: T, start: T) -> Option<T> {
+    let zero = T::zero();
+    let (mut lo, mut hi) = (start, start);
+    if f(start) >= zero {
+        let half = T::from(0.5).unwrap();
+        for _ in 0..BRACKET_MAX_ITER {
+            lo = lower + (lo - lower) * half;
+            if f(lo) < zero {
+                break;
+            }
+        }
+    } else {
+        for _ in 0..BRACKET_MAX_ITER {
+            hi = hi + hi;
+            if f(hi) >= zero {
+                break;
+            }
+        }
+    }
+    let tol = T::epsilon().powf(T::from(2. / 3.).unwrap()) * hi;
+    find_root(f, lo, hi, tol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statistical_test::{cohens_h, proportion_from_cohens_h};
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn power_values() {
+        let solve = |analysis: PowerAnalysis<f64>, d, n, alpha, power| {
+            analysis
+                .solve(PowerParameters {
+                    effect_size: d,
+                    sample_size: n,
+                    alpha,
+                    power,
+                })
+                .unwrap()
+        };
+        let two_sided = TestTSide::TwoSided;
+        // Two-sample t-test: 64 units per group detect d = 0.5.
+        let t = PowerAnalysis::new(PowerTest::TwoSampleT { ratio: 1. }, two_sided).unwrap();
+        let n = solve(t, Some(0.5), None, Some(0.05), Some(0.8));
+        assert!(rel(n, 63.765610190952237) < 1e-9);
+        assert!(rel(t.power(0.5, n, 0.05).unwrap(), 0.8) < 1e-12);
+        let alpha = solve(t, Some(0.5), Some(n), None, Some(0.8));
+        assert!(rel(alpha, 0.05) < 1e-9);
+        let unbalanced =
+            PowerAnalysis::new(PowerTest::TwoSampleT { ratio: 2. }, two_sided).unwrap();
+        assert!(
+            rel(
+                unbalanced.power(0.4, 50., 0.05).unwrap(),
+                0.63098351825076728
+            ) < 1e-11
+        );
+        // One-sample t-tests, one-sided in both directions.
+        let upper = PowerAnalysis::new(PowerTest::OneSampleT, TestTSide::UpperOneSided).unwrap();
+        let lower = PowerAnalysis::new(PowerTest::OneSampleT, TestTSide::LowerOneSided).unwrap();
+        assert!(rel(upper.power(0.6, 20., 0.05).unwrap(), 0.82663951873763175) < 1e-11);
+        assert!(rel(lower.power(-0.6, 20., 0.05).unwrap(), 0.82663951873763175) < 1e-11);
+        let d = solve(upper, None, Some(25.), Some(0.05), Some(0.8));
+        assert!(rel(d, 0.51193916039190820) < 1e-9);
+        assert!(rel(solve(lower, None, Some(25.), Some(0.05), Some(0.8)), -d) < 1e-12);
+        let one = PowerAnalysis::new(PowerTest::OneSampleT, two_sided).unwrap();
+        let n = solve(one, Some(0.5), None, Some(0.05), Some(0.8));
+        assert!(rel(n, 33.367128953330836) < 1e-9);
+        // z-tests.
+        let z = PowerAnalysis::new(PowerTest::TwoSampleZ { ratio: 1. }, two_sided).unwrap();
+        assert!(rel(z.power(0.3, 100., 0.05).unwrap(), 0.56411602543100381) < 1e-13);
+        let z = PowerAnalysis::new(PowerTest::OneSampleZ, TestTSide::UpperOneSided).unwrap();
+        let alpha = solve(z, Some(0.25), Some(150.), None, Some(0.9));
+        assert!(rel(alpha, 0.037512570608367276) < 1e-9);
+        // Conversion rates from 10% to 12%, with 1.5 controls per treated.
+        let h = cohens_h(0.12, 0.10).unwrap();
+        assert!(rel(h, 0.063982102986058585) < 1e-14);
+        assert!(rel(proportion_from_cohens_h(0.10, h).unwrap(), 0.12) < 1e-14);
+        let proportions =
+            PowerAnalysis::new(PowerTest::TwoProportions { ratio: 1.5 }, two_sided).unwrap();
+        let n = solve(proportions, Some(h), None, Some(0.05), Some(0.8));
+        assert!(rel(n, 3195.4964498976126) < 1e-9);
+        // One-way ANOVA with four groups.
+        let anova = PowerAnalysis::new(PowerTest::Anova { groups: 4 }, two_sided).unwrap();
+        assert!(rel(anova.power(0.25, 30., 0.05).unwrap(), 0.60652278674777920) < 1e-11);
+        let n = solve(anova, Some(0.25), None, Some(0.05), Some(0.8));
+        assert!(rel(n, 44.599274306099869) < 1e-9);
+        assert!(rel(anova.power(0., 30., 0.05).unwrap(), 0.05) < 1e-12);
+        // Minimum detectable effects.
+        let table = t.mde_table(0.05, &[0.8, 0.9], &[50., 200.]).unwrap();
+        let expected = [
+            [0.56588224375557240, 0.65475239914101332],
+            [0.28083645028758997, 0.32493656321988665],
+        ];
+        for (row, expected) in table.rows().into_iter().zip(expected) {
+            for (d, expected) in row.iter().zip(expected) {
+                assert!(rel(*d, expected) < 1e-9);
+            }
+        }
+        // Invalid designs and parameters.
+        assert!(PowerAnalysis::new(PowerTest::TwoSampleT { ratio: 0. }, two_sided).is_none());
+        assert!(PowerAnalysis::new(PowerTest::<f64>::Anova { groups: 1 }, two_sided).is_none());
+        assert!(one.power(0.5, 1., 0.05).is_none());
+        assert!(one.power(0.5, 10., 1.).is_none());
+        let unsolvable = PowerParameters {
+            effect_size: Some(0.5),
+            power: Some(0.01),
+            alpha: Some(0.05),
+            sample_size: None,
+        };
+        assert!(t.solve(unsolvable).is_none());
+        assert!(t.solve(PowerParameters::default()).is_none());
+    }
+}
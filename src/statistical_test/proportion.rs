@@ -0,0 +1,658 @@
+use num_traits::{Float, FloatConst};
+
+use super::{pvalue_z, wald_interval, TestOutput, TestTSide, TIE_TOLERANCE};
+use crate::distribution::find_root;
+use crate::{quantile_n01, Beta, Binomial, Continuous, Discrete};
+
+/// Method of a confidence interval of a single proportion, see [Brown, Cai
+/// and DasGupta (2001)][brown] for their coverages.
+///
+/// [brown]: https://doi.org/10.1214/ss/1009213286
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProportionInterval {
+    /// Inversion of the score test, the one of
+    /// [`one_sample_proportion_ztest`].
+    Wilson,
+    /// Inversion of the exact binomial test, with a coverage at least the
+    /// level.
+    ClopperPearson,
+    /// The Wald interval of the proportion after adding z<sup>2</sup>/2
+    /// successes and failures, clipped to \[0, 1\].
+    AgrestiCoull,
+    /// The equal-tailed interval of the Beta(x + 1/2, n - x + 1/2)
+    /// posterior of the Jeffreys prior, with the lower bound 0 when x = 0 and
+    /// the upper bound 1 when x = n as in Brown, Cai and DasGupta (2001).
+    Jeffreys,
+}
+
+/// Method of a confidence interval of the difference p<sub>1</sub> -
+/// p<sub>2</sub> of two proportions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifferenceInterval {
+    /// The hybrid score interval of [Newcombe (1998)][newcombe] combining
+    /// the Wilson intervals of both proportions.
+    ///
+    /// [newcombe]: https://doi.org/10.1002/(SICI)1097-0258(19980430)17:8%3C873::AID-SIM779%3E3.0.CO;2-I
+    Newcombe,
+    /// Inversion of the score test of [Miettinen and Nurminen
+    /// (1985)][miettinen], whose variance is at the maximum likelihood
+    /// estimate under the tested difference.
+    ///
+    /// [miettinen]: https://doi.org/10.1002/sim.4780040211
+    MiettinenNurminen,
+}
+
+/// One-sample proportion Z-test.
+///
+/// Assuming `successes` follows the binomial distribution with `trials`
+/// trials and success probability p, this function tests p against
+/// p<sub>0</sub>, see [`TestTSide`], with the score statistic
+/// (p̂ - p<sub>0</sub>) / (p<sub>0</sub>(1 - p<sub>0</sub>) /
+/// n)<sup>1/2</sup>, which asymptotically follows the standard normal
+/// distribution under H<sub>0</sub>. The output holds p̂, its standard
+/// error (p̂(1 - p̂) / n)<sup>1/2</sup> and its Wilson interval at `level`,
+/// which inverts the test.
+///
+/// Returns `None` when there is no trial, when `successes` exceeds `trials`,
+/// when `p0` is not in (0, 1) or when `level` is not in (0, 1).
+pub fn one_sample_proportion_ztest<T: Float + FloatConst>(
+    p0: T,
+    successes: u64,
+    trials: u64,
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>> {
+    let (zero, one) = (T::zero(), T::one());
+    if !(valid_counts(successes, trials) && p0 > zero && p0 < one) {
+        return None;
+    }
+    let (x, n) = (T::from(successes).unwrap(), T::from(trials).unwrap());
+    let p = x / n;
+    let stat = (p - p0) / (p0 * (one - p0) / n).sqrt();
+    let interval = one_sided_bounds(test_type, level, |alpha| {
+        Some(wilson(successes, trials, quantile_n01(one - alpha)?))
+    })?;
+    Some(TestOutput {
+        statistics: stat,
+        pvalue: pvalue_z(stat, test_type),
+        estimate: Some(p),
+        std_error: Some((p * (one - p) / n).sqrt()),
+        df: None,
+        interval: Some(interval),
+    })
+}
+
+/// Exact binomial test.
+///
+/// Assuming `successes` follows the binomial distribution with `trials`
+/// trials and success probability p, this function tests p against
+/// p<sub>0</sub>, see [`TestTSide`]. The one-sided p-values are binomial
+/// tails, and the two-sided p-value sums the probabilities of the counts at
+/// most as likely as the observed one. The statistic is the number of
+/// successes, and the output holds p̂ and its Clopper-Pearson interval at
+/// `level`.
+///
+/// Returns `None` when there is no trial, when `successes` exceeds `trials`,
+/// when `p0` is not in \[0, 1\] or when `level` is not in (0, 1).
+pub fn binomial_test<T: Float + FloatConst>(
+    p0: T,
+    successes: u64,
+    trials: u64,
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>> {
+    if !valid_counts(successes, trials) {
+        return None;
+    }
+    let law = Binomial::new(trials, p0)?;
+    let pvalue = match test_type {
+        TestTSide::UpperOneSided if successes == 0 => T::one(),
+        TestTSide::UpperOneSided => law.sf(successes - 1),
+        TestTSide::LowerOneSided => law.cdf(successes),
+        TestTSide::TwoSided => {
+            let cutoff = law.ln_pmf(successes) + T::from(TIE_TOLERANCE).unwrap().ln_1p();
+            (0..=trials)
+                .map(|k| law.ln_pmf(k))
+                .filter(|ln_p| *ln_p <= cutoff)
+                .fold(T::zero(), |acc, ln_p| acc + ln_p.exp())
+        }
+    };
+    let interval = one_sided_bounds(test_type, level, |alpha| {
+        clopper_pearson(successes, trials, alpha)
+    })?;
+    let (x, n) = (T::from(successes).unwrap(), T::from(trials).unwrap());
+    Some(TestOutput {
+        statistics: x,
+        pvalue: Some(pvalue.min(T::one())),
+        estimate: Some(x / n),
+        std_error: None,
+        df: None,
+        interval: Some(interval),
+    })
+}
+
+/// Two-sample proportion Z-test with pooled variance.
+///
+/// Assuming the `successes` of two independent groups follow binomial
+/// distributions with `trials` trials and success probabilities
+/// p<sub>1</sub> and p<sub>2</sub>, this function tests p<sub>1</sub> -
+/// p<sub>2</sub> against 0, see [`TestTSide`], with the statistic
+/// (p̂<sub>1</sub> - p̂<sub>2</sub>) / (p̂(1 - p̂)(1/n<sub>1</sub> +
+/// 1/n<sub>2</sub>))<sup>1/2</sup>, where p̂ is the pooled proportion, which
+/// asymptotically follows the standard normal distribution under
+/// H<sub>0</sub>. The output holds p̂<sub>1</sub> - p̂<sub>2</sub>, its
+/// unpooled standard error and its Wald interval at `level`, clipped to
+/// \[-1, 1\].
+///
+/// Returns `None` when a group has no trial, when successes exceed trials,
+/// when all the trials are successes or all failures, or when `level` is not
+/// in (0, 1).
+pub fn two_sample_pooled_proportion_ztest<T: Float + FloatConst>(
+    successes: (u64, u64),
+    trials: (u64, u64),
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>> {
+    let (estimate, std_error, interval) = difference_wald(successes, trials, test_type, level)?;
+    let one = T::one();
+    let (x1, x2) = (T::from(successes.0).unwrap(), T::from(successes.1).unwrap());
+    let (n1, n2) = (T::from(trials.0).unwrap(), T::from(trials.1).unwrap());
+    let pooled = (x1 + x2) / (n1 + n2);
+    let var = pooled * (one - pooled) * (n1.recip() + n2.recip());
+    if var <= T::zero() {
+        return None;
+    }
+    let stat = estimate / var.sqrt();
+    Some(TestOutput {
+        statistics: stat,
+        pvalue: pvalue_z(stat, test_type),
+        estimate: Some(estimate),
+        std_error: Some(std_error),
+        df: None,
+        interval: Some(interval),
+    })
+}
+
+/// Two-sample proportion Z-test with unpooled variance.
+///
+/// Assuming the `successes` of two independent groups follow binomial
+/// distributions with `trials` trials and success probabilities
+/// p<sub>1</sub> and p<sub>2</sub>, this function tests p<sub>1</sub> -
+/// p<sub>2</sub> against ∆<sub>0</sub>, see [`TestTSide`], for instance a
+/// non-inferiority margin, with the statistic (p̂<sub>1</sub> -
+/// p̂<sub>2</sub> - ∆<sub>0</sub>) / (p̂<sub>1</sub>(1 -
+/// p̂<sub>1</sub>)/n<sub>1</sub> + p̂<sub>2</sub>(1 -
+/// p̂<sub>2</sub>)/n<sub>2</sub>)<sup>1/2</sup>, which asymptotically
+/// follows the standard normal distribution under H<sub>0</sub>. The output
+/// holds p̂<sub>1</sub> - p̂<sub>2</sub>, its standard error and its Wald
+/// interval at `level`, clipped to \[-1, 1\].
+///
+/// Returns `None` when a group has no trial, when successes exceed trials,
+/// when both groups are only successes or only failures, or when `level` is
+/// not in (0, 1).
+pub fn two_sample_unpooled_proportion_ztest<T: Float + FloatConst>(
+    delta0: T,
+    successes: (u64, u64),
+    trials: (u64, u64),
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>> {
+    let (estimate, std_error, interval) = difference_wald(successes, trials, test_type, level)?;
+    if std_error <= T::zero() {
+        return None;
+    }
+    let stat = (estimate - delta0) / std_error;
+    Some(TestOutput {
+        statistics: stat,
+        pvalue: pvalue_z(stat, test_type),
+        estimate: Some(estimate),
+        std_error: Some(std_error),
+        df: None,
+        interval: Some(interval),
+    })
+}
+
+/// Wald test of the risk ratio p<sub>1</sub> / p<sub>2</sub> of two
+/// independent groups against 1, see [`TestTSide`].
+///
+/// The statistic ln(p̂<sub>1</sub> / p̂<sub>2</sub>) / s, with s<sup>2</sup>
+/// = 1/x<sub>1</sub> - 1/n<sub>1</sub> + 1/x<sub>2</sub> -
+/// 1/n<sub>2</sub> the delta-method variance of the logarithm, asymptotically
+/// follows the standard normal distribution under H<sub>0</sub>. The output
+/// holds p̂<sub>1</sub> / p̂<sub>2</sub>, the standard error s of its
+/// logarithm and the interval at `level` of [Katz et al. (1978)][katz], the
+/// exponential of the Wald interval of the logarithm.
+///
+/// Returns `None` when a group has no success, when successes exceed trials
+/// or when `level` is not in (0, 1).
+///
+/// [katz]: https://doi.org/10.2307/2530610
+pub fn risk_ratio_test<T: Float + FloatConst>(
+    successes: (u64, u64),
+    trials: (u64, u64),
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>> {
+    if !valid_counts(successes.0, trials.0) || !valid_counts(successes.1, trials.1) {
+        return None;
+    }
+    if successes.0 == 0 || successes.1 == 0 {
+        return None;
+    }
+    let float = |n: u64| T::from(n).unwrap();
+    let (x1, x2, n1, n2) = (
+        float(successes.0),
+        float(successes.1),
+        float(trials.0),
+        float(trials.1),
+    );
+    let ln_ratio = (x1 / n1 / (x2 / n2)).ln();
+    let std_error = (x1.recip() - n1.recip() + x2.recip() - n2.recip()).sqrt();
+    log_wald_test(ln_ratio, std_error, test_type, level)
+}
+
+/// Wald test of the odds ratio p<sub>1</sub>(1 - p<sub>2</sub>) /
+/// (p<sub>2</sub>(1 - p<sub>1</sub>)) of two independent groups against 1,
+/// see [`TestTSide`].
+///
+/// The statistic ln(ad / (bc)) / s of the table \[\[a, b\], \[c, d\]\] of the
+/// successes and failures of both groups, with s<sup>2</sup> = 1/a + 1/b +
+/// 1/c + 1/d the variance of the logarithm of [Woolf (1955)][woolf],
+/// asymptotically follows the standard normal distribution under
+/// H<sub>0</sub>. The output holds the sample odds ratio, the standard error
+/// s of its logarithm and the exponential of the Wald interval at `level`
+/// of the logarithm. See [`fisher_exact_test`](super::fisher_exact_test) for
+/// small counts.
+///
+/// Returns `None` when a cell of the table is zero, when successes exceed
+/// trials or when `level` is not in (0, 1).
+///
+/// [woolf]: https://doi.org/10.1111/j.1469-1809.1955.tb01348.x
+pub fn odds_ratio_test<T: Float + FloatConst>(
+    successes: (u64, u64),
+    trials: (u64, u64),
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>> {
+    if !valid_counts(successes.0, trials.0) || !valid_counts(successes.1, trials.1) {
+        return None;
+    }
+    let cells = [
+        successes.0,
+        trials.0 - successes.0,
+        successes.1,
+        trials.1 - successes.1,
+    ];
+    if cells.contains(&0) {
+        return None;
+    }
+    let [a, b, c, d] = cells.map(|n| T::from(n).unwrap());
+    let ln_ratio = (a * d / (b * c)).ln();
+    let std_error = (a.recip() + b.recip() + c.recip() + d.recip()).sqrt();
+    log_wald_test(ln_ratio, std_error, test_type, level)
+}
+
+/// Two-sided confidence interval at `level` of the success probability of
+/// `successes` among `trials` by `method`. Returns `None` when there is no
+/// trial, when `successes` exceeds `trials` or when `level` is not in (0,
+/// 1).
+pub fn proportion_interval<T: Float + FloatConst>(
+    successes: u64,
+    trials: u64,
+    method: ProportionInterval,
+    level: T,
+) -> Option<(T, T)> {
+    let one = T::one();
+    if !(valid_counts(successes, trials) && level > T::zero() && level < one) {
+        return None;
+    }
+    let alpha = (one - level) / (one + one);
+    let z = quantile_n01(one - alpha)?;
+    let (x, n) = (T::from(successes).unwrap(), T::from(trials).unwrap());
+    match method {
+        ProportionInterval::Wilson => Some(wilson(successes, trials, z)),
+        ProportionInterval::ClopperPearson => clopper_pearson(successes, trials, alpha),
+        ProportionInterval::AgrestiCoull => {
+            let z2 = z * z;
+            let size = n + z2;
+            let p = (x + z2 / (one + one)) / size;
+            let margin = z * (p * (one - p) / size).sqrt();
+            Some(((p - margin).max(T::zero()), (p + margin).min(one)))
+        }
+        ProportionInterval::Jeffreys => {
+            let half = one / (one + one);
+            let posterior = Beta::new(x + half, n - x + half)?;
+            let lower = if successes == 0 {
+                T::zero()
+            } else {
+                posterior.quantile(alpha)?
+            };
+            let upper = if successes == trials {
+                one
+            } else {
+                posterior.quantile(one - alpha)?
+            };
+            Some((lower, upper))
+        }
+    }
+}
+
+/// Two-sided confidence interval at `level` of the difference
+/// p<sub>1</sub> - p<sub>2</sub> of the success probabilities of two
+/// independent groups with `successes` among `trials`, by `method`. Returns
+/// `None` when a group has no trial, when successes exceed trials or when
+/// `level` is not in (0, 1).
+pub fn proportion_difference_interval<T: Float + FloatConst>(
+    successes: (u64, u64),
+    trials: (u64, u64),
+    method: DifferenceInterval,
+    level: T,
+) -> Option<(T, T)> {
+    let one = T::one();
+    if !valid_counts(successes.0, trials.0) || !valid_counts(successes.1, trials.1) {
+        return None;
+    }
+    if !(level > T::zero() && level < one) {
+        return None;
+    }
+    let z = quantile_n01((one + level) / (one + one))?;
+    let float = |n: u64| T::from(n).unwrap();
+    let (n1, n2) = (float(trials.0), float(trials.1));
+    let (p1, p2) = (float(successes.0) / n1, float(successes.1) / n2);
+    let delta = p1 - p2;
+    match method {
+        DifferenceInterval::Newcombe => {
+            let (l1, u1) = wilson(successes.0, trials.0, z);
+            let (l2, u2) = wilson(successes.1, trials.1, z);
+            let below = ((p1 - l1).powi(2) + (u2 - p2).powi(2)).sqrt();
+            let above = ((u1 - p1).powi(2) + (p2 - l2).powi(2)).sqrt();
+            Some((delta - below, delta + above))
+        }
+        DifferenceInterval::MiettinenNurminen => {
+            // The bounds solve |δ̂ - δ| = z s(δ) on each side of δ̂. When both
+            // proportions are 0 or 1, s(δ̂) vanishes and δ̂ itself is a root,
+            // which the search of the bounds skips.
+            let total = n1 + n2;
+            let std_error = |d: T| {
+                let (q1, q2) = constrained_mle(p1, p2, n2 / n1, d);
+                let var = q1 * (one - q1) / n1 + q2 * (one - q2) / n2;
+                (var.max(T::zero()) * total / (total - one)).sqrt()
+            };
+            let score = |d: T| (delta - d).abs() - z * std_error(d);
+            let tol = T::epsilon();
+            let lower = score_bound(score, -one, delta, tol)?;
+            let upper = score_bound(score, one, delta, tol)?;
+            Some((lower, upper))
+        }
+    }
+}
+
+/// Root of `f` between `outer`, where `f` is positive, and `inner`, next to
+/// which `f` is negative, other than `inner` itself. The bracket is halved
+/// towards `inner` until `f` turns negative.
+fn score_bound<T: Float, F: Fn(T) -> T>(f: F, outer: T, inner: T, tol: T) -> Option<T> {
+    if outer == inner {
+        return Some(inner);
+    }
+    let two = T::one() + T::one();
+    let (mut far, mut near) = (outer, (outer + inner) / two);
+    while f(near) >= T::zero() && near != inner {
+        far = near;
+        near = (near + inner) / two;
+    }
+    find_root(f, far, near, tol)
+}
+
+/// Maximum likelihood estimates of two success probabilities under the
+/// constraint p<sub>1</sub> - p<sub>2</sub> = `delta`, from the sample
+/// proportions and the ratio n<sub>2</sub>/n<sub>1</sub> of the sizes, by the
+/// closed form of the root of the cubic score equation of [Miettinen and
+/// Nurminen (1985)][miettinen].
+///
+/// [miettinen]: https://doi.org/10.1002/sim.4780040211
+fn constrained_mle<T: Float + FloatConst>(p1: T, p2: T, ratio: T, delta: T) -> (T, T) {
+    let one = T::one();
+    let (two, three) = (one + one, one + one + one);
+    let a = one + ratio;
+    let b = -(one + ratio + p1 + ratio * p2 + delta * (ratio + two));
+    let c = delta * delta + delta * (two * p1 + ratio + one) + p1 + ratio * p2;
+    let d = -p1 * delta * (one + delta);
+    let v = b.powi(3) / (T::from(27.).unwrap() * a.powi(3))
+        - b * c / (T::from(6.).unwrap() * a * a)
+        + d / (two * a);
+    let u = (b * b / (T::from(9.).unwrap() * a * a) - c / (three * a))
+        .max(T::zero())
+        .sqrt();
+    let u = if v < T::zero() { -u } else { u };
+    let cosine = if u == T::zero() {
+        T::zero()
+    } else {
+        (v / u.powi(3)).max(-one).min(one)
+    };
+    let w = (T::PI() + cosine.acos()) / three;
+    let q1 = (two * u * w.cos() - b / (three * a))
+        .max(T::zero())
+        .min(one);
+    let q2 = (q1 - delta).max(T::zero()).min(one);
+    (q1, q2)
+}
+
+/// Wilson interval of `successes` among `trials` at the normal quantile `z`.
+fn wilson<T: Float>(successes: u64, trials: u64, z: T) -> (T, T) {
+    let one = T::one();
+    let (x, n) = (T::from(successes).unwrap(), T::from(trials).unwrap());
+    let p = x / n;
+    let z2 = z * z;
+    let scale = one + z2 / n;
+    let center = (p + z2 / (n + n)) / scale;
+    let margin = z * (p * (one - p) / n + z2 / (T::from(4.).unwrap() * n * n)).sqrt() / scale;
+    ((center - margin).max(T::zero()), (center + margin).min(one))
+}
+
+/// Clopper-Pearson interval of `successes` among `trials` with the
+/// probability `alpha` in each tail.
+fn clopper_pearson<T: Float + FloatConst>(successes: u64, trials: u64, alpha: T) -> Option<(T, T)> {
+    let one = T::one();
+    let (x, n) = (T::from(successes).unwrap(), T::from(trials).unwrap());
+    let lower = if successes == 0 {
+        T::zero()
+    } else {
+        Beta::new(x, n - x + one)?.quantile(alpha)?
+    };
+    let upper = if successes == trials {
+        one
+    } else {
+        Beta::new(x + one, n - x)?.quantile(one - alpha)?
+    };
+    Some((lower, upper))
+}
+
+/// Interval of a proportion matching the alternative of `test_type` at
+/// `level`, from the two-sided `interval` with the probability α in each
+/// tail, using \[0, 1\] as the range of the one-sided intervals.
+fn one_sided_bounds<T, F>(test_type: TestTSide, level: T, interval: F) -> Option<(T, T)>
+where
+    T: Float,
+    F: Fn(T) -> Option<(T, T)>,
+{
+    let one = T::one();
+    if !(level > T::zero() && level < one) {
+        return None;
+    }
+    Some(match test_type {
+        TestTSide::TwoSided => interval((one - level) / (one + one))?,
+        TestTSide::UpperOneSided => (interval(one - level)?.0, one),
+        TestTSide::LowerOneSided => (T::zero(), interval(one - level)?.1),
+    })
+}
+
+/// p̂<sub>1</sub> - p̂<sub>2</sub>, its unpooled standard error and its Wald
+/// interval at `level` clipped to \[-1, 1\].
+fn difference_wald<T: Float + FloatConst>(
+    successes: (u64, u64),
+    trials: (u64, u64),
+    test_type: TestTSide,
+    level: T,
+) -> Option<(T, T, (T, T))> {
+    if !valid_counts(successes.0, trials.0) || !valid_counts(successes.1, trials.1) {
+        return None;
+    }
+    let one = T::one();
+    let float = |n: u64| T::from(n).unwrap();
+    let (n1, n2) = (float(trials.0), float(trials.1));
+    let (p1, p2) = (float(successes.0) / n1, float(successes.1) / n2);
+    let std_error = (p1 * (one - p1) / n1 + p2 * (one - p2) / n2).sqrt();
+    let (lower, upper) = wald_interval(p1 - p2, std_error, None, test_type, level)?;
+    Some((p1 - p2, std_error, (lower.max(-one), upper.min(one))))
+}
+
+/// Wald test against 0 of the logarithm `ln_ratio` of a ratio with the
+/// standard error `std_error`, reported on the scale of the ratio.
+fn log_wald_test<T: Float + FloatConst>(
+    ln_ratio: T,
+    std_error: T,
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>> {
+    let (lower, upper) = wald_interval(ln_ratio, std_error, None, test_type, level)?;
+    let stat = ln_ratio / std_error;
+    Some(TestOutput {
+        statistics: stat,
+        pvalue: pvalue_z(stat, test_type),
+        estimate: Some(ln_ratio.exp()),
+        std_error: Some(std_error),
+        df: None,
+        interval: Some((lower.exp(), upper.exp())),
+    })
+}
+
+fn valid_counts(successes: u64, trials: u64) -> bool {
+    trials > 0 && successes <= trials
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rel;
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn proportion_values() {
+        // The references are binomial sums, Beta quantiles by bisection of
+        // betainc, and the Miettinen-Nurminen constrained estimate by
+        // numerical maximization of the likelihood.
+        let close = |(a, b): (f64, f64), (c, d): (f64, f64)| rel(a, c) < 1e-12 && rel(b, d) < 1e-12;
+        let output = one_sample_proportion_ztest(0.3, 37, 100, TestTSide::TwoSided, 0.95).unwrap();
+        assert!(rel(output.statistics, 1.52752523165194667) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.126630457947617192) < 1e-12);
+        assert!(rel(output.std_error.unwrap(), 0.0482804308182932436) < 1e-12);
+        let interval = (0.281823605343245333, 0.467794704190570945);
+        assert!(close(output.interval.unwrap(), interval));
+        let output = one_sample_proportion_ztest(0.3, 37, 100, TestTSide::UpperOneSided, 0.95);
+        let output = output.unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.0633152289738085961) < 1e-12);
+        assert!(close(output.interval.unwrap(), (0.294988491953312456, 1.)));
+
+        let output = binomial_test(0.2, 7, 20, TestTSide::TwoSided, 0.95).unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.0982217286134687334) < 1e-12);
+        assert!(close(
+            output.interval.unwrap(),
+            (0.153909204784541160, 0.592188534532828118)
+        ));
+        let output = binomial_test(0.2, 7, 20, TestTSide::UpperOneSided, 0.95).unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.0866925135674002637) < 1e-12);
+        assert!(close(output.interval.unwrap(), (0.177310917574449116, 1.)));
+        let output = binomial_test(0.2, 7, 20, TestTSide::LowerOneSided, 0.95).unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.967857336919124869) < 1e-12);
+
+        let interval = |x, method| proportion_interval(x, 20, method, 0.95).unwrap();
+        let wilson = (0.181191824101082049, 0.567145723314763767);
+        assert!(close(interval(7, ProportionInterval::Wilson), wilson));
+        let agresti = (0.179926361438228009, 0.568411185977617807);
+        assert!(close(
+            interval(7, ProportionInterval::AgrestiCoull),
+            agresti
+        ));
+        let jeffreys = (0.172276213631912017, 0.567766093841496173);
+        assert!(close(interval(7, ProportionInterval::Jeffreys), jeffreys));
+        let (lower, upper) = interval(0, ProportionInterval::Wilson);
+        assert!(lower.abs() < 1e-16 && rel(upper, 0.161125158052819387) < 1e-12);
+        let (lower, upper) = interval(0, ProportionInterval::ClopperPearson);
+        assert!(lower == 0. && rel(upper, 0.168433470983085337) < 1e-12);
+        let (lower, upper) = interval(0, ProportionInterval::AgrestiCoull);
+        assert!(lower == 0. && rel(upper, 0.189809560542488864) < 1e-12);
+        let (lower, upper) = interval(0, ProportionInterval::Jeffreys);
+        assert!(lower == 0. && rel(upper, 0.116638982904875427) < 1e-12);
+        let (lower, upper) = interval(20, ProportionInterval::Jeffreys);
+        assert!(rel(lower, 0.883361017095124573) < 1e-12 && upper == 1.);
+
+        let (successes, trials) = ((56, 48), (250, 260));
+        let test_type = TestTSide::TwoSided;
+        let output = two_sample_pooled_proportion_ztest(successes, trials, test_type, 0.95);
+        let output = output.unwrap();
+        assert!(rel(output.statistics, 1.10354154734491694) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.269792059277274808) < 1e-12);
+        assert!(rel(output.std_error.unwrap(), 0.0356968811597519638) < 1e-12);
+        let wald = (-0.0305799860489048668, 0.109349216818135636);
+        assert!(close(output.interval.unwrap(), wald));
+        let test_type = TestTSide::UpperOneSided;
+        let output =
+            two_sample_unpooled_proportion_ztest(-0.02, successes, trials, test_type, 0.95);
+        let output = output.unwrap();
+        assert!(rel(output.statistics, 1.66357993906681153) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.0480982018085285386) < 1e-12);
+        assert!(close(
+            output.interval.unwrap(),
+            (-0.0193315290618583268, 1.)
+        ));
+
+        let interval = |method| proportion_difference_interval(successes, trials, method, 0.95);
+        let newcombe = (-0.0306257658243969215, 0.109364308182619930);
+        assert!(close(
+            interval(DifferenceInterval::Newcombe).unwrap(),
+            newcombe
+        ));
+        let miettinen = (-0.0307541638949045378, 0.109874678290042766);
+        let output = interval(DifferenceInterval::MiettinenNurminen).unwrap();
+        assert!(close(output, miettinen));
+        // Tables with a proportion 0 or 1, where 2z²/(19 + 2z²) is the closed
+        // form of the bound of 0/10 against 0/10.
+        let miettinen = |successes, trials| {
+            let method = DifferenceInterval::MiettinenNurminen;
+            proportion_difference_interval(successes, trials, method, 0.95).unwrap()
+        };
+        let bound = 0.287933941282012180;
+        assert!(close(miettinen((0, 0), (10, 10)), (-bound, bound)));
+        assert!(close(miettinen((10, 10), (10, 10)), (-bound, bound)));
+        let output = miettinen((10, 0), (10, 10));
+        assert!(close(output, (0.663641551894767430, 1.)));
+        let output = miettinen((0, 5), (5, 5));
+        assert!(close(output, (-1., -0.401709903160911800)));
+        let output = miettinen((3, 0), (10, 10));
+        assert!(close(
+            output,
+            (-0.0319484283330113810, 0.610138497517912460)
+        ));
+
+        let output = risk_ratio_test(successes, trials, TestTSide::TwoSided, 0.95).unwrap();
+        assert!(rel(output.estimate.unwrap(), 1.21333333333333333) < 1e-12);
+        assert!(rel(output.std_error.unwrap(), 0.175625517349621904) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.270877579939044115) < 1e-12);
+        assert!(close(
+            output.interval.unwrap(),
+            (0.859978151069087643, 1.71187811684242214)
+        ));
+        let output = odds_ratio_test(successes, trials, TestTSide::TwoSided, 0.95).unwrap();
+        assert!(rel(output.estimate.unwrap(), 1.27491408934707904) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.270396264850774473) < 1e-12);
+        assert!(close(
+            output.interval.unwrap(),
+            (0.827757757700566056, 1.96362513077608371)
+        ));
+
+        assert!(binomial_test(0.2, 21, 20, TestTSide::TwoSided, 0.95).is_none());
+        assert!(one_sample_proportion_ztest(1., 7, 20, TestTSide::TwoSided, 0.95).is_none());
+        assert!(risk_ratio_test((0, 3), (10, 10), TestTSide::TwoSided, 0.95).is_none());
+        assert!(proportion_interval(0, 0, ProportionInterval::Wilson, 0.95).is_none());
+    }
+}
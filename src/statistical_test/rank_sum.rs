@@ -0,0 +1,423 @@
+use std::borrow::Borrow;
+
+use num_traits::{Float, FloatConst};
+
+use super::{
+    average_ranks, pvalue_t, pvalue_z, select_sorted_matrix, wald_interval, PValueMethod,
+    TestOutput, TestTSide,
+};
+use crate::distribution::quantile_n01;
+
+/// Size of both samples from which [`PValueMethod::Auto`] uses the normal
+/// approximation of the Mann-Whitney statistic.
+const EXACT_MAX_LEN: usize = 50;
+/// Number of entries of the table of the exact distribution of the rank sum,
+/// n<sub>1</sub> times about (n<sub>1</sub> + n<sub>2</sub>)<sup>2</sup>,
+/// beyond which [`PValueMethod::Exact`] is refused.
+const EXACT_MAX_ENTRIES: usize = 10_000_000;
+
+/// Mann-Whitney U test, or Wilcoxon rank-sum test.
+///
+/// Assuming `sample1` and `sample2` are independent samples of continuous
+/// distributions which differ by a location shift ∆ at most, this function
+/// tests ∆ against ∆<sub>0</sub>, see [`TestTSide`]; without this
+/// assumption, it tests P(X - ∆<sub>0</sub> > Y) against 1/2 when the
+/// variances of the placements are equal, see [`brunner_munzel_test`]
+/// otherwise. The statistic U is the sum of the ranks of x<sub>i</sub> -
+/// ∆<sub>0</sub> in the pooled sample, ties receiving their average rank,
+/// minus n<sub>1</sub>(n<sub>1</sub> + 1)/2.
+///
+/// The exact p-value is computed from the distribution of U over the equally
+/// likely assignments of the pooled ranks to the samples, which stays exact
+/// with ties, from a table of about n<sub>1</sub>(n<sub>1</sub> +
+/// n<sub>2</sub>)<sup>2</sup> entries, limited to 10<sup>7</sup>. The normal
+/// approximation uses a continuity correction and the variance of U
+/// corrected for the ties. [`PValueMethod::Auto`] is exact when both samples
+/// have less than 50 values, see [`mann_whitney_uses_exact`].
+///
+/// The output holds the Hodges-Lehmann estimate of the shift, the median of
+/// the differences x<sub>i</sub> - y<sub>j</sub>, and its confidence interval
+/// at `level`, bounded by order statistics of the differences whose ranks
+/// come from the exact null distribution of U without ties when the p-value
+/// is exact, and from its normal approximation otherwise, see [Hollander,
+/// Wolfe and Chicken (2014)][hwc], section 4.3. The common-language effect
+/// size U / (n<sub>1</sub> n<sub>2</sub>) is the estimate of
+/// [`brunner_munzel_test`] on x<sub>i</sub> - ∆<sub>0</sub> and
+/// y<sub>j</sub>.
+///
+/// `sample1` and `sample2` can be slices, ndarray views or iterators of
+/// values. Returns `None` when a sample is empty or has a NaN or an infinite
+/// value, when `level` is not in (0, 1), or when the exact distribution is
+/// requested beyond the limit of its table.
+///
+/// [hwc]: https://doi.org/10.1002/9781119196037
+pub fn mann_whitney_u_test<T, I1, I2>(
+    delta0: T,
+    sample1: I1,
+    sample2: I2,
+    test_type: TestTSide,
+    method: PValueMethod,
+    level: T,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let (zero, one) = (T::zero(), T::one());
+    let two = one + one;
+    let (x, y) = collect_samples(sample1, sample2)?;
+    if level.is_nan()
+        || level <= zero
+        || level >= one
+        || x.iter().chain(&y).any(|v| v.is_infinite())
+    {
+        return None;
+    }
+    let (n1, n2) = (x.len(), y.len());
+    let pooled = x.iter().map(|v| *v - delta0).chain(y.iter().copied());
+    let (ranks, groups) = average_ranks(&pooled.collect::<Vec<_>>());
+    let sum = ranks[..n1].iter().fold(zero, |s, r| s + *r);
+    let (f1, f2) = (T::from(n1).unwrap(), T::from(n2).unwrap());
+    let stat = sum - f1 * (f1 + one) / two;
+    if method == PValueMethod::Exact
+        && (n1 + 1) * ((n1 + n2) * (n1 + n2 + 1) + 1) > EXACT_MAX_ENTRIES
+    {
+        return None;
+    }
+    let exact = mann_whitney_uses_exact(n1, n2, method);
+    let pvalue = if exact {
+        // The ranks are multiples of 1/2: the distribution runs over twice
+        // the rank sum.
+        let doubled = ranks
+            .iter()
+            .map(|r| (*r + *r).to_usize().unwrap())
+            .collect::<Vec<_>>();
+        let dist = rank_sum_distribution::<T>(&doubled, n1);
+        let w = (sum + sum).to_usize().unwrap();
+        let lower = dist[..=w].iter().fold(zero, |s, p| s + *p);
+        let upper = dist[w..].iter().fold(zero, |s, p| s + *p);
+        Some(match test_type {
+            TestTSide::UpperOneSided => upper.min(one),
+            TestTSide::LowerOneSided => lower.min(one),
+            TestTSide::TwoSided => (two * lower.min(upper)).min(one),
+        })
+    } else {
+        let total = f1 + f2;
+        let ties = groups
+            .iter()
+            .map(|t| {
+                let t = T::from(*t).unwrap();
+                t * t * t - t
+            })
+            .fold(zero, |s, c| s + c);
+        let var = f1 * f2 / T::from(12.).unwrap() * (total + one - ties / (total * (total - one)));
+        let diff = stat - f1 * f2 / two;
+        let half = one / two;
+        let correction = match test_type {
+            TestTSide::UpperOneSided => half,
+            TestTSide::LowerOneSided => -half,
+            TestTSide::TwoSided if diff > zero => half,
+            TestTSide::TwoSided if diff < zero => -half,
+            TestTSide::TwoSided => zero,
+        };
+        if var > zero {
+            pvalue_z((diff - correction) / var.sqrt(), test_type).map(|p| p.min(one))
+        } else {
+            None
+        }
+    };
+    let (shift, interval) = hodges_lehmann_shift(&x, &y, test_type, exact, level)?;
+    Some(TestOutput {
+        statistics: stat,
+        pvalue,
+        estimate: Some(shift),
+        std_error: None,
+        df: None,
+        interval: Some(interval),
+    })
+}
+
+/// Whether [`mann_whitney_u_test`] computes the p-value from the exact null
+/// distribution for samples of sizes `n1` and `n2` under `method`.
+pub fn mann_whitney_uses_exact(n1: usize, n2: usize, method: PValueMethod) -> bool {
+    match method {
+        PValueMethod::Exact => true,
+        PValueMethod::Asymptotic => false,
+        PValueMethod::Auto => n1 < EXACT_MAX_LEN && n2 < EXACT_MAX_LEN,
+    }
+}
+
+/// Brunner-Munzel test.
+///
+/// Assuming `sample1` and `sample2` are independent samples, this function
+/// tests the relative effect p = P(X > Y) + P(X = Y)/2 against 1/2, see
+/// [`TestTSide`], without assuming equal variances or shapes, unlike
+/// [`mann_whitney_u_test`]. The statistic of [Brunner and Munzel
+/// (2000)][bm] is the ratio of p̂ - 1/2 to its standard error estimated from
+/// the placements of each observation among the other sample, and follows
+/// under H<sub>0</sub> approximately the central Student's t-distribution
+/// with the Satterthwaite degrees of freedom. The output holds p̂, its
+/// standard error and its confidence interval at `level`, clipped to \[0,
+/// 1\].
+///
+/// `sample1` and `sample2` can be slices, ndarray views or iterators of
+/// values. Returns `None` when a sample has less than two values or has a
+/// NaN, when the samples are completely separated or constant, so that the
+/// variance estimate vanishes, or when `level` is not in (0, 1).
+///
+/// [bm]: https://doi.org/10.1002/(SICI)1521-4036(200001)42:1%3C17::AID-BIMJ17%3E3.0.CO;2-U
+pub fn brunner_munzel_test<T, I1, I2>(
+    sample1: I1,
+    sample2: I2,
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let (zero, one) = (T::zero(), T::one());
+    let two = one + one;
+    let (x, y) = collect_samples(sample1, sample2)?;
+    let (n1, n2) = (x.len(), y.len());
+    if n1 < 2 || n2 < 2 {
+        return None;
+    }
+    let pooled = x.iter().chain(y.iter()).copied().collect::<Vec<_>>();
+    let (ranks, _) = average_ranks(&pooled);
+    let (f1, f2) = (T::from(n1).unwrap(), T::from(n2).unwrap());
+    // Mean pooled rank of a sample and variance of the placements, the
+    // pooled minus the internal ranks.
+    let placements = |pooled: &[T], sample: &[T], size: T| {
+        let (internal, _) = average_ranks(sample);
+        let mean = pooled.iter().fold(zero, |s, r| s + *r) / size;
+        let center = mean - (size + one) / two;
+        let squares = pooled
+            .iter()
+            .zip(&internal)
+            .fold(zero, |s, (r, q)| s + (*r - *q - center).powi(2));
+        (mean, squares / (size - one))
+    };
+    let (mean1, var1) = placements(&ranks[..n1], &x, f1);
+    let (_, var2) = placements(&ranks[n1..], &y, f2);
+    let (scaled1, scaled2) = (f1 * var1, f2 * var2);
+    let spread = scaled1 + scaled2;
+    if spread <= zero {
+        return None;
+    }
+    let estimate = (mean1 - (f1 + one) / two) / f2;
+    let std_error = spread.sqrt() / (f1 * f2);
+    let stat = (estimate - one / two) / std_error;
+    let df = spread * spread / (scaled1 * scaled1 / (f1 - one) + scaled2 * scaled2 / (f2 - one));
+    let (lower, upper) = wald_interval(estimate, std_error, Some(df), test_type, level)?;
+    Some(TestOutput {
+        statistics: stat,
+        pvalue: pvalue_t(stat, df, test_type),
+        estimate: Some(estimate),
+        std_error: Some(std_error),
+        df: Some(df),
+        interval: Some((lower.max(zero), upper.min(one))),
+    })
+}
+
+/// Values of both samples, or `None` when one is empty or has a NaN.
+fn collect_samples<T, I1, I2>(sample1: I1, sample2: I2) -> Option<(Vec<T>, Vec<T>)>
+where
+    T: Float,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let x = sample1.into_iter().map(|v| *v.borrow()).collect::<Vec<_>>();
+    let y = sample2.into_iter().map(|v| *v.borrow()).collect::<Vec<_>>();
+    if x.is_empty() || y.is_empty() || x.iter().chain(&y).any(|v| v.is_nan()) {
+        return None;
+    }
+    Some((x, y))
+}
+
+/// Probabilities of the values 0, 1, . . . of the sum of a random subset of
+/// `size` of the `weights`, all subsets being equally likely.
+fn rank_sum_distribution<T: Float>(weights: &[usize], size: usize) -> Vec<T> {
+    let total = weights.iter().sum::<usize>();
+    // counts[k][s]: number of subsets of k of the weights seen so far with
+    // sum s.
+    let mut counts = vec![vec![T::zero(); total + 1]; size + 1];
+    counts[0][0] = T::one();
+    let mut reach = 0;
+    for w in weights {
+        reach += w;
+        for k in (1..=size).rev() {
+            let (done, todo) = counts.split_at_mut(k);
+            let (previous, current) = (&done[k - 1], &mut todo[0]);
+            for s in (*w..=reach).rev() {
+                current[s] = current[s] + previous[s - w];
+            }
+        }
+    }
+    let mut dist = counts.swap_remove(size);
+    let norm = dist.iter().fold(T::zero(), |s, c| s + *c);
+    dist.iter_mut().for_each(|c| *c = *c / norm);
+    dist
+}
+
+/// Hodges-Lehmann estimate of the shift between `x` and `y` and its
+/// confidence interval, see [`mann_whitney_u_test`].
+fn hodges_lehmann_shift<T: Float + FloatConst>(
+    x: &[T],
+    y: &[T],
+    test_type: TestTSide,
+    exact: bool,
+    level: T,
+) -> Option<(T, (T, T))> {
+    let one = T::one();
+    let two = one + one;
+    let half = one / two;
+    let sort = |sample: &[T]| {
+        let mut sorted = sample.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        sorted
+    };
+    let (x, y) = (sort(x), sort(y));
+    let (n1, n2) = (x.len(), y.len());
+    let len = n1 * n2;
+    // Difference of rank k among the x(i) - y(j) of the sorted samples, with
+    // the columns in decreasing order of y.
+    let diff = |k| select_sorted_matrix(n1, n2, |_| 0, |i, j| x[i] - y[n2 - 1 - j], k);
+    let median = half * diff((len - 1) / 2) + half * diff(len / 2);
+    let alpha = match test_type {
+        TestTSide::TwoSided => (one - level) / two,
+        _ => one - level,
+    };
+    // The interval [D(k), D(len + 1 - k)] of the sorted differences, with k
+    // the smallest integer such that P(U ≤ k) ≥ alpha, or its normal
+    // approximation.
+    let k = if exact {
+        let dist = rank_sum_distribution::<T>(&(1..=n1 + n2).collect::<Vec<_>>(), n1);
+        let mut cumulated = T::zero();
+        dist[n1 * (n1 + 1) / 2..]
+            .iter()
+            .position(|p| {
+                cumulated = cumulated + *p;
+                cumulated >= alpha
+            })
+            .unwrap_or(len)
+    } else {
+        let (f1, f2) = (T::from(n1).unwrap(), T::from(n2).unwrap());
+        let sd = (f1 * f2 * (f1 + f2 + one) / T::from(12.).unwrap()).sqrt();
+        let k = T::from(len).unwrap() / two + quantile_n01(alpha)? * sd;
+        k.floor().max(T::zero()).to_usize().unwrap_or(0)
+    };
+    let k = k.clamp(1, len);
+    let (lower, upper) = (diff(k - 1), diff(len - k));
+    let interval = match test_type {
+        TestTSide::TwoSided => (lower, upper),
+        TestTSide::UpperOneSided => (lower, T::infinity()),
+        TestTSide::LowerOneSided => (T::neg_infinity(), upper),
+    };
+    Some((median, interval))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{earnings, rel};
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn rank_sum_values() {
+        // The reference values enumerate the exact distributions with
+        // Python fractions and sort all the differences x(i) - y(j).
+        // Permeability constants of the human chorioamnion at term and at 12
+        // to 26 weeks of gestational age, from Hollander and Wolfe.
+        let x = [0.80, 0.83, 1.89, 1.04, 1.45, 1.38, 1.91, 1.64, 0.73, 1.46];
+        let y = [1.15, 0.88, 0.90, 0.74, 1.21];
+        let test = |side, method| mann_whitney_u_test(0., x, y, side, method, 0.95).unwrap();
+        let upper = test(TestTSide::UpperOneSided, PValueMethod::Auto);
+        assert!(mann_whitney_uses_exact(10, 5, PValueMethod::Auto));
+        assert_eq!(upper.statistics, 35.);
+        assert!(rel(upper.pvalue.unwrap(), 0.127206127206127206) < 1e-12);
+        assert!(rel(upper.estimate.unwrap(), 0.305) < 1e-12);
+        assert!(rel(upper.interval.unwrap().0, -0.08) < 1e-12);
+        assert_eq!(upper.interval.unwrap().1, f64::INFINITY);
+        let two_sided = test(TestTSide::TwoSided, PValueMethod::Exact);
+        assert!(rel(two_sided.pvalue.unwrap(), 0.254412254412254412) < 1e-12);
+        assert!(rel(two_sided.interval.unwrap().0, -0.15) < 1e-12);
+        assert!(rel(two_sided.interval.unwrap().1, 0.76) < 1e-12);
+        let normal = test(TestTSide::TwoSided, PValueMethod::Asymptotic);
+        assert!(!mann_whitney_uses_exact(10, 5, PValueMethod::Asymptotic));
+        assert!(rel(normal.pvalue.unwrap(), 0.244623605126983321) < 1e-12);
+        let output = brunner_munzel_test(x, y, TestTSide::TwoSided, 0.95).unwrap();
+        // The common-language effect size U / (n1 n2).
+        assert!(rel(output.estimate.unwrap(), 0.7) < 1e-12);
+        assert!(rel(output.statistics, 1.41030148416680535) < 1e-12);
+        assert!(rel(output.df.unwrap(), 11.6282573330046833) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.184635896803675789) < 1e-12);
+        assert!(rel(output.interval.unwrap().0, 0.389915484614526484) < 1e-12);
+        assert_eq!(output.interval.unwrap().1, 1.);
+
+        // Lalonde earnings in 1978 of the treated against the controls, with
+        // many zeros.
+        let (treated, controls) = (earnings(1), earnings(0));
+        let test = |side, len: Option<usize>| {
+            let len = |sample: &[f64]| len.unwrap_or(sample.len());
+            mann_whitney_u_test(
+                0.,
+                &treated[..len(&treated)],
+                &controls[..len(&controls)],
+                side,
+                PValueMethod::Auto,
+                0.95,
+            )
+            .unwrap()
+        };
+        let output = test(TestTSide::TwoSided, None);
+        assert!(!mann_whitney_uses_exact(
+            treated.len(),
+            controls.len(),
+            PValueMethod::Auto
+        ));
+        assert_eq!(output.statistics, 68209.5);
+        assert!(rel(output.pvalue.unwrap(), 0.0619228097060161238) < 1e-12);
+        assert!(rel(output.estimate.unwrap(), 130.67909240722656) < 1e-12);
+        assert_eq!(output.interval.unwrap().0, 0.);
+        assert!(rel(output.interval.unwrap().1, 883.1552429199219) < 1e-12);
+        let upper = test(TestTSide::UpperOneSided, None);
+        assert!(rel(upper.pvalue.unwrap(), 0.0309614048530080619) < 1e-12);
+        // The first 20 of each group, with zeros in both: exact p-value.
+        let output = test(TestTSide::TwoSided, Some(20));
+        assert!(mann_whitney_uses_exact(20, 20, PValueMethod::Auto));
+        assert_eq!(output.statistics, 224.);
+        assert!(rel(output.pvalue.unwrap(), 0.527888207595128328) < 1e-12);
+        assert!(rel(output.estimate.unwrap(), 714.485595703125) < 1e-12);
+        assert!(rel(output.interval.unwrap().0, -2453.6337890625) < 1e-12);
+        assert!(rel(output.interval.unwrap().1, 5150.32568359375) < 1e-12);
+        let output = brunner_munzel_test(&treated, &controls, TestTSide::TwoSided, 0.95).unwrap();
+        assert!(rel(output.statistics, 1.87566195778125629) < 1e-12);
+        assert!(rel(output.df.unwrap(), 647.909431347409346) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.0611511541339956823) < 1e-12);
+        assert!(rel(output.estimate.unwrap(), 0.540380273321449792) < 1e-12);
+        assert!(rel(output.std_error.unwrap(), 0.0215285452444832417) < 1e-12);
+        let (lower, upper) = output.interval.unwrap();
+        assert!(rel(lower, 0.498106129914621757) < 1e-12);
+        assert!(rel(upper, 0.582654416728277828) < 1e-12);
+
+        let side = TestTSide::TwoSided;
+        assert!(mann_whitney_u_test(0., x, [0f64; 0], side, PValueMethod::Auto, 0.95).is_none());
+        assert!(mann_whitney_u_test(0., x, y, side, PValueMethod::Auto, 1.).is_none());
+        let (x, y) = ([f64::INFINITY, 1., 2.], [f64::INFINITY, 3.]);
+        assert!(mann_whitney_u_test(0., x, y, side, PValueMethod::Auto, 0.95).is_none());
+        let large = (0..300).map(f64::from).collect::<Vec<_>>();
+        let test = |method| mann_whitney_u_test(0.5, &large, &large, side, method, 0.95);
+        assert!(test(PValueMethod::Exact).is_none());
+        assert!(test(PValueMethod::Auto).is_some());
+        assert!(brunner_munzel_test([1., 2.], [3., 4.], side, 0.95).is_none());
+    }
+}
@@ -0,0 +1,330 @@
+use std::borrow::Borrow;
+
+use num_traits::{Float, FloatConst};
+
+use super::{
+    average_ranks, differences, pvalue_z, select_sorted_matrix, PValueMethod, TestOutput, TestTSide,
+};
+use crate::distribution::quantile_n01;
+
+/// Number of non-zero differences from which [`PValueMethod::Auto`] uses the
+/// normal approximation of the signed-rank statistic.
+const EXACT_MAX_LEN: usize = 50;
+
+/// Wilcoxon signed-rank test.
+///
+/// Assuming the differences D<sub>1</sub>, . . . , D<sub>n</sub> in `sample`
+/// are independent and symmetric about their pseudo-median θ, this function
+/// tests θ against μ<sub>0</sub>, see [`TestTSide`]. The statistic V is the
+/// sum of the ranks of |D<sub>i</sub> - μ<sub>0</sub>| over the positive
+/// D<sub>i</sub> - μ<sub>0</sub>, ties receiving their average rank. The
+/// zeros are ranked with the other differences and then dropped, following
+/// [Pratt (1959)][pratt].
+///
+/// The exact p-value is computed from the distribution of V over the
+/// 2<sup>m</sup> equally likely signs of the m non-zero differences given
+/// their ranks, which stays exact with ties and zeros. The normal
+/// approximation uses a continuity correction and the mean and variance of V
+/// corrected for the zeros and the ties. [`PValueMethod::Auto`] is exact
+/// below 50 non-zero differences, see [`signed_rank_uses_exact`].
+///
+/// The output holds the Hodges-Lehmann estimate of the pseudo-median, the
+/// median of the Walsh averages (d<sub>i</sub> + d<sub>j</sub>)/2, i ≤ j, and
+/// its confidence interval at `level`, bounded by order statistics of the
+/// Walsh averages whose ranks come from the exact null distribution of V
+/// without ties when the p-value is exact, and from its normal approximation
+/// otherwise, see [Hollander, Wolfe and Chicken (2014)][hwc], section 3.3.
+///
+/// `sample` can be a slice, an ndarray view or an iterator of values.
+/// Returns `None` when the sample has a NaN, an infinite value or no non-zero
+/// difference, or when `level` is not in (0, 1).
+///
+/// [pratt]: https://doi.org/10.1080/01621459.1959.10501526
+/// [hwc]: https://doi.org/10.1002/9781119196037
+pub fn wilcoxon_signed_rank_test<T, I>(
+    mu0: T,
+    sample: I,
+    test_type: TestTSide,
+    method: PValueMethod,
+    level: T,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I: IntoIterator,
+    I::Item: Borrow<T>,
+{
+    let sample = sample.into_iter().map(|x| *x.borrow()).collect::<Vec<_>>();
+    signed_rank_test(mu0, sample, test_type, method, level)
+}
+
+/// [`wilcoxon_signed_rank_test`] on the within-unit differences
+/// x<sub>i</sub> - y<sub>i</sub> of the paired samples `sample1` and
+/// `sample2`, for instance the same units before and after a treatment.
+/// Returns `None` when their lengths differ.
+pub fn paired_wilcoxon_signed_rank_test<T, I1, I2>(
+    mu0: T,
+    sample1: I1,
+    sample2: I2,
+    test_type: TestTSide,
+    method: PValueMethod,
+    level: T,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let diffs = differences(sample1, sample2)?;
+    signed_rank_test(mu0, diffs, test_type, method, level)
+}
+
+fn signed_rank_test<T: Float + FloatConst>(
+    mu0: T,
+    sample: Vec<T>,
+    test_type: TestTSide,
+    method: PValueMethod,
+    level: T,
+) -> Option<TestOutput<T, T>> {
+    let (zero, one) = (T::zero(), T::one());
+    let two = one + one;
+    if level.is_nan() || level <= zero || level >= one || sample.iter().any(|x| !x.is_finite()) {
+        return None;
+    }
+    let shifted = sample.iter().map(|x| *x - mu0).collect::<Vec<_>>();
+    let (ranks, groups) = average_ranks(&shifted.iter().map(|x| x.abs()).collect::<Vec<_>>());
+    let n = sample.len();
+    let zeros = shifted.iter().filter(|x| **x == zero).count();
+    let m = n - zeros;
+    if m == 0 {
+        return None;
+    }
+    let stat = shifted
+        .iter()
+        .zip(&ranks)
+        .filter(|(x, _)| **x > zero)
+        .fold(zero, |v, (_, r)| v + *r);
+    let exact = signed_rank_uses_exact(m, method);
+    let pvalue = if exact {
+        // The ranks are multiples of 1/2: the distribution runs over 2V.
+        let doubled = shifted
+            .iter()
+            .zip(&ranks)
+            .filter(|(x, _)| **x != zero)
+            .map(|(_, r)| (*r + *r).to_usize().unwrap())
+            .collect::<Vec<_>>();
+        let dist = signed_rank_distribution::<T>(&doubled);
+        let v = (stat + stat).to_usize().unwrap();
+        let lower = dist[..=v].iter().fold(zero, |s, p| s + *p);
+        let upper = dist[v..].iter().fold(zero, |s, p| s + *p);
+        Some(match test_type {
+            TestTSide::UpperOneSided => upper.min(one),
+            TestTSide::LowerOneSided => lower.min(one),
+            TestTSide::TwoSided => (two * lower.min(upper)).min(one),
+        })
+    } else {
+        // Mean and variance of V given the zeros and the ties, see Pratt
+        // (1959).
+        let moments = |k: usize| {
+            let k = T::from(k).unwrap();
+            let triangle = k * (k + one);
+            (triangle, triangle * (k + k + one))
+        };
+        let (sum_n, squares_n) = moments(n);
+        let (sum_zeros, squares_zeros) = moments(zeros);
+        let ties = groups
+            .iter()
+            .skip(usize::from(zeros > 0))
+            .map(|t| {
+                let t = T::from(*t).unwrap();
+                t * t * t - t
+            })
+            .fold(zero, |s, c| s + c);
+        let mean = (sum_n - sum_zeros) / T::from(4.).unwrap();
+        let var =
+            (squares_n - squares_zeros) / T::from(24.).unwrap() - ties / T::from(48.).unwrap();
+        let diff = stat - mean;
+        let half = one / two;
+        let correction = match test_type {
+            TestTSide::UpperOneSided => half,
+            TestTSide::LowerOneSided => -half,
+            TestTSide::TwoSided if diff > zero => half,
+            TestTSide::TwoSided if diff < zero => -half,
+            TestTSide::TwoSided => zero,
+        };
+        pvalue_z((diff - correction) / var.sqrt(), test_type).map(|p| p.min(one))
+    };
+    let (pseudo_median, interval) = hodges_lehmann(&sample, test_type, exact, level)?;
+    Some(TestOutput {
+        statistics: stat,
+        pvalue,
+        estimate: Some(pseudo_median),
+        std_error: None,
+        df: None,
+        interval: Some(interval),
+    })
+}
+
+/// Whether [`wilcoxon_signed_rank_test`] computes the p-value from the exact
+/// null distribution for `nonzero` non-zero differences under `method`.
+pub fn signed_rank_uses_exact(nonzero: usize, method: PValueMethod) -> bool {
+    match method {
+        PValueMethod::Exact => true,
+        PValueMethod::Asymptotic => false,
+        PValueMethod::Auto => nonzero < EXACT_MAX_LEN,
+    }
+}
+
+/// Probabilities of the values 0, 1, . . . of the sum of the random subset
+/// of `weights` including each weight independently with probability 1/2.
+fn signed_rank_distribution<T: Float>(weights: &[usize]) -> Vec<T> {
+    let half = T::from(0.5).unwrap();
+    let mut dist = vec![T::one()];
+    for w in weights {
+        let mut next = vec![T::zero(); dist.len() + w];
+        for (s, p) in dist.iter().enumerate() {
+            next[s] = next[s] + *p * half;
+            next[s + w] = next[s + w] + *p * half;
+        }
+        dist = next;
+    }
+    dist
+}
+
+/// Hodges-Lehmann estimate of the pseudo-median of `sample` and its
+/// confidence interval, see [`wilcoxon_signed_rank_test`].
+fn hodges_lehmann<T: Float + FloatConst>(
+    sample: &[T],
+    test_type: TestTSide,
+    exact: bool,
+    level: T,
+) -> Option<(T, (T, T))> {
+    let one = T::one();
+    let two = one + one;
+    let half = one / two;
+    let mut sorted = sample.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = sorted.len();
+    let len = n * (n + 1) / 2;
+    // Walsh average of rank k among the (x(i) + x(j))/2, i ≤ j, of the
+    // sorted sample.
+    let walsh =
+        |k| select_sorted_matrix(n, n, |i| i, |i, j| half * sorted[i] + half * sorted[j], k);
+    let median = (walsh((len - 1) / 2) + walsh(len / 2)) / two;
+    let alpha = match test_type {
+        TestTSide::TwoSided => (one - level) / two,
+        _ => one - level,
+    };
+    // The interval [W(k), W(len + 1 - k)] of the sorted Walsh averages,
+    // with k the smallest integer such that P(V ≤ k) ≥ alpha, or its normal
+    // approximation.
+    let k = if exact {
+        let dist = signed_rank_distribution::<T>(&(1..=n).collect::<Vec<_>>());
+        let mut cumulated = T::zero();
+        dist.iter()
+            .position(|p| {
+                cumulated = cumulated + *p;
+                cumulated >= alpha
+            })
+            .unwrap_or(len)
+    } else {
+        let nf = T::from(n).unwrap();
+        let sd = (nf * (nf + one) * (nf + nf + one) / T::from(24.).unwrap()).sqrt();
+        let k = T::from(len).unwrap() / two + quantile_n01(alpha)? * sd;
+        k.floor().max(T::zero()).to_usize().unwrap_or(0)
+    };
+    let k = k.clamp(1, len);
+    let (lower, upper) = (walsh(k - 1), walsh(len - k));
+    let interval = match test_type {
+        TestTSide::TwoSided => (lower, upper),
+        TestTSide::UpperOneSided => (lower, T::infinity()),
+        TestTSide::LowerOneSided => (T::neg_infinity(), upper),
+    };
+    Some((median, interval))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::lalonde::{RE75, RE78},
+        test_util::rel,
+    };
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    #[allow(clippy::approx_constant)]
+    fn signed_rank_values() {
+        // The reference values enumerate the exact distributions with
+        // Python fractions, sort all the Walsh averages, and compute the
+        // normal p-values in mpmath from the moments of Pratt (1959).
+        // Hamilton depression scale before and after a tranquilizer, from
+        // Hollander and Wolfe, as in R's wilcox.test(x, y, paired = TRUE).
+        let x = [1.83, 0.50, 1.62, 2.48, 1.68, 1.88, 1.55, 3.06, 1.30];
+        let y = [0.878, 0.647, 0.598, 2.05, 1.06, 1.29, 1.06, 3.14, 1.29];
+        let test =
+            |side, method| paired_wilcoxon_signed_rank_test(0., x, y, side, method, 0.95).unwrap();
+        let upper = test(TestTSide::UpperOneSided, PValueMethod::Auto);
+        assert!(signed_rank_uses_exact(9, PValueMethod::Auto));
+        assert_eq!(upper.statistics, 40.);
+        assert!(rel(upper.pvalue.unwrap(), 10. / 512.) < 1e-12);
+        assert!(rel(upper.estimate.unwrap(), 0.46) < 1e-12);
+        assert!(rel(upper.interval.unwrap().0, 0.175) < 1e-12);
+        assert_eq!(upper.interval.unwrap().1, f64::INFINITY);
+        let two_sided = test(TestTSide::TwoSided, PValueMethod::Exact);
+        assert!(rel(two_sided.pvalue.unwrap(), 20. / 512.) < 1e-12);
+        assert!(rel(two_sided.interval.unwrap().0, 0.01) < 1e-12);
+        assert!(rel(two_sided.interval.unwrap().1, 0.786) < 1e-12);
+        let normal = test(TestTSide::TwoSided, PValueMethod::Asymptotic);
+        assert!(!signed_rank_uses_exact(9, PValueMethod::Asymptotic));
+        assert!(rel(normal.pvalue.unwrap(), 0.04401098401295141) < 1e-12);
+        // Lalonde earnings in 1978 against 1975, per person: 95 zeros, from
+        // the units without earnings both years, and ties.
+        let re78 = RE78.iter().map(|x| *x as f64).collect::<Vec<_>>();
+        let re75 = RE75.iter().map(|x| *x as f64).collect::<Vec<_>>();
+        let test = |side, len| {
+            paired_wilcoxon_signed_rank_test(
+                0.,
+                &re78[..len],
+                &re75[..len],
+                side,
+                PValueMethod::Auto,
+                0.95,
+            )
+            .unwrap()
+        };
+        let output = test(TestTSide::TwoSided, re78.len());
+        assert!(!signed_rank_uses_exact(627, PValueMethod::Auto));
+        assert_eq!(output.statistics, 179473.);
+        assert!(rel(output.pvalue.unwrap(), 5.5646134564554109e-20) < 1e-11);
+        assert!(rel(output.estimate.unwrap(), 2046.14794921875) < 1e-12);
+        assert!(rel(output.interval.unwrap().0, 1614.25146484375) < 1e-12);
+        assert!(rel(output.interval.unwrap().1, 2522.9629516601562) < 1e-12);
+        let upper = test(TestTSide::UpperOneSided, re78.len());
+        assert!(rel(upper.pvalue.unwrap(), 2.7823067282277054e-20) < 1e-11);
+        let lower = test(TestTSide::LowerOneSided, re78.len());
+        assert!(rel(lower.pvalue.unwrap(), 1.) < 1e-12);
+        // The first 40 units, with 5 zeros and ties: exact p-value.
+        let output = test(TestTSide::TwoSided, 40);
+        assert!(signed_rank_uses_exact(35, PValueMethod::Auto));
+        assert_eq!(output.statistics, 796.);
+        assert!(rel(output.pvalue.unwrap(), 2.910383045673370361328125e-10) < 1e-12);
+        assert!(rel(output.estimate.unwrap(), 6997.141876220703) < 1e-12);
+        assert!(rel(output.interval.unwrap().0, 4948.5244140625) < 1e-12);
+        assert!(rel(output.interval.unwrap().1, 9460.62890625) < 1e-12);
+        let level = |level| {
+            wilcoxon_signed_rank_test(0., x, TestTSide::TwoSided, PValueMethod::Auto, level)
+        };
+        assert!(level(1.).is_none());
+        assert!(level(0.5).is_some());
+        let zeros = [0., 0.];
+        let side = TestTSide::TwoSided;
+        assert!(wilcoxon_signed_rank_test(0., zeros, side, PValueMethod::Auto, 0.9).is_none());
+        let infinite = [f64::INFINITY, f64::NEG_INFINITY, 1., 2., 3.];
+        assert!(wilcoxon_signed_rank_test(0., infinite, side, PValueMethod::Auto, 0.9).is_none());
+        assert!(
+            paired_wilcoxon_signed_rank_test(0., x, [1.], side, PValueMethod::Auto, 0.9).is_none()
+        );
+    }
+}
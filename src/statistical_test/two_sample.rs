@@ -1,10 +1,9 @@
-use std::fmt::Debug;
+use std::{borrow::Borrow, fmt::Debug};
 
 use num_traits::{Float, FloatConst};
 
-use crate::cdf_nt;
-
-use super::{TestOutput, TestTSide};
+use super::{pvalue_t, wald_interval, TestOutput, TestTSide};
+use crate::distribution::variance;
 
 /// Two samples T-test assuming equal population variances.
 ///
@@ -30,12 +29,22 @@ use super::{TestOutput, TestTSide};
 ///
 /// (c) H<sub>0</sub> : ∆ = ∆<sub>0</sub> vs H<sub>1</sub> : ∆ != ∆<sub>0</sub>
 /// (two-sided test).
+///
+/// Under H<sub>0</sub> and σ<sub>1</sub> = σ<sub>2</sub>, the statistic
+/// follows the central Student's t-distribution with n<sub>1</sub> +
+/// n<sub>2</sub> - 2 degrees of freedom.
+///
+/// The output holds the estimate m<sub>1</sub> - m<sub>2</sub> of ∆, its
+/// standard error from the pooled variance, the degrees of freedom and the
+/// Student's confidence interval at `level`. Returns `None` when `level` is
+/// not in (0, 1).
 pub fn two_sample_homoscedastic_ttest<T: Float + FloatConst + Debug>(
     delta0: T,
     sample_means: (T, T),
     sample_sizes: (T, T),
     sample_vars: (T, T),
     test_type: TestTSide,
+    level: T,
 ) -> Option<TestOutput<T, T>> {
     let (m1, m2) = sample_means;
     let (n1, n2) = sample_sizes;
@@ -47,21 +56,218 @@ pub fn two_sample_homoscedastic_ttest<T: Float + FloatConst + Debug>(
         return None;
     }
     let var = (one / (n - two)) * ((n1 - one) * v1 + (n2 - one) * v2);
-    let sigma = var.sqrt();
-    let stat = if var > T::zero() {
-        (m1 - m2 - delta0) / (sigma * (one / n1 + one / n2).sqrt())
+    let std_error = if var > T::zero() {
+        var.sqrt() * (one / n1 + one / n2).sqrt()
     } else {
         return None;
     };
-    let pvalue = match test_type {
-        TestTSide::UpperOneSided => cdf_nt(stat, n - two, stat).map(|cdf_stat| one - cdf_stat),
-        TestTSide::LowerOneSided => cdf_nt(stat, n - two, stat),
-        TestTSide::TwoSided => {
-            cdf_nt(stat.abs(), n - two, stat).map(|cdf_abs_stat| two * (one - cdf_abs_stat))
-        }
-    };
+    let estimate = m1 - m2;
+    let stat = (estimate - delta0) / std_error;
+    let df = n - two;
     Some(TestOutput {
         statistics: stat,
-        pvalue,
+        pvalue: pvalue_t(stat, df, test_type),
+        estimate: Some(estimate),
+        std_error: Some(std_error),
+        df: Some(df),
+        interval: Some(wald_interval(
+            estimate,
+            std_error,
+            Some(df),
+            test_type,
+            level,
+        )?),
     })
 }
+
+/// Two samples T-test of Welch, without assuming equal population variances.
+///
+/// With the populations and hypotheses of
+/// [`two_sample_homoscedastic_ttest`], but σ<sub>1</sub> and σ<sub>2</sub>
+/// possibly different, the statistic
+///
+/// t = (m<sub>1</sub> - m<sub>2</sub> - ∆<sub>0</sub>) /
+/// (s<sup>2</sup><sub>1</sub>/n<sub>1</sub> +
+/// s<sup>2</sup><sub>2</sub>/n<sub>2</sub>)<sup>1/2</sup>
+///
+/// approximately follows under H<sub>0</sub> the central Student's
+/// t-distribution with the Welch-Satterthwaite degrees of freedom
+///
+/// ν = (w<sub>1</sub> + w<sub>2</sub>)<sup>2</sup> /
+/// (w<sub>1</sub><sup>2</sup>/(n<sub>1</sub> - 1) +
+/// w<sub>2</sub><sup>2</sup>/(n<sub>2</sub> - 1)) with w<sub>i</sub> =
+/// s<sup>2</sup><sub>i</sub>/n<sub>i</sub>,
+///
+/// where m<sub>i</sub> and s<sup>2</sup><sub>i</sub> are the sample means and
+/// unbiased sample variances. The output holds the estimate m<sub>1</sub> -
+/// m<sub>2</sub> of ∆, its standard error, ν and the confidence interval at
+/// `level`. Returns `None` when a sample has less than two observations,
+/// both variances are zero or `level` is not in (0, 1).
+pub fn two_sample_heteroscedastic_ttest<T: Float + FloatConst>(
+    delta0: T,
+    sample_means: (T, T),
+    sample_sizes: (T, T),
+    sample_vars: (T, T),
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>> {
+    let (m1, m2) = sample_means;
+    let (n1, n2) = sample_sizes;
+    let (v1, v2) = sample_vars;
+    let one = T::one();
+    let two = one + one;
+    if n1.is_nan() || n2.is_nan() || n1 < two || n2 < two || v1 < T::zero() || v2 < T::zero() {
+        return None;
+    }
+    let (w1, w2) = (v1 / n1, v2 / n2);
+    let var = w1 + w2;
+    if var.is_nan() || var <= T::zero() {
+        return None;
+    }
+    let (estimate, std_error) = (m1 - m2, var.sqrt());
+    let stat = (estimate - delta0) / std_error;
+    let df = var * var / (w1 * w1 / (n1 - one) + w2 * w2 / (n2 - one));
+    Some(TestOutput {
+        statistics: stat,
+        pvalue: pvalue_t(stat, df, test_type),
+        estimate: Some(estimate),
+        std_error: Some(std_error),
+        df: Some(df),
+        interval: Some(wald_interval(
+            estimate,
+            std_error,
+            Some(df),
+            test_type,
+            level,
+        )?),
+    })
+}
+
+/// [`two_sample_homoscedastic_ttest`] computing the means, the sizes and the
+/// unbiased variances of `sample1` and `sample2`, which can be slices,
+/// ndarray views or iterators of values. Returns `None` when a sample has
+/// less than two values or both have all their values equal.
+pub fn two_sample_homoscedastic_ttest_from_samples<T, I1, I2>(
+    delta0: T,
+    sample1: I1,
+    sample2: I2,
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst + Debug,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let (v1, m1, n1) = variance(sample1)?;
+    let (v2, m2, n2) = variance(sample2)?;
+    two_sample_homoscedastic_ttest(delta0, (m1, m2), (n1, n2), (v1, v2), test_type, level)
+}
+
+/// [`two_sample_heteroscedastic_ttest`] computing the means, the sizes and
+/// the unbiased variances of `sample1` and `sample2`, which can be slices,
+/// ndarray views or iterators of values. Returns `None` when a sample has
+/// less than two values or both have all their values equal.
+pub fn two_sample_heteroscedastic_ttest_from_samples<T, I1, I2>(
+    delta0: T,
+    sample1: I1,
+    sample2: I2,
+    test_type: TestTSide,
+    level: T,
+) -> Option<TestOutput<T, T>>
+where
+    T: Float + FloatConst,
+    I1: IntoIterator,
+    I1::Item: Borrow<T>,
+    I2: IntoIterator,
+    I2::Item: Borrow<T>,
+{
+    let (v1, m1, n1) = variance(sample1)?;
+    let (v2, m2, n2) = variance(sample2)?;
+    two_sample_heteroscedastic_ttest(delta0, (m1, m2), (n1, n2), (v1, v2), test_type, level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{earnings, rel};
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn two_sample_ttest_values() {
+        // Lalonde earnings in 1978 of the treated and the controls.
+        let means = (5976.3520236545139, 5090.0483012031106);
+        let sizes = (297., 425.);
+        let vars = (47938956.669181735, 32696539.096408253);
+        let welch =
+            |side| two_sample_heteroscedastic_ttest(0., means, sizes, vars, side, 0.95).unwrap();
+        let output = welch(TestTSide::TwoSided);
+        assert!(rel(output.statistics, 1.8154352748587828) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.069994590371177159) < 1e-12);
+        assert!(rel(output.std_error.unwrap(), 488.20452853674231) < 1e-12);
+        assert!(rel(output.df.unwrap(), 557.06172166123553) < 1e-12);
+        let (lower, upper) = output.interval.unwrap();
+        assert!(rel(lower, -72.643058186424328) < 1e-11);
+        assert!(rel(upper, 1845.2505030892308) < 1e-12);
+        let upper = welch(TestTSide::UpperOneSided).pvalue.unwrap();
+        assert!(rel(upper, 0.034997295185588579) < 1e-12);
+        let bound =
+            two_sample_heteroscedastic_ttest(0., means, sizes, vars, TestTSide::UpperOneSided, 0.9)
+                .unwrap()
+                .interval
+                .unwrap();
+        assert!(rel(bound.0, 259.90160546314012) < 1e-12);
+        assert_eq!(bound.1, f64::INFINITY);
+        let lower = welch(TestTSide::LowerOneSided).pvalue.unwrap();
+        assert!(rel(lower, 0.96500270481441142) < 1e-12);
+        let pooled =
+            |side| two_sample_homoscedastic_ttest(0., means, sizes, vars, side, 0.95).unwrap();
+        let output = pooled(TestTSide::TwoSided);
+        assert!(rel(output.statistics, 1.8774190271233509) < 1e-12);
+        assert!(rel(output.pvalue.unwrap(), 0.060864750062344094) < 1e-12);
+        assert!(rel(output.std_error.unwrap(), 472.08625759451780) < 1e-12);
+        assert_eq!(output.df, Some(720.));
+        let (lower, upper) = output.interval.unwrap();
+        assert!(rel(lower, -40.526353390459805) < 1e-11);
+        assert!(rel(upper, 1813.1337982932663) < 1e-12);
+        let upper = pooled(TestTSide::UpperOneSided).pvalue.unwrap();
+        assert!(rel(upper, 0.030432375031172047) < 1e-12);
+        let lower = pooled(TestTSide::LowerOneSided).pvalue.unwrap();
+        assert!(rel(lower, 0.96956762496882795) < 1e-12);
+        assert!(two_sample_heteroscedastic_ttest(
+            0.,
+            means,
+            (1., 425.),
+            vars,
+            TestTSide::TwoSided,
+            0.95
+        )
+        .is_none());
+        assert!(two_sample_heteroscedastic_ttest(
+            0.,
+            means,
+            sizes,
+            (0., 0.),
+            TestTSide::TwoSided,
+            0.95
+        )
+        .is_none());
+        // From the samples.
+        let side = TestTSide::TwoSided;
+        let output =
+            two_sample_heteroscedastic_ttest_from_samples(0., earnings(1), earnings(0), side, 0.95)
+                .unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.069994590371177159) < 1e-12);
+        let output =
+            two_sample_homoscedastic_ttest_from_samples(0., earnings(1), earnings(0), side, 0.95)
+                .unwrap();
+        assert!(rel(output.pvalue.unwrap(), 0.060864750062344094) < 1e-12);
+        let constant = [1., 1.];
+        assert!(
+            two_sample_homoscedastic_ttest_from_samples(0., constant, constant, side, 0.95)
+                .is_none()
+        );
+    }
+}